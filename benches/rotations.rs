@@ -0,0 +1,72 @@
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use iced_test::linear_algebra::Vector3;
+use iced_test::rotations::{
+    euler_angles::{EulerAngles, EulerSequence},
+    mrp::Mrp,
+    quaternion::Quaternion,
+    rotation_matrix::RotationMatrix,
+    Rotation, RotationTrait,
+};
+
+fn representations() -> Vec<(&'static str, Rotation)> {
+    let q = Quaternion::new(0.18119546436307749, 0.4381103371317225, 0.10015469662419728, 0.8747551502773175);
+    vec![
+        ("euler angles", Rotation::EulerAngles(EulerAngles::from_quaternion(q, EulerSequence::ZYX))),
+        ("mrp", Rotation::Mrp(Mrp::from(q))),
+        ("quaternion", Rotation::Quaternion(q)),
+        ("rotation matrix", Rotation::RotationMatrix(RotationMatrix::from(q))),
+    ]
+}
+
+fn bench_rotate(c: &mut Criterion) {
+    let v = Vector3::new(1.0, 2.0, 3.0);
+    let mut group = c.benchmark_group("rotate");
+    for (name, rotation) in representations() {
+        group.bench_function(name, |b| {
+            b.iter(|| {
+                black_box(black_box(rotation).rotate(black_box(v)));
+            })
+        });
+    }
+    group.finish();
+}
+
+fn bench_transform(c: &mut Criterion) {
+    let v = Vector3::new(1.0, 2.0, 3.0);
+    let mut group = c.benchmark_group("transform");
+    for (name, rotation) in representations() {
+        group.bench_function(name, |b| {
+            b.iter(|| {
+                black_box(black_box(rotation).transform(black_box(v)));
+            })
+        });
+    }
+    group.finish();
+}
+
+fn bench_compose(c: &mut Criterion) {
+    let mut group = c.benchmark_group("compose");
+    for (name, rotation) in representations() {
+        group.bench_function(name, |b| {
+            b.iter(|| {
+                black_box(black_box(rotation).compose(black_box(&rotation)));
+            })
+        });
+    }
+    group.finish();
+}
+
+fn bench_inv(c: &mut Criterion) {
+    let mut group = c.benchmark_group("inv");
+    for (name, rotation) in representations() {
+        group.bench_function(name, |b| {
+            b.iter(|| {
+                black_box(black_box(rotation).inv());
+            })
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_rotate, bench_transform, bench_compose, bench_inv);
+criterion_main!(benches);
@@ -1,15 +1,39 @@
 pub mod cartesian;
 pub mod cylindrical;
+pub mod enu;
+pub mod geodetic;
+pub mod ned;
 pub mod spherical;
 
+use serde::{Deserialize, Serialize};
 use cartesian::Cartesian;
 use cylindrical::Cylindrical;
+use enu::Enu;
+use geodetic::Geodetic;
+use ned::Ned;
 use spherical::Spherical;
 
-#[derive(Debug, Copy, Clone)]
+#[derive(Debug, Clone, Copy)]
+pub enum CoordinateSystemErrors {
+    /// Velocity conversions that are singular at the given position
+    AtOrigin,
+    AtPole,
+    OnAxis,
+    /// A local system has no place on the earth to convert from
+    NotPlanetary,
+}
+
+/// The planetary systems (`Ecef`, `Enu`, `Geodetic`, `Ned`) convert to cartesian
+/// coordinates in the earth-centered, earth-fixed frame. The local ones convert to
+/// their own frame, which isn't tied to the earth.
+#[derive(Debug, Copy, Clone, Serialize, Deserialize, PartialEq)]
 pub enum CoordinateSystem {
     Cartesian(Cartesian),
     Cylindrical(Cylindrical),
+    Ecef(Cartesian),
+    Enu(Enu),
+    Geodetic(Geodetic),
+    Ned(Ned),
     Spherical(Spherical),
 }
 
@@ -17,4 +41,207 @@ impl Default for CoordinateSystem {
     fn default() -> Self {
         Self::Cartesian(Cartesian::default())
     }
-}
\ No newline at end of file
+}
+
+impl CoordinateSystem {
+    pub fn to_cartesian(&self) -> Cartesian {
+        match self {
+            Self::Cartesian(cartesian) => *cartesian,
+            Self::Ecef(ecef) => *ecef,
+            Self::Cylindrical(cylindrical) => Cartesian::from(*cylindrical),
+            Self::Enu(enu) => Cartesian::from(*enu),
+            Self::Geodetic(geodetic) => Cartesian::from(*geodetic),
+            Self::Ned(ned) => Cartesian::from(*ned),
+            Self::Spherical(spherical) => Cartesian::from(*spherical),
+        }
+    }
+
+    pub fn to_cylindrical(&self) -> Cylindrical {
+        match self {
+            Self::Cylindrical(cylindrical) => *cylindrical,
+            Self::Spherical(spherical) => Cylindrical::from(*spherical),
+            _ => Cylindrical::from(self.to_cartesian()),
+        }
+    }
+
+    /// Converts a planetary system to earth-centered, earth-fixed coordinates
+    pub fn to_ecef(&self) -> Result<Cartesian, CoordinateSystemErrors> {
+        match self {
+            Self::Ecef(ecef) => Ok(*ecef),
+            Self::Enu(enu) => Ok(Cartesian::from(*enu)),
+            Self::Geodetic(geodetic) => Ok(Cartesian::from(*geodetic)),
+            Self::Ned(ned) => Ok(Cartesian::from(*ned)),
+            _ => Err(CoordinateSystemErrors::NotPlanetary),
+        }
+    }
+
+    /// Converts a planetary system to the local tangent plane at `origin`
+    pub fn to_enu(&self, origin: Geodetic) -> Result<Enu, CoordinateSystemErrors> {
+        Ok(Enu::from_ecef(self.to_ecef()?, origin))
+    }
+
+    /// Converts a planetary system to WGS-84 geodetic
+    pub fn to_geodetic(&self) -> Result<Geodetic, CoordinateSystemErrors> {
+        match self {
+            Self::Geodetic(geodetic) => Ok(*geodetic),
+            _ => Ok(Geodetic::from(self.to_ecef()?)),
+        }
+    }
+
+    /// Converts a planetary system to the local tangent plane at `origin`
+    pub fn to_ned(&self, origin: Geodetic) -> Result<Ned, CoordinateSystemErrors> {
+        Ok(Ned::from_ecef(self.to_ecef()?, origin))
+    }
+
+    pub fn to_spherical(&self) -> Spherical {
+        match self {
+            Self::Cylindrical(cylindrical) => Spherical::from(*cylindrical),
+            Self::Spherical(spherical) => *spherical,
+            _ => Spherical::from(self.to_cartesian()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::linear_algebra::Vector3;
+    use crate::test_utils::assert_vector_eq;
+
+    #[test]
+    fn test_coordinate_system_round_trips() {
+        let cartesian = Cartesian::new(1.0, -2.0, 3.0);
+        let p = Vector3::from(cartesian);
+
+        let via_cylindrical = CoordinateSystem::Cylindrical(Cylindrical::from(cartesian));
+        assert_vector_eq(via_cylindrical.to_cartesian().into(), p);
+
+        let via_spherical = CoordinateSystem::Spherical(Spherical::from(cartesian));
+        assert_vector_eq(via_spherical.to_cartesian().into(), p);
+
+        let spherical_via_cylindrical =
+            CoordinateSystem::Spherical(via_cylindrical.to_spherical());
+        assert_vector_eq(spherical_via_cylindrical.to_cartesian().into(), p);
+    }
+
+    #[test]
+    fn test_coordinate_system_geodetic() {
+        use std::f64::consts::FRAC_PI_2;
+
+        let equator = CoordinateSystem::Geodetic(Geodetic::new(0.0, 0.0, 0.0));
+        assert_vector_eq(
+            equator.to_cartesian().into(),
+            Vector3::new(geodetic::WGS84_A, 0.0, 0.0),
+        );
+
+        let north_pole = CoordinateSystem::Geodetic(Geodetic::new(FRAC_PI_2, 0.0, 100.0));
+        // semi-minor axis plus altitude
+        assert!((north_pole.to_cartesian().z - 6356852.314245179).abs() < 1e-6);
+
+        for geodetic in [
+            Geodetic::new(0.7, -1.9, 1500.0),
+            Geodetic::new(-0.3, 3.0, -50.0),
+            Geodetic::new(FRAC_PI_2, 0.0, 100.0),
+            Geodetic::new(0.1, 0.2, 400e3),
+        ] {
+            let ecef = CoordinateSystem::Ecef(Cartesian::from(geodetic));
+            let back = ecef.to_geodetic().unwrap();
+            assert!((back.latitude - geodetic.latitude).abs() < 1e-12);
+            assert!((back.altitude - geodetic.altitude).abs() < 1e-6);
+            if geodetic.latitude.abs() < FRAC_PI_2 {
+                assert!((back.longitude - geodetic.longitude).abs() < 1e-12);
+            }
+        }
+
+        // a local cartesian point isn't anywhere on the earth
+        let local = CoordinateSystem::Cartesian(Cartesian::from(Geodetic::new(0.7, -1.9, 0.0)));
+        assert!(matches!(
+            local.to_geodetic(),
+            Err(CoordinateSystemErrors::NotPlanetary)
+        ));
+    }
+
+    #[test]
+    fn test_coordinate_system_local_tangent_planes() {
+        let origin = Geodetic::new(0.6, 2.1, 250.0);
+
+        let up = CoordinateSystem::Enu(Enu::new(0.0, 0.0, 10.0, origin));
+        let above = up.to_geodetic().unwrap();
+        assert!((above.altitude - 260.0).abs() < 1e-6);
+        assert!((above.latitude - origin.latitude).abs() < 1e-12);
+
+        let ned = CoordinateSystem::Enu(Enu::new(1.0, 2.0, 3.0, origin))
+            .to_ned(origin)
+            .unwrap();
+        assert_vector_eq(
+            Vector3::new(ned.north, ned.east, ned.down),
+            Vector3::new(2.0, 1.0, -3.0),
+        );
+
+        let enu = CoordinateSystem::Ned(ned).to_enu(origin).unwrap();
+        assert_vector_eq(
+            Vector3::new(enu.east, enu.north, enu.up),
+            Vector3::new(1.0, 2.0, 3.0),
+        );
+    }
+
+    #[test]
+    fn test_coordinate_system_velocity_jacobians() {
+        let cylindrical = Cylindrical::new(2.0, 0.7, -1.0);
+        let cylindrical_rates = Cylindrical::new(0.3, -0.2, 1.5);
+        let velocity = cylindrical.velocity_to_cartesian(cylindrical_rates);
+        let back = cylindrical.velocity_from_cartesian(velocity).unwrap();
+        assert_vector_eq(
+            Vector3::new(back.radius, back.theta, back.height),
+            Vector3::new(0.3, -0.2, 1.5),
+        );
+
+        let spherical = Spherical::new(3.0, -1.1, 0.4);
+        let spherical_rates = Spherical::new(-0.5, 0.25, 0.1);
+        let velocity = spherical.velocity_to_cartesian(spherical_rates);
+        let back = spherical.velocity_from_cartesian(velocity).unwrap();
+        assert_vector_eq(
+            Vector3::new(back.radius, back.azimuth, back.elevation),
+            Vector3::new(-0.5, 0.25, 0.1),
+        );
+
+        // the same motion expressed through cylindrical rates
+        let via_cylindrical = Cylindrical::from(spherical)
+            .velocity_to_spherical(
+                spherical
+                    .velocity_to_cylindrical(spherical_rates)
+                    .unwrap(),
+            )
+            .unwrap();
+        assert_vector_eq(
+            Vector3::new(
+                via_cylindrical.radius,
+                via_cylindrical.azimuth,
+                via_cylindrical.elevation,
+            ),
+            Vector3::new(-0.5, 0.25, 0.1),
+        );
+    }
+
+    #[test]
+    fn test_coordinate_system_singularities() {
+        let velocity = Vector3::new(1.0, 0.0, 0.0);
+
+        let on_axis = Cylindrical::from(Cartesian::new(0.0, 0.0, 5.0));
+        assert_eq!(on_axis.theta, 0.0);
+        assert!(matches!(
+            on_axis.velocity_from_cartesian(velocity),
+            Err(CoordinateSystemErrors::OnAxis)
+        ));
+
+        let pole = Spherical::new(1.0, 0.0, std::f64::consts::FRAC_PI_2);
+        assert!(matches!(
+            pole.velocity_from_cartesian(velocity),
+            Err(CoordinateSystemErrors::AtPole)
+        ));
+        assert!(matches!(
+            Spherical::default().velocity_from_cartesian(velocity),
+            Err(CoordinateSystemErrors::AtOrigin)
+        ));
+    }
+}
@@ -1,18 +1,51 @@
 use crate::linear_algebra::Vector3;
+use serde::{Deserialize, Serialize};
+use super::{cylindrical::Cylindrical, spherical::Spherical};
 
-#[derive(Debug, Copy, Clone)]
+#[derive(Debug, Default, Copy, Clone, Serialize, Deserialize, PartialEq)]
 pub struct Cartesian {
-    value: Vector3
+    pub x: f64,
+    pub y: f64,
+    pub z: f64,
 }
 
 impl Cartesian {
     pub fn new(x:f64, y:f64, z:f64) -> Self {
-        Self {value: Vector3::new(x,y,z)}
+        Self {x, y, z}
     }
 }
 
-impl Default for Cartesian {
-    fn default() -> Self {
-        Self { value : Vector3::new(0.0,0.0,0.0)}
+impl From<Vector3> for Cartesian {
+    fn from(value: Vector3) -> Self {
+        Self::new(value.e1, value.e2, value.e3)
     }
-}
\ No newline at end of file
+}
+
+impl From<Cartesian> for Vector3 {
+    fn from(cartesian: Cartesian) -> Self {
+        Vector3::new(cartesian.x, cartesian.y, cartesian.z)
+    }
+}
+
+impl From<Cylindrical> for Cartesian {
+    fn from(cylindrical: Cylindrical) -> Self {
+        let (sin, cos) = cylindrical.theta.sin_cos();
+        Self::new(
+            cylindrical.radius * cos,
+            cylindrical.radius * sin,
+            cylindrical.height,
+        )
+    }
+}
+
+impl From<Spherical> for Cartesian {
+    fn from(spherical: Spherical) -> Self {
+        let (sin_azimuth, cos_azimuth) = spherical.azimuth.sin_cos();
+        let (sin_elevation, cos_elevation) = spherical.elevation.sin_cos();
+        Self::new(
+            spherical.radius * cos_elevation * cos_azimuth,
+            spherical.radius * cos_elevation * sin_azimuth,
+            spherical.radius * sin_elevation,
+        )
+    }
+}
@@ -1,12 +1,78 @@
-#[derive(Debug, Default, Copy, Clone)]
+use super::{cartesian::Cartesian, spherical::Spherical, CoordinateSystemErrors};
+use crate::linear_algebra::Vector3;
+use serde::{Deserialize, Serialize};
+
+/// x = radius * cos(theta), y = radius * sin(theta), z = height
+#[derive(Debug, Default, Copy, Clone, Serialize, Deserialize, PartialEq)]
 pub struct Cylindrical {
-    radius: f64,
-    theta: f64,
-    height: f64,
+    pub radius: f64,
+    pub theta: f64,
+    pub height: f64,
 }
 
 impl Cylindrical {
     pub fn new(radius:f64, theta:f64, height:f64) -> Self {
         Self {radius,theta,height}
     }
-}
\ No newline at end of file
+
+    /// Converts the rates (radius_dot, theta_dot, height_dot) of a point at `self`
+    /// to a cartesian velocity. The jacobian is defined everywhere.
+    pub fn velocity_to_cartesian(&self, rates: Cylindrical) -> Vector3 {
+        let (sin, cos) = self.theta.sin_cos();
+        Vector3::new(
+            rates.radius * cos - self.radius * sin * rates.theta,
+            rates.radius * sin + self.radius * cos * rates.theta,
+            rates.height,
+        )
+    }
+
+    /// Converts a cartesian velocity of a point at `self` to cylindrical rates.
+    ///
+    /// # Errors
+    ///
+    /// Returns `CoordinateSystemErrors::OnAxis` on the z axis, where theta_dot is undefined.
+    pub fn velocity_from_cartesian(
+        &self,
+        velocity: Vector3,
+    ) -> Result<Cylindrical, CoordinateSystemErrors> {
+        if self.radius.abs() < f64::EPSILON {
+            return Err(CoordinateSystemErrors::OnAxis);
+        }
+        let (sin, cos) = self.theta.sin_cos();
+        Ok(Cylindrical::new(
+            velocity.e1 * cos + velocity.e2 * sin,
+            (velocity.e2 * cos - velocity.e1 * sin) / self.radius,
+            velocity.e3,
+        ))
+    }
+
+    /// Converts the cylindrical rates of a point at `self` to spherical rates
+    pub fn velocity_to_spherical(
+        &self,
+        rates: Cylindrical,
+    ) -> Result<Spherical, CoordinateSystemErrors> {
+        Spherical::from(*self).velocity_from_cartesian(self.velocity_to_cartesian(rates))
+    }
+}
+
+impl From<Cartesian> for Cylindrical {
+    /// Theta is set to 0 on the z axis, where it is undefined
+    fn from(cartesian: Cartesian) -> Self {
+        Self::new(
+            cartesian.x.hypot(cartesian.y),
+            cartesian.y.atan2(cartesian.x),
+            cartesian.z,
+        )
+    }
+}
+
+impl From<Spherical> for Cylindrical {
+    fn from(spherical: Spherical) -> Self {
+        let (sin, cos) = spherical.elevation.sin_cos();
+        Self::new(
+            spherical.radius * cos,
+            spherical.azimuth,
+            spherical.radius * sin,
+        )
+    }
+}
@@ -0,0 +1,69 @@
+use super::{cartesian::Cartesian, geodetic::Geodetic, ned::Ned};
+use crate::linear_algebra::Vector3;
+use serde::{Deserialize, Serialize};
+
+/// East, north and up (m) in the local tangent plane at `origin`
+#[derive(Debug, Default, Copy, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Enu {
+    pub east: f64,
+    pub north: f64,
+    pub up: f64,
+    pub origin: Geodetic,
+}
+
+impl Enu {
+    pub fn new(east: f64, north: f64, up: f64, origin: Geodetic) -> Self {
+        Self {
+            east,
+            north,
+            up,
+            origin,
+        }
+    }
+
+    /// Expresses an ECEF position in the local tangent plane at `origin`
+    pub fn from_ecef(ecef: Cartesian, origin: Geodetic) -> Self {
+        let (east_axis, north_axis, up_axis) = local_axes(&origin);
+        let delta = Vector3::from(ecef) - Vector3::from(Cartesian::from(origin));
+        Self::new(
+            east_axis.dot(&delta),
+            north_axis.dot(&delta),
+            up_axis.dot(&delta),
+            origin,
+        )
+    }
+}
+
+/// Returns the ECEF unit vectors of the east, north and up axes at `origin`
+pub fn local_axes(origin: &Geodetic) -> (Vector3, Vector3, Vector3) {
+    let (sin_latitude, cos_latitude) = origin.latitude.sin_cos();
+    let (sin_longitude, cos_longitude) = origin.longitude.sin_cos();
+    (
+        Vector3::new(-sin_longitude, cos_longitude, 0.0),
+        Vector3::new(
+            -sin_latitude * cos_longitude,
+            -sin_latitude * sin_longitude,
+            cos_latitude,
+        ),
+        Vector3::new(
+            cos_latitude * cos_longitude,
+            cos_latitude * sin_longitude,
+            sin_latitude,
+        ),
+    )
+}
+
+impl From<Enu> for Cartesian {
+    /// Converts to ECEF
+    fn from(enu: Enu) -> Self {
+        let (east_axis, north_axis, up_axis) = local_axes(&enu.origin);
+        let origin = Vector3::from(Cartesian::from(enu.origin));
+        Cartesian::from(origin + east_axis * enu.east + north_axis * enu.north + up_axis * enu.up)
+    }
+}
+
+impl From<Ned> for Enu {
+    fn from(ned: Ned) -> Self {
+        Self::new(ned.east, ned.north, -ned.down, ned.origin)
+    }
+}
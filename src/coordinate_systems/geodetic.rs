@@ -0,0 +1,70 @@
+use super::cartesian::Cartesian;
+use serde::{Deserialize, Serialize};
+
+/// WGS-84 semi-major axis (m)
+pub const WGS84_A: f64 = 6378137.0;
+/// WGS-84 flattening
+pub const WGS84_F: f64 = 1.0 / 298.257223563;
+/// WGS-84 first eccentricity squared
+pub const WGS84_E2: f64 = WGS84_F * (2.0 - WGS84_F);
+
+/// Geodetic latitude and longitude (rad) and altitude above the WGS-84 ellipsoid (m)
+#[derive(Debug, Default, Copy, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Geodetic {
+    pub latitude: f64,
+    pub longitude: f64,
+    pub altitude: f64,
+}
+
+impl Geodetic {
+    pub fn new(latitude: f64, longitude: f64, altitude: f64) -> Self {
+        Self {
+            latitude,
+            longitude,
+            altitude,
+        }
+    }
+}
+
+impl From<Geodetic> for Cartesian {
+    /// Converts to ECEF
+    fn from(geodetic: Geodetic) -> Self {
+        let (sin_latitude, cos_latitude) = geodetic.latitude.sin_cos();
+        let (sin_longitude, cos_longitude) = geodetic.longitude.sin_cos();
+        // prime vertical radius of curvature
+        let n = WGS84_A / (1.0 - WGS84_E2 * sin_latitude * sin_latitude).sqrt();
+
+        Cartesian::new(
+            (n + geodetic.altitude) * cos_latitude * cos_longitude,
+            (n + geodetic.altitude) * cos_latitude * sin_longitude,
+            (n * (1.0 - WGS84_E2) + geodetic.altitude) * sin_latitude,
+        )
+    }
+}
+
+impl From<Cartesian> for Geodetic {
+    /// Converts from ECEF with Vermeille's closed form solution, which is exact
+    /// everywhere outside of a ~45 km sphere at the center of the earth
+    fn from(ecef: Cartesian) -> Self {
+        let e4 = WGS84_E2 * WGS84_E2;
+        let planar_squared = ecef.x * ecef.x + ecef.y * ecef.y;
+
+        let p = planar_squared / (WGS84_A * WGS84_A);
+        let q = (1.0 - WGS84_E2) * ecef.z * ecef.z / (WGS84_A * WGS84_A);
+        let r = (p + q - e4) / 6.0;
+        let s = e4 * p * q / (4.0 * r * r * r);
+        let t = (1.0 + s + (s * (2.0 + s)).sqrt()).cbrt();
+        let u = r * (1.0 + t + 1.0 / t);
+        let v = (u * u + e4 * q).sqrt();
+        let w = WGS84_E2 * (u + v - q) / (2.0 * v);
+        let k = (u + v + w * w).sqrt() - w;
+        let d = k * planar_squared.sqrt() / (k + WGS84_E2);
+        let d_z = d.hypot(ecef.z);
+
+        Self::new(
+            2.0 * ecef.z.atan2(d + d_z),
+            ecef.y.atan2(ecef.x),
+            (k + WGS84_E2 - 1.0) / k * d_z,
+        )
+    }
+}
@@ -0,0 +1,40 @@
+use super::{cartesian::Cartesian, enu::Enu, geodetic::Geodetic};
+use serde::{Deserialize, Serialize};
+
+/// North, east and down (m) in the local tangent plane at `origin`
+#[derive(Debug, Default, Copy, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Ned {
+    pub north: f64,
+    pub east: f64,
+    pub down: f64,
+    pub origin: Geodetic,
+}
+
+impl Ned {
+    pub fn new(north: f64, east: f64, down: f64, origin: Geodetic) -> Self {
+        Self {
+            north,
+            east,
+            down,
+            origin,
+        }
+    }
+
+    /// Expresses an ECEF position in the local tangent plane at `origin`
+    pub fn from_ecef(ecef: Cartesian, origin: Geodetic) -> Self {
+        Self::from(Enu::from_ecef(ecef, origin))
+    }
+}
+
+impl From<Ned> for Cartesian {
+    /// Converts to ECEF
+    fn from(ned: Ned) -> Self {
+        Cartesian::from(Enu::from(ned))
+    }
+}
+
+impl From<Enu> for Ned {
+    fn from(enu: Enu) -> Self {
+        Self::new(enu.north, enu.east, -enu.up, enu.origin)
+    }
+}
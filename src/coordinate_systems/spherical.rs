@@ -1,12 +1,94 @@
-#[derive(Debug, Default, Copy, Clone)]
+use super::{cartesian::Cartesian, cylindrical::Cylindrical, CoordinateSystemErrors};
+use crate::linear_algebra::Vector3;
+use serde::{Deserialize, Serialize};
+
+/// Elevation is measured from the xy plane, so the poles are at +/- pi/2
+#[derive(Debug, Default, Copy, Clone, Serialize, Deserialize, PartialEq)]
 pub struct Spherical {
-    radius: f64,
-    azimuth: f64,
-    elevation: f64,
+    pub radius: f64,
+    pub azimuth: f64,
+    pub elevation: f64,
 }
 
 impl Spherical {
     pub fn new(radius:f64, azimuth:f64, elevation:f64) -> Self {
         Self {radius,azimuth,elevation}
     }
-}
\ No newline at end of file
+
+    /// Converts the rates (radius_dot, azimuth_dot, elevation_dot) of a point at `self`
+    /// to a cartesian velocity. The jacobian is defined everywhere.
+    pub fn velocity_to_cartesian(&self, rates: Spherical) -> Vector3 {
+        let (sin_azimuth, cos_azimuth) = self.azimuth.sin_cos();
+        let (sin_elevation, cos_elevation) = self.elevation.sin_cos();
+        let r = self.radius;
+        Vector3::new(
+            rates.radius * cos_elevation * cos_azimuth
+                - r * sin_elevation * cos_azimuth * rates.elevation
+                - r * cos_elevation * sin_azimuth * rates.azimuth,
+            rates.radius * cos_elevation * sin_azimuth
+                - r * sin_elevation * sin_azimuth * rates.elevation
+                + r * cos_elevation * cos_azimuth * rates.azimuth,
+            rates.radius * sin_elevation + r * cos_elevation * rates.elevation,
+        )
+    }
+
+    /// Converts a cartesian velocity of a point at `self` to spherical rates.
+    ///
+    /// # Errors
+    ///
+    /// Returns `CoordinateSystemErrors::AtOrigin` at the origin, where no angle rate is defined,
+    /// and `CoordinateSystemErrors::AtPole` at the poles, where azimuth_dot is undefined.
+    pub fn velocity_from_cartesian(
+        &self,
+        velocity: Vector3,
+    ) -> Result<Spherical, CoordinateSystemErrors> {
+        if self.radius.abs() < f64::EPSILON {
+            return Err(CoordinateSystemErrors::AtOrigin);
+        }
+        let (sin_elevation, cos_elevation) = self.elevation.sin_cos();
+        if cos_elevation.abs() < f64::EPSILON {
+            return Err(CoordinateSystemErrors::AtPole);
+        }
+        let (sin_azimuth, cos_azimuth) = self.azimuth.sin_cos();
+
+        // project onto the local radial, azimuth and elevation unit vectors
+        let planar = velocity.e1 * cos_azimuth + velocity.e2 * sin_azimuth;
+        let azimuthal = velocity.e2 * cos_azimuth - velocity.e1 * sin_azimuth;
+        Ok(Spherical::new(
+            planar * cos_elevation + velocity.e3 * sin_elevation,
+            azimuthal / (self.radius * cos_elevation),
+            (velocity.e3 * cos_elevation - planar * sin_elevation) / self.radius,
+        ))
+    }
+
+    /// Converts the spherical rates of a point at `self` to cylindrical rates
+    pub fn velocity_to_cylindrical(
+        &self,
+        rates: Spherical,
+    ) -> Result<Cylindrical, CoordinateSystemErrors> {
+        Cylindrical::from(*self).velocity_from_cartesian(self.velocity_to_cartesian(rates))
+    }
+}
+
+impl From<Cartesian> for Spherical {
+    /// Azimuth is set to 0 at the poles and both angles are set to 0 at the origin,
+    /// where they are undefined
+    fn from(cartesian: Cartesian) -> Self {
+        let planar = cartesian.x.hypot(cartesian.y);
+        Self::new(
+            planar.hypot(cartesian.z),
+            cartesian.y.atan2(cartesian.x),
+            cartesian.z.atan2(planar),
+        )
+    }
+}
+
+impl From<Cylindrical> for Spherical {
+    fn from(cylindrical: Cylindrical) -> Self {
+        Self::new(
+            cylindrical.radius.hypot(cylindrical.height),
+            cylindrical.theta,
+            cylindrical.height.atan2(cylindrical.radius),
+        )
+    }
+}
@@ -0,0 +1,7 @@
+pub mod coordinate_systems;
+pub mod linear_algebra;
+pub mod rotations;
+pub mod transforms;
+
+#[cfg(test)]
+mod test_utils;
@@ -1,6 +1,6 @@
-use std::ops::Mul;
+use std::ops::{Add, Mul, Neg, Sub};
 
-#[derive(Debug, Copy, Clone)]
+#[derive(Debug, Default, Copy, Clone)]
 pub struct Vector3 {
     pub e1: f64,
     pub e2: f64,
@@ -12,6 +12,10 @@ impl Vector3 {
         Self { e1, e2, e3 }
     }
 
+    pub fn dot(&self, rhs: &Self) -> f64 {
+        self.e1 * rhs.e1 + self.e2 * rhs.e2 + self.e3 * rhs.e3
+    }
+
     pub fn norm(&self) -> f64 {
         (self.e1 * self.e1 + self.e2 * self.e2 + self.e3 * self.e3).sqrt()
     }
@@ -45,6 +49,34 @@ impl Vector3 {
     }
 }
 
+impl Add<Vector3> for Vector3 {
+    type Output = Self;
+    fn add(self, rhs: Self) -> Self {
+        Self::new(self.e1 + rhs.e1, self.e2 + rhs.e2, self.e3 + rhs.e3)
+    }
+}
+
+impl Neg for Vector3 {
+    type Output = Self;
+    fn neg(self) -> Self {
+        Self::new(-self.e1, -self.e2, -self.e3)
+    }
+}
+
+impl Sub<Vector3> for Vector3 {
+    type Output = Self;
+    fn sub(self, rhs: Self) -> Self {
+        Self::new(self.e1 - rhs.e1, self.e2 - rhs.e2, self.e3 - rhs.e3)
+    }
+}
+
+impl Mul<f64> for Vector3 {
+    type Output = Self;
+    fn mul(self, rhs: f64) -> Self {
+        Self::new(self.e1 * rhs, self.e2 * rhs, self.e3 * rhs)
+    }
+}
+
 #[derive(Debug, Copy, Clone)]
 pub struct Matrix3 {
     e11: f64,
@@ -58,6 +90,7 @@ pub struct Matrix3 {
     e33: f64,
 }
 impl Matrix3 {
+    #[allow(clippy::too_many_arguments)]
     fn new(
         e11: f64,
         e21: f64,
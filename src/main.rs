@@ -3,30 +3,59 @@
 
 use iced::{
     alignment, font, keyboard,
-    mouse::Cursor,
+    mouse::{Cursor, ScrollDelta},
     widget::{
         button,
         canvas::{Cache, Canvas},
-        container, text, text_input, Column, Row,
+        container, pick_list, scrollable, text, text_input, tooltip, Column, Row, TextInput,
     },
-    window, Application, Command, Element, Length, Settings, Size, Subscription,
+    advanced::widget,
+    window, Application, Command, Element, Length, Settings, Size, Subscription, Vector,
 };
 
-use iced_aw::{card, modal};
+use iced_aw::{card, floating_element, floating_element::Anchor, modal, split::Axis, Split};
+use std::collections::HashMap;
+use std::path::PathBuf;
 use std::time::{Duration, Instant};
+use uuid::Uuid;
 
 mod multibody;
+mod project;
 mod ui;
 
-use crate::multibody::{
-    body::BodyField, joints::revolute::RevoluteField, MultibodyComponent, MultibodyTrait,
+use crate::multibody::MultibodyTrait;
+use crate::project::Project;
+use crate::ui::canvas::graph::{
+    context_menu::{ContextAction, ContextMenu, ContextTarget},
+    layout::LayoutOrientation,
+    Graph, GraphMessage,
 };
-use crate::ui::canvas::graph::{Graph, GraphMessage};
+use crate::ui::canvas::grid::GridSettings;
 use crate::ui::canvas::nodebar::{Nodebar, NodebarMessage};
 use crate::ui::canvas::GraphCanvas;
-use crate::ui::dummies::{DummyBase, DummyBody, DummyComponent, DummyRevolute, DummyTrait};
+use crate::ui::dummies::{DummyComponent, DummyTrait};
 use crate::ui::errors::Errors;
+use crate::ui::inspector::{Inspector, MIN_INSPECTOR_WIDTH};
 use crate::ui::modals::ActiveModal;
+use crate::ui::parameters::{Parameter, Parameters};
+use crate::ui::schema::FieldContext;
+use crate::ui::theme::ThemeChoice;
+use crate::ui::toasts::{Toasts, TOAST_TICK};
+use crate::ui::units::UnitSystem;
+
+const MENU_BAR_HEIGHT: f32 = 40.0;
+const PROBLEMS_PANEL_WIDTH: f32 = 300.0;
+const PARAMETERS_PANEL_WIDTH: f32 = 300.0;
+const TOAST_WIDTH: f32 = 350.0;
+const TOOLTIP_WIDTH: f32 = 300.0;
+const CONTEXT_MENU_WIDTH: f32 = 180.0;
+const CONTEXT_MENU_ITEM_HEIGHT: f32 = 30.0;
+/// So the context menu can focus the name field
+const NAME_INPUT_ID: &str = "name";
+/// Width of the draggable divider between the graph and the inspector
+const INSPECTOR_DIVIDER_WIDTH: f32 = 5.0;
+/// Screen pixels moved per arrow key press
+const PAN_STEP: f32 = 50.0;
 
 fn main() -> iced::Result {
     let mut settings = Settings::default();
@@ -37,58 +66,129 @@ fn main() -> iced::Result {
 // Define the possible user interactions
 #[derive(Debug, Clone)]
 enum Message {
-    BodyNameInputChanged(String),
-    BodyMassInputChanged(String),
-    BodyCmxInputChanged(String),
-    BodyCmyInputChanged(String),
-    BodyCmzInputChanged(String),
-    BodyIxxInputChanged(String),
-    BodyIyyInputChanged(String),
-    BodyIzzInputChanged(String),
-    BodyIxyInputChanged(String),
-    BodyIxzInputChanged(String),
-    BodyIyzInputChanged(String),
-    RevoluteConstantForceInputChanged(String),
-    RevoluteDampeningInputChanged(String),
-    RevoluteOmegaInputChanged(String),
-    RevoluteNameInputChanged(String),
-    RevoluteSpringConstantInputChanged(String),
-    RevoluteThetaInputChanged(String),
+    /// Index into the edited component's schema
+    FieldInputChanged(usize, String),
+    NameInputChanged(String),
     LeftButtonPressed(Cursor),
     LeftButtonReleased(Cursor),
     MiddleButtonPressed(Cursor),
     RightButtonPressed(Cursor),
     RightButtonReleased(Cursor),
     CursorMoved(Cursor),
+    ModifiersChanged(keyboard::Modifiers),
+    WheelScrolled(Cursor, ScrollDelta),
+    AnimationTick(Instant),
+    AutoLayoutPressed(LayoutOrientation),
     CloseError,
     CloseModal,
+    ContextActionSelected(ContextAction),
+    ContextMenuClosed,
+    CopyPressed,
+    /// Asks which text input has focus, answered by `FocusChanged`
+    CheckFocus,
+    CutPressed,
     DeletePressed,
+    DuplicatePressed,
     EnterPressed,
+    FitAllPressed,
+    FocusChanged(Option<widget::Id>),
+    GridSpacingChanged(String),
+    GridToggled,
+    InspectorResized(u16),
+    InspectorToggled,
+    PanPressed(Vector),
+    ParameterAdded,
+    /// Index into the parameters table
+    ParameterExpressionChanged(usize, String),
+    ParameterNameChanged(usize, String),
+    ParameterRemoved(usize),
+    ParametersToggled,
     TabPressed,
+    ThemeSelected(ThemeChoice),
+    ToastClosed(usize),
+    ToastTick(Instant),
+    UndoPressed,
+    UnitSystemSelected(UnitSystem),
     FontLoaded(Result<(), font::Error>),
     Loaded(Result<(), String>),
+    OpenProject,
+    PastePressed,
+    ProblemSelected(Uuid),
+    ProblemsToggled,
+    RedoPressed,
+    OpenProjectFileSelected(Option<PathBuf>),
     SaveComponent,
+    SaveProject,
+    SaveProjectAs,
+    SnapToggled,
+    SaveProjectFileSelected(Option<PathBuf>),
     WindowResized(Size),
 }
 
+impl Message {
+    /// Messages that can't change the selection or the components, so the inspector doesn't follow them
+    fn is_view_only(&self) -> bool {
+        matches!(
+            self,
+            Message::AnimationTick(_)
+                | Message::CheckFocus
+                | Message::CursorMoved(_)
+                | Message::FieldInputChanged(_, _)
+                | Message::FitAllPressed
+                | Message::FocusChanged(_)
+                | Message::FontLoaded(_)
+                | Message::GridSpacingChanged(_)
+                | Message::GridToggled
+                | Message::InspectorResized(_)
+                | Message::ModifiersChanged(_)
+                | Message::NameInputChanged(_)
+                | Message::PanPressed(_)
+                | Message::ParametersToggled
+                | Message::ProblemsToggled
+                | Message::SnapToggled
+                | Message::ThemeSelected(_)
+                | Message::ToastClosed(_)
+                | Message::ToastTick(_)
+                | Message::WheelScrolled(_, _)
+                | Message::WindowResized(_)
+        )
+    }
+}
+
 #[derive(Debug)]
 enum IcedTest {
     Loading,
-    Loaded(AppState),
+    Loaded(Box<AppState>),
 }
 
 #[derive(Debug)]
 struct AppState {
     active_error: Option<Errors>,
     cache: Cache,
-    counter_body: usize,
-    counter_revolute: usize,
+    /// Open until an action is picked or the canvas is clicked
+    context_menu: Option<ContextMenu>,
+    /// The text input that had focus at the last check, the inspector is applied when it changes
+    focused_input: Option<widget::Id>,
     graph: Graph,
+    grid_spacing_input: String,
+    inspector: Inspector,
+    is_parameters_open: bool,
+    is_problems_open: bool,
     left_clicked_time_1: Option<Instant>,
     left_clicked_time_2: Option<Instant>,
     modal: Option<ActiveModal>,
+    modifiers: keyboard::Modifiers,
+    /// The last number given to an unnamed component, by name prefix
+    name_counters: HashMap<String, usize>,
     nodebar: Nodebar,
+    project_path: Option<PathBuf>,
     theme: crate::ui::theme::Theme,
+    theme_choice: ThemeChoice,
+    /// The built-in palettes followed by any user themes
+    theme_choices: Vec<ThemeChoice>,
+    /// Failures that don't need the error card
+    toasts: Toasts,
+    window_size: Size,
 }
 
 impl Default for AppState {
@@ -96,14 +196,25 @@ impl Default for AppState {
         Self {
             active_error: None,
             cache: Cache::new(),
-            counter_body: 0,
-            counter_revolute: 0,
+            context_menu: None,
+            focused_input: None,
             left_clicked_time_1: None,
             left_clicked_time_2: None,
             graph: Graph::default(),
+            grid_spacing_input: GridSettings::default().spacing.to_string(),
+            inspector: Inspector::default(),
+            is_parameters_open: false,
+            is_problems_open: false,
             modal: None,
+            modifiers: keyboard::Modifiers::default(),
+            name_counters: HashMap::new(),
             nodebar: Nodebar::default(),
-            theme: crate::ui::theme::Theme::ORANGE,
+            project_path: None,
+            theme: ThemeChoice::default().get_theme(),
+            theme_choice: ThemeChoice::default(),
+            theme_choices: ThemeChoice::ALL.to_vec(),
+            toasts: Toasts::default(),
+            window_size: Settings::<()>::default().window.size,
         }
     }
 }
@@ -123,6 +234,114 @@ enum MouseButtonReleaseEvents {
 }
 
 impl AppState {
+    pub fn animation_tick(&mut self, now: Instant) -> Command<Message> {
+        if self.graph.animation_tick(now) {
+            self.cache.clear();
+        }
+        Command::none()
+    }
+
+    /// Applies the inspector's edits through `edit_component` as one undo step.
+    /// An emptied name keeps the old one.
+    fn apply_inspector(&mut self) {
+        if !self.inspector.is_dirty {
+            return;
+        }
+        // invalid text stays in the inspector, marked, until it's fixed
+        let context = self.graph.get_field_context();
+        if self
+            .inspector
+            .dummy
+            .as_ref()
+            .is_some_and(|dummy| dummy.has_invalid_fields(&context))
+        {
+            return;
+        }
+        let component_id = match self.inspector.get_component_id() {
+            Some(id) => id,
+            None => return,
+        };
+        if let Some(dummy) = &mut self.inspector.dummy {
+            if dummy.get_name().trim().is_empty() {
+                if let Some(component) = self.graph.components.get(&component_id) {
+                    dummy.set_name(component.get_name());
+                }
+            }
+            // a rejected edit stays in the inspector so it can be fixed
+            match self.graph.edit_component(dummy, component_id) {
+                Ok(_) => self.inspector.is_dirty = false,
+                Err(error) => self
+                    .toasts
+                    .push(format!("{}: {}", dummy.get_name(), error), Instant::now()),
+            }
+        }
+        self.inspector.refresh(&self.graph);
+        self.cache.clear();
+    }
+
+    pub fn auto_layout_pressed(&mut self, orientation: LayoutOrientation) -> Command<Message> {
+        self.graph.auto_layout(orientation);
+        Command::none()
+    }
+
+    /// Asks which widget has focus, the answer comes back as `Message::FocusChanged`
+    pub fn check_focus(&mut self) -> Command<Message> {
+        Command::widget(ui::inspector::find_focused(Message::FocusChanged))
+    }
+
+    /// The menu closes after any action, which applies to the target the menu was opened on
+    pub fn context_action_selected(&mut self, action: ContextAction) -> Command<Message> {
+        let menu = match self.context_menu.take() {
+            Some(menu) => menu,
+            None => return Command::none(),
+        };
+        if menu.target != ContextTarget::Canvas {
+            self.graph.select(menu.target);
+        }
+        let component_id = match menu.target {
+            ContextTarget::Node(node_id) => self.graph.nodes.get(&node_id).map(|graphnode| graphnode.component_id),
+            _ => None,
+        };
+
+        let mut command = Command::none();
+        match (action, menu.target) {
+            (ContextAction::AddComponent(dummy_id), _) => {
+                self.graph.set_cursor_position(menu.position);
+                self.open_new_component(dummy_id);
+            }
+            (ContextAction::AutoLayout(orientation), _) => self.graph.auto_layout(orientation),
+            (ContextAction::Delete, _) => self.graph.delete_pressed(),
+            (ContextAction::DisconnectAll, ContextTarget::Node(node_id)) => self.graph.disconnect_node(node_id),
+            (ContextAction::Duplicate, _) => self.graph.duplicate_pressed(),
+            (ContextAction::Edit, _) => self.open_inspector(),
+            (ContextAction::FitView, _) => {
+                self.graph.fit_all();
+            }
+            (ContextAction::Paste, _) => {
+                self.graph.set_cursor_position(menu.position);
+                self.graph.paste_pressed();
+            }
+            (ContextAction::Rename, _) => {
+                self.open_inspector();
+                command = text_input::focus(text_input::Id::new(NAME_INPUT_ID));
+            }
+            (ContextAction::Reverse, ContextTarget::Edge(edge_id)) => self.graph.reverse_edge(edge_id),
+            (ContextAction::ToggleOutput, _) => {
+                if let Some(component_id) = component_id {
+                    self.graph.toggle_output(component_id);
+                }
+            }
+            _ => {}
+        }
+        self.cache.clear();
+        command
+    }
+
+    pub fn context_menu_closed(&mut self) -> Command<Message> {
+        self.context_menu = None;
+        Command::none()
+    }
+
     pub fn close_error(&mut self) -> Command<Message> {
         self.active_error = None;
         Command::none()
@@ -133,6 +352,11 @@ impl AppState {
         Command::none()
     }
 
+    pub fn copy_pressed(&mut self) -> Command<Message> {
+        self.graph.copy_pressed();
+        Command::none()
+    }
+
     pub fn cursor_moved(&mut self, cursor: Cursor) -> Command<Message> {
         let nodebar_redraw = self.nodebar.cursor_moved(cursor);
         let graph_redraw = self.graph.cursor_moved(cursor);
@@ -144,6 +368,12 @@ impl AppState {
         Command::none()
     }
 
+    pub fn cut_pressed(&mut self) -> Command<Message> {
+        self.graph.cut_pressed();
+        self.cache.clear();
+        Command::none()
+    }
+
     pub fn delete_pressed(&mut self) -> Command<Message> {
         self.graph.delete_pressed();
         //self.nodebar.delete_pressed(); // no need for this, maybe ever?
@@ -151,6 +381,12 @@ impl AppState {
         Command::none()
     }
 
+    pub fn duplicate_pressed(&mut self) -> Command<Message> {
+        self.graph.duplicate_pressed();
+        self.cache.clear();
+        Command::none()
+    }
+
     pub fn enter_pressed(&mut self) -> Command<Message> {
         // if the error modal is currently open, close it
         if self.active_error.is_some() {
@@ -160,12 +396,50 @@ impl AppState {
         self.save_component()
     }
 
+    pub fn fit_all_pressed(&mut self) -> Command<Message> {
+        if self.graph.fit_all() {
+            self.cache.clear();
+        }
+        Command::none()
+    }
+
+    /// A field losing focus applies the inspector, like pressing Enter would
+    pub fn focus_changed(&mut self, focused: Option<widget::Id>) -> Command<Message> {
+        if focused != self.focused_input {
+            self.apply_inspector();
+            self.focused_input = focused;
+        }
+        Command::none()
+    }
+
+    /// Keeps the text as typed, the spacing only changes once it's a positive number
+    pub fn grid_spacing_changed(&mut self, value: String) -> Command<Message> {
+        if let Ok(spacing) = value.parse::<f32>() {
+            if spacing > 0.0 && spacing.is_finite() {
+                self.graph.grid.spacing = spacing;
+                self.cache.clear();
+            }
+        }
+        self.grid_spacing_input = value;
+        Command::none()
+    }
+
+    pub fn grid_toggled(&mut self) -> Command<Message> {
+        self.graph.grid.is_visible = !self.graph.grid.is_visible;
+        self.cache.clear();
+        Command::none()
+    }
+
     pub fn left_button_pressed(&mut self, cursor: Cursor) -> Command<Message> {
+        // clicking the canvas takes focus away from the inspector and closes the context menu
+        self.apply_inspector();
+        self.context_menu = None;
+
         self.left_clicked_time_1 = self.left_clicked_time_2;
         self.left_clicked_time_2 = Some(Instant::now());
 
         self.nodebar.left_button_pressed(cursor);
-        self.graph.left_button_pressed(cursor);
+        self.graph.left_button_pressed(cursor, self.modifiers);
         self.cache.clear();
         Command::none()
     }
@@ -199,63 +473,14 @@ impl AppState {
         {
             // Only create a new component if the mouse is over the graph
             if cursor.is_over(self.graph.bounds) {
-                if self.nodebar.components.contains_key(&id) {
-                    if let Some(component) = self.nodebar.components.get(&id) {
-                        // Don't allow more than one base
-                        let mut set_modal = false;
-
-                        match component {
-                            &DummyComponent::Base(_) => {
-                                if !self.graph.components.is_empty() {
-                                    let mut has_base = false;
-                                    for (_, other_component) in &self.graph.components {
-                                        if matches!(other_component, MultibodyComponent::Base(_)) {
-                                            has_base = true;
-                                            break;
-                                        }
-                                    }
-
-                                    if has_base {
-                                        self.active_error = Some(Errors::TooManyBases);
-                                    } else {
-                                        set_modal = true;
-                                    }
-                                } else {
-                                    set_modal = true;
-                                }
-                            }
-                            _ => {
-                                // Not a base, all good
-                                set_modal = true;
-                            }
-                        }
-
-                        if set_modal {
-                            self.modal = Some(ActiveModal::new(id, None));
-                        }
-                    }
-                }
+                self.open_new_component(id);
             }
         }
-        if let Some(GraphMessage::EditComponent(id)) =
+        // double clicking a node brings back the inspector if it was closed
+        if let Some(GraphMessage::EditComponent) =
             self.graph.left_button_released(&release_event, cursor)
         {
-            if let Some(component) = self.graph.components.get(&id) {
-                let active_modal = ActiveModal::new(component.get_dummy_id(), Some(id));
-
-                if let Some(dummy) = self
-                    .nodebar
-                    .components
-                    .get_mut(&active_modal.dummy_component_id)
-                {
-                    if let Some(component_id) = active_modal.graph_component_id {
-                        // Editing an existing component, populate values
-                        dummy.inherit_from(&component_id, &self.graph);
-                    }
-
-                    self.modal = Some(active_modal);
-                }
-            }
+            self.open_inspector();
         }
 
         self.cache.clear();
@@ -265,32 +490,239 @@ impl AppState {
     pub fn middle_button_pressed(&mut self, _cursor: Cursor) -> Command<Message> {
         match self.graph.create_multibody_system() {
             Ok(system) => dbg!(system),
+            // the panel lists everything that's wrong, not just the first error
+            Err(_) if !self.is_problems_open => return self.problems_toggled(),
+            Err(_) => return Command::none(),
+        };
+        Command::none()
+    }
+
+    pub fn open_project(&mut self) -> Command<Message> {
+        Command::perform(pick_project_file(), Message::OpenProjectFileSelected)
+    }
+
+    pub fn open_project_file_selected(&mut self, path: Option<PathBuf>) -> Command<Message> {
+        // early return, the dialog was cancelled
+        let path = match path {
+            Some(path) => path,
+            None => return Command::none(),
+        };
+
+        let project = match Project::load(&path) {
+            Ok(project) => project,
             Err(error) => {
-                // TODO: handle error
-                return Command::none()
+                self.active_error = Some(Errors::Project(error));
+                return Command::none();
+            }
+        };
+
+        self.name_counters = project.get_name_counters();
+
+        // dummy ids are regenerated every launch, so point the components at this session's dummies
+        let mut components = project.components;
+        for component in components.values_mut() {
+            if let Some(dummy_id) = self.nodebar.get_dummy_id(component) {
+                component.set_dummy_id(dummy_id);
             }
+        }
+
+        self.graph
+            .load(components, project.edges, project.nodes, project.outputs);
+        self.context_menu = None;
+        self.graph.grid = project.grid;
+        self.graph.parameters = project.parameters;
+        self.graph.unit_system = project.unit_system;
+        self.graph.parameters_changed();
+        self.grid_spacing_input = project.grid.spacing.to_string();
+        self.modal = None;
+        self.project_path = Some(path);
+        self.cache.clear();
+        Command::none()
+    }
+
+    /// `position` is the center of the divider, the inspector gets everything right of it
+    pub fn inspector_resized(&mut self, position: u16) -> Command<Message> {
+        let split_width = self.get_split_width();
+        let max_width = (split_width - self.nodebar.bounds.width - MIN_INSPECTOR_WIDTH)
+            .max(MIN_INSPECTOR_WIDTH);
+        self.inspector.width = (split_width - position as f32 - INSPECTOR_DIVIDER_WIDTH / 2.0)
+            .clamp(MIN_INSPECTOR_WIDTH, max_width);
+        self.layout_panels();
+        Command::none()
+    }
+
+    /// Shows the inspector if it was closed
+    fn open_inspector(&mut self) {
+        if !self.inspector.is_open {
+            self.inspector.is_open = true;
+            self.layout_panels();
+        }
+    }
+
+    /// Opens the modal for a new component from the nodebar dummy `dummy_id`.
+    /// Only one base is allowed.
+    fn open_new_component(&mut self, dummy_id: Uuid) {
+        let dummy = match self.nodebar.components.get(&dummy_id) {
+            Some(dummy) => dummy,
+            None => return,
         };
+        let is_taken = dummy.is_unique()
+            && self
+                .graph
+                .components
+                .values()
+                .any(|component| dummy.is_kind_of(component));
+        if is_taken {
+            self.active_error = Some(Errors::TooManyBases);
+        } else {
+            self.modal = Some(ActiveModal::new(dummy_id, None));
+        }
+    }
+
+    pub fn inspector_toggled(&mut self) -> Command<Message> {
+        self.inspector.is_open = !self.inspector.is_open;
+        self.layout_panels();
+        Command::none()
+    }
+
+    /// The width shared by the canvas and the inspector
+    fn get_split_width(&self) -> f32 {
+        let mut width = self.window_size.width;
+        if self.is_problems_open {
+            width -= PROBLEMS_PANEL_WIDTH;
+        }
+        if self.is_parameters_open {
+            width -= PARAMETERS_PANEL_WIDTH;
+        }
+        width
+    }
+
+    /// Gives the graph whatever the nodebar and the open panels leave of the window
+    fn layout_panels(&mut self) {
+        let canvas_height = self.window_size.height - MENU_BAR_HEIGHT;
+        let mut canvas_width = self.get_split_width();
+        if self.inspector.is_open {
+            canvas_width -= self.inspector.width + INSPECTOR_DIVIDER_WIDTH;
+        }
+        let graph_size = Size::new(canvas_width - self.nodebar.bounds.width, canvas_height);
+        self.graph.window_resized(graph_size);
+        let nodebar_size = Size::new(self.nodebar.bounds.width, canvas_height);
+        self.nodebar.window_resized(nodebar_size);
+        self.cache.clear();
+    }
+
+    pub fn modifiers_changed(&mut self, modifiers: keyboard::Modifiers) -> Command<Message> {
+        self.modifiers = modifiers;
+        Command::none()
+    }
+
+    pub fn pan_pressed(&mut self, delta: Vector) -> Command<Message> {
+        self.graph.pan(delta);
+        self.cache.clear();
+        Command::none()
+    }
+
+    /// New parameters get the first free name, so the table always evaluates
+    pub fn parameter_added(&mut self) -> Command<Message> {
+        let parameters = &mut self.graph.parameters;
+        let name = (1..)
+            .map(|index| format!("p{}", index))
+            .find(|name| parameters.entries.iter().all(|parameter| &parameter.name != name))
+            .unwrap_or_default();
+        parameters.entries.push(Parameter {
+            name,
+            expression: "0".to_string(),
+        });
+        self.graph.parameters_changed();
+        self.cache.clear();
+        Command::none()
+    }
+
+    pub fn parameter_expression_changed(&mut self, index: usize, value: String) -> Command<Message> {
+        if let Some(parameter) = self.graph.parameters.entries.get_mut(index) {
+            parameter.expression = value;
+        }
+        self.graph.parameters_changed();
+        self.cache.clear();
+        Command::none()
+    }
+
+    pub fn parameter_name_changed(&mut self, index: usize, value: String) -> Command<Message> {
+        if let Some(parameter) = self.graph.parameters.entries.get_mut(index) {
+            parameter.name = value;
+        }
+        self.graph.parameters_changed();
+        self.cache.clear();
+        Command::none()
+    }
+
+    pub fn parameter_removed(&mut self, index: usize) -> Command<Message> {
+        if index < self.graph.parameters.entries.len() {
+            self.graph.parameters.entries.remove(index);
+        }
+        self.graph.parameters_changed();
+        self.cache.clear();
+        Command::none()
+    }
+
+    /// The panel takes its width from the graph
+    pub fn parameters_toggled(&mut self) -> Command<Message> {
+        self.is_parameters_open = !self.is_parameters_open;
+        self.layout_panels();
+        Command::none()
+    }
+
+    pub fn paste_pressed(&mut self) -> Command<Message> {
+        self.graph.paste_pressed();
+        self.cache.clear();
+        Command::none()
+    }
+
+    pub fn problem_selected(&mut self, component_id: Uuid) -> Command<Message> {
+        self.graph.focus_component(component_id);
+        self.cache.clear();
+        Command::none()
+    }
+
+    /// The panel takes its width from the graph
+    pub fn problems_toggled(&mut self) -> Command<Message> {
+        self.is_problems_open = !self.is_problems_open;
+        self.layout_panels();
+        Command::none()
+    }
+
+    pub fn redo_pressed(&mut self) -> Command<Message> {
+        self.settle_inspector();
+        if self.graph.redo() {
+            self.cache.clear();
+        }
         Command::none()
     }
 
     pub fn right_button_pressed(&mut self, cursor: Cursor) -> Command<Message> {
+        self.context_menu = None;
         self.nodebar.right_button_pressed(cursor);
         self.graph.right_button_pressed(cursor);
         Command::none()
     }
 
     pub fn right_button_released(&mut self, cursor: Cursor) -> Command<Message> {
-        self.graph.right_button_released(cursor);
+        if let Some(GraphMessage::OpenContextMenu(menu)) = self.graph.right_button_released(cursor) {
+            self.context_menu = Some(menu);
+        }
         self.nodebar.right_button_released(cursor);
         self.cache.clear();
         Command::none()
     }
 
     pub fn save_component(&mut self) -> Command<Message> {
-        // early return
+        // without a modal, Enter applies the inspector
         let modal = match self.modal {
             Some(ref modal) => modal,
-            None => return Command::none(),
+            None => {
+                self.apply_inspector();
+                return Command::none();
+            }
         };
 
         // early return
@@ -299,30 +731,40 @@ impl AppState {
             None => return Command::none(),
         };
 
+        // early return, the modal stays open until every field is valid
+        if dummy_component.has_invalid_fields(&self.graph.get_field_context()) {
+            return Command::none();
+        }
+
         // Ensure the body has a unique name if it's empty
-        if dummy_component.get_name().is_empty() {
-            let name = match dummy_component {
-                DummyComponent::Base(_) => "base".to_string(),
-                DummyComponent::Body(_) => {
-                    self.counter_body += 1;
-                    format!("body{}", self.counter_body)
-                }
-                DummyComponent::Revolute(_) => {
-                    self.counter_revolute += 1;
-                    format!("revolute{}", self.counter_revolute)
+        if dummy_component.get_name().trim().is_empty() {
+            let prefix = dummy_component.get_name_prefix();
+            let name = if dummy_component.is_unique() {
+                prefix.to_string()
+            } else {
+                let counter = self.name_counters.entry(prefix.to_string()).or_default();
+                // skip names taken by pasted components
+                loop {
+                    *counter += 1;
+                    let name = format!("{}{}", prefix, counter);
+                    if !self.graph.is_name_taken(&name) {
+                        break name;
+                    }
                 }
             };
             dummy_component.set_name(&name);
         }
         let graph_message = match modal.graph_component_id {
-            Some(id) => self.graph.edit_component(&dummy_component, id),
-            None => self.graph.save_component(&dummy_component),
+            Some(id) => self.graph.edit_component(dummy_component, id),
+            None => self.graph.save_component(dummy_component),
         };
 
-        //TODO: actually do something with the error/message
-        //match graph_message {
-
-        //}
+        // early return, the modal stays open so the input isn't lost
+        if let Err(error) = graph_message {
+            let message = format!("{}: {}", dummy_component.get_name(), error);
+            self.toasts.push(message, Instant::now());
+            return Command::none();
+        }
 
         // Clear the modal and cache
         dummy_component.clear();
@@ -331,6 +773,42 @@ impl AppState {
         Command::none()
     }
 
+    pub fn save_project(&mut self) -> Command<Message> {
+        match self.project_path.clone() {
+            Some(path) => self.save_project_file_selected(Some(path)),
+            None => self.save_project_as(),
+        }
+    }
+
+    pub fn save_project_as(&mut self) -> Command<Message> {
+        Command::perform(pick_save_file(), Message::SaveProjectFileSelected)
+    }
+
+    pub fn save_project_file_selected(&mut self, path: Option<PathBuf>) -> Command<Message> {
+        // early return, the dialog was cancelled
+        let path = match path {
+            Some(path) => path,
+            None => return Command::none(),
+        };
+
+        let project = Project::new(
+            &self.name_counters,
+            self.graph.components.clone(),
+            self.graph.edges.clone(),
+            self.graph.nodes.clone(),
+            self.graph.grid,
+            self.graph.parameters.clone(),
+            self.graph.unit_system,
+            self.graph.get_outputs(),
+        );
+
+        match project.save(&path) {
+            Ok(()) => self.project_path = Some(path),
+            Err(error) => self.active_error = Some(Errors::Project(error)),
+        }
+        Command::none()
+    }
+
     fn tab_pressed(&mut self) -> Command<Message> {
         if self.modal.is_some() {
             Command::none()
@@ -340,62 +818,129 @@ impl AppState {
         }
     }
 
-    pub fn update_body_field(&mut self, field: BodyField, value: &str) -> Command<Message> {
-        if let Some(dummy_component) = self.nodebar.components.get_mut(&self.nodebar.map.body) {
-            if let DummyComponent::Body(dummy_body) = dummy_component {
-                match field {
-                    BodyField::Name => dummy_body.set_name(value),
-                    BodyField::Mass => dummy_body.mass = value.to_string(),
-                    BodyField::Cmx => dummy_body.cmx = value.to_string(),
-                    BodyField::Cmy => dummy_body.cmy = value.to_string(),
-                    BodyField::Cmz => dummy_body.cmz = value.to_string(),
-                    BodyField::Ixx => dummy_body.ixx = value.to_string(),
-                    BodyField::Iyy => dummy_body.iyy = value.to_string(),
-                    BodyField::Izz => dummy_body.izz = value.to_string(),
-                    BodyField::Ixy => dummy_body.ixy = value.to_string(),
-                    BodyField::Ixz => dummy_body.ixz = value.to_string(),
-                    BodyField::Iyz => dummy_body.iyz = value.to_string(),
-                }
-            } else {
-                // Handle error: must be the dummy body
-                eprintln!("Error: Component is not a DummyBody");
-            }
+    pub fn snap_toggled(&mut self) -> Command<Message> {
+        self.graph.grid.is_snapping = !self.graph.grid.is_snapping;
+        Command::none()
+    }
+
+    /// Adds the themes found in the themes directory to the picker.
+    /// Broken files are reported as toasts, the rest still load.
+    pub fn load_user_themes(&mut self) {
+        let Some(dir) = crate::ui::theme::user::get_themes_dir() else {
+            return;
+        };
+        let (themes, errors) = crate::ui::theme::user::load_themes(&dir);
+        self.theme_choices.extend(
+            themes
+                .into_iter()
+                .map(|(name, theme)| ThemeChoice::User(name, theme)),
+        );
+        for (file_name, error) in errors {
+            let message = Errors::Theme(file_name, error).get_error_message();
+            self.toasts.push(message, Instant::now());
         }
+    }
+
+    pub fn toast_closed(&mut self, id: usize) -> Command<Message> {
+        self.toasts.close(id);
         Command::none()
     }
 
-    pub fn update_revolute_field(&mut self, field: RevoluteField, value: &str) -> Command<Message> {
-        if let Some(dummy_component) = self.nodebar.components.get_mut(&self.nodebar.map.revolute) {
-            if let DummyComponent::Revolute(dummy_revolute) = dummy_component {
-                match field {
-                    RevoluteField::Name => dummy_revolute.set_name(value),
-                    RevoluteField::ConstantForce => {
-                        dummy_revolute.constant_force = value.to_string()
-                    }
-                    RevoluteField::Dampening => dummy_revolute.dampening = value.to_string(),
-                    RevoluteField::Omega => dummy_revolute.omega = value.to_string(),
-                    RevoluteField::SpringConstant => {
-                        dummy_revolute.spring_constant = value.to_string()
-                    }
-                    RevoluteField::Theta => dummy_revolute.theta = value.to_string(),
-                }
-            } else {
-                // Handle error: must be the dummy revolute
-                eprintln!("Error: Component is not a DummyRevolute");
-            }
+    pub fn toast_tick(&mut self, now: Instant) -> Command<Message> {
+        self.toasts.expire(now);
+        Command::none()
+    }
+
+    /// Widgets restyle from `Application::theme`, the canvas needs redrawing
+    pub fn theme_selected(&mut self, choice: ThemeChoice) -> Command<Message> {
+        self.theme = choice.get_theme();
+        self.theme_choice = choice;
+        self.cache.clear();
+        Command::none()
+    }
+
+    /// Applies pending inspector edits, edits that can't be applied are dropped with a warning.
+    /// Called before anything that reloads the inspector, which would otherwise keep showing them.
+    fn settle_inspector(&mut self) {
+        self.apply_inspector();
+        if !self.inspector.is_dirty {
+            return;
+        }
+        if let Some(dummy) = &self.inspector.dummy {
+            self.toasts.push(
+                format!("{}: edits that couldn't be applied were discarded", dummy.get_name()),
+                Instant::now(),
+            );
+        }
+        self.inspector.discard(&self.graph);
+    }
+
+    /// Follows the graph selection, edits to the previous component are applied first
+    fn sync_inspector(&mut self) {
+        let selected = self.graph.get_selected_component();
+        if selected == self.inspector.get_component_id() {
+            self.inspector.refresh(&self.graph);
+        } else {
+            self.settle_inspector();
+            self.inspector.select(selected, &self.graph);
+        }
+    }
+
+    /// The inspector is reloaded so its text is written in the new units
+    pub fn unit_system_selected(&mut self, unit_system: UnitSystem) -> Command<Message> {
+        self.settle_inspector();
+        self.graph.unit_system = unit_system;
+        self.inspector
+            .select(self.inspector.get_component_id(), &self.graph);
+        Command::none()
+    }
+
+    pub fn undo_pressed(&mut self) -> Command<Message> {
+        self.settle_inspector();
+        if self.graph.undo() {
+            self.cache.clear();
+        }
+        Command::none()
+    }
+
+    /// Field messages edit the open modal, or the inspector when there isn't one
+    fn get_editing_dummy(&mut self) -> Option<&mut DummyComponent> {
+        match self.modal {
+            Some(modal) => self.nodebar.components.get_mut(&modal.dummy_component_id),
+            None => self.inspector.dummy.as_mut(),
+        }
+    }
+
+    pub fn update_field(&mut self, index: usize, value: &str) -> Command<Message> {
+        if self.modal.is_none() {
+            self.inspector.is_dirty = self.inspector.dummy.is_some();
+        }
+        if let Some(dummy) = self.get_editing_dummy() {
+            dummy.set_value(index, value);
+        }
+        Command::none()
+    }
+
+    pub fn update_name(&mut self, value: &str) -> Command<Message> {
+        if self.modal.is_none() {
+            self.inspector.is_dirty = self.inspector.dummy.is_some();
+        }
+        if let Some(dummy) = self.get_editing_dummy() {
+            dummy.set_name(value);
+        }
+        Command::none()
+    }
+
+    pub fn wheel_scrolled(&mut self, cursor: Cursor, delta: ScrollDelta) -> Command<Message> {
+        if self.graph.wheel_scrolled(cursor, delta) {
+            self.cache.clear();
         }
         Command::none()
     }
 
     fn window_resized(&mut self, window_size: Size) -> Command<Message> {
-        let graph_size = Size::new(
-            window_size.width - self.nodebar.bounds.width,
-            window_size.height,
-        );
-        self.graph.window_resized(graph_size);
-        let nodebar_size = Size::new(self.nodebar.bounds.width, window_size.height);
-        self.nodebar.window_resized(nodebar_size);
-        self.cache.clear();
+        self.window_size = window_size;
+        self.layout_panels();
         Command::none()
     }
 }
@@ -404,6 +949,23 @@ async fn load() -> Result<(), String> {
     Ok(())
 }
 
+async fn pick_project_file() -> Option<PathBuf> {
+    rfd::AsyncFileDialog::new()
+        .add_filter("project", &["ron"])
+        .pick_file()
+        .await
+        .map(|file| file.path().to_path_buf())
+}
+
+async fn pick_save_file() -> Option<PathBuf> {
+    rfd::AsyncFileDialog::new()
+        .add_filter("project", &["ron"])
+        .set_file_name("project.ron")
+        .save_file()
+        .await
+        .map(|file| file.path().to_path_buf())
+}
+
 impl Application for IcedTest {
     type Message = Message;
     type Theme = crate::ui::theme::Theme;
@@ -421,85 +983,103 @@ impl Application for IcedTest {
     }
 
     fn title(&self) -> String {
-        String::from("jds")
+        let file_name = match self {
+            IcedTest::Loaded(state) => state
+                .project_path
+                .as_ref()
+                .and_then(|path| path.file_name())
+                .map(|name| name.to_string_lossy().to_string()),
+            IcedTest::Loading => None,
+        };
+        match file_name {
+            Some(name) => format!("jds - {}", name),
+            None => String::from("jds"),
+        }
+    }
+
+    fn theme(&self) -> Self::Theme {
+        match self {
+            IcedTest::Loaded(state) => state.theme,
+            IcedTest::Loading => Self::Theme::default(),
+        }
     }
 
     fn update(&mut self, message: Message) -> Command<Message> {
         match self {
             IcedTest::Loading => {
                 if let Message::Loaded(_) = message {
-                    *self = IcedTest::Loaded(AppState::default());
+                    let mut state = AppState::default();
+                    state.load_user_themes();
+                    state.layout_panels();
+                    *self = IcedTest::Loaded(Box::new(state));
                 }
                 Command::none()
             }
-            IcedTest::Loaded(state) => match message {
-                Message::FontLoaded(_) => Command::none(),
-                Message::Loaded(_) => Command::none(),
-                Message::BodyNameInputChanged(value) => {
-                    state.update_body_field(BodyField::Name, &value)
-                }
-                Message::BodyMassInputChanged(value) => {
-                    state.update_body_field(BodyField::Mass, &value)
-                }
-                Message::BodyCmxInputChanged(value) => {
-                    state.update_body_field(BodyField::Cmx, &value)
-                }
-                Message::BodyCmyInputChanged(value) => {
-                    state.update_body_field(BodyField::Cmy, &value)
-                }
-                Message::BodyCmzInputChanged(value) => {
-                    state.update_body_field(BodyField::Cmz, &value)
-                }
-                Message::BodyIxxInputChanged(value) => {
-                    state.update_body_field(BodyField::Ixx, &value)
-                }
-                Message::BodyIyyInputChanged(value) => {
-                    state.update_body_field(BodyField::Iyy, &value)
-                }
-                Message::BodyIzzInputChanged(value) => {
-                    state.update_body_field(BodyField::Izz, &value)
-                }
-                Message::BodyIxyInputChanged(value) => {
-                    state.update_body_field(BodyField::Ixy, &value)
-                }
-                Message::BodyIxzInputChanged(value) => {
-                    state.update_body_field(BodyField::Ixz, &value)
-                }
-                Message::BodyIyzInputChanged(value) => {
-                    state.update_body_field(BodyField::Iyz, &value)
-                }
-                Message::RevoluteConstantForceInputChanged(value) => {
-                    state.update_revolute_field(RevoluteField::ConstantForce, &value)
-                }
-                Message::RevoluteDampeningInputChanged(value) => {
-                    state.update_revolute_field(RevoluteField::Dampening, &value)
-                }
-                Message::RevoluteNameInputChanged(value) => {
-                    state.update_revolute_field(RevoluteField::Name, &value)
-                }
-                Message::RevoluteOmegaInputChanged(value) => {
-                    state.update_revolute_field(RevoluteField::Omega, &value)
-                }
-                Message::RevoluteSpringConstantInputChanged(value) => {
-                    state.update_revolute_field(RevoluteField::SpringConstant, &value)
-                }
-                Message::RevoluteThetaInputChanged(value) => {
-                    state.update_revolute_field(RevoluteField::Theta, &value)
+            IcedTest::Loaded(state) => {
+                let is_view_only = message.is_view_only();
+                let command = match message {
+                    Message::FontLoaded(_) => Command::none(),
+                    Message::Loaded(_) => Command::none(),
+                    Message::FieldInputChanged(index, value) => state.update_field(index, &value),
+                    Message::NameInputChanged(value) => state.update_name(&value),
+                    Message::LeftButtonPressed(cursor) => state.left_button_pressed(cursor),
+                    Message::LeftButtonReleased(cursor) => state.left_button_released(cursor),
+                    Message::MiddleButtonPressed(cursor) => state.middle_button_pressed(cursor),
+                    Message::RightButtonPressed(cursor) => state.right_button_pressed(cursor),
+                    Message::RightButtonReleased(cursor) => state.right_button_released(cursor),
+                    Message::AnimationTick(now) => state.animation_tick(now),
+                    Message::AutoLayoutPressed(orientation) => state.auto_layout_pressed(orientation),
+                    Message::CheckFocus => state.check_focus(),
+                    Message::CloseError => state.close_error(),
+                    Message::CloseModal => state.close_modal(),
+                    Message::ContextActionSelected(action) => state.context_action_selected(action),
+                    Message::ContextMenuClosed => state.context_menu_closed(),
+                    Message::CopyPressed => state.copy_pressed(),
+                    Message::CursorMoved(cursor) => state.cursor_moved(cursor),
+                    Message::CutPressed => state.cut_pressed(),
+                    Message::DeletePressed => state.delete_pressed(),
+                    Message::DuplicatePressed => state.duplicate_pressed(),
+                    Message::EnterPressed => state.enter_pressed(),
+                    Message::FitAllPressed => state.fit_all_pressed(),
+                    Message::FocusChanged(focused) => state.focus_changed(focused),
+                    Message::GridSpacingChanged(value) => state.grid_spacing_changed(value),
+                    Message::GridToggled => state.grid_toggled(),
+                    Message::InspectorResized(position) => state.inspector_resized(position),
+                    Message::InspectorToggled => state.inspector_toggled(),
+                    Message::ModifiersChanged(modifiers) => state.modifiers_changed(modifiers),
+                    Message::PanPressed(delta) => state.pan_pressed(delta),
+                    Message::ParameterAdded => state.parameter_added(),
+                    Message::ParameterExpressionChanged(index, value) => {
+                        state.parameter_expression_changed(index, value)
+                    }
+                    Message::ParameterNameChanged(index, value) => state.parameter_name_changed(index, value),
+                    Message::ParameterRemoved(index) => state.parameter_removed(index),
+                    Message::ParametersToggled => state.parameters_toggled(),
+                    Message::RedoPressed => state.redo_pressed(),
+                    Message::TabPressed => state.tab_pressed(),
+                    Message::ThemeSelected(choice) => state.theme_selected(choice),
+                    Message::ToastClosed(id) => state.toast_closed(id),
+                    Message::ToastTick(now) => state.toast_tick(now),
+                    Message::UndoPressed => state.undo_pressed(),
+                    Message::UnitSystemSelected(unit_system) => state.unit_system_selected(unit_system),
+                    Message::OpenProject => state.open_project(),
+                    Message::OpenProjectFileSelected(path) => state.open_project_file_selected(path),
+                    Message::PastePressed => state.paste_pressed(),
+                    Message::ProblemSelected(component_id) => state.problem_selected(component_id),
+                    Message::ProblemsToggled => state.problems_toggled(),
+                    Message::SaveComponent => state.save_component(),
+                    Message::SaveProject => state.save_project(),
+                    Message::SaveProjectAs => state.save_project_as(),
+                    Message::SaveProjectFileSelected(path) => state.save_project_file_selected(path),
+                    Message::SnapToggled => state.snap_toggled(),
+                    Message::WheelScrolled(cursor, delta) => state.wheel_scrolled(cursor, delta),
+                    Message::WindowResized(size) => state.window_resized(size),
+                };
+                if !is_view_only {
+                    state.sync_inspector();
                 }
-                Message::LeftButtonPressed(cursor) => state.left_button_pressed(cursor),
-                Message::LeftButtonReleased(cursor) => state.left_button_released(cursor),
-                Message::MiddleButtonPressed(cursor) => state.middle_button_pressed(cursor),
-                Message::RightButtonPressed(cursor) => state.right_button_pressed(cursor),
-                Message::RightButtonReleased(cursor) => state.right_button_released(cursor),
-                Message::CloseError => state.close_error(),
-                Message::CloseModal => state.close_modal(),
-                Message::CursorMoved(cursor) => state.cursor_moved(cursor),
-                Message::DeletePressed => state.delete_pressed(),
-                Message::EnterPressed => state.enter_pressed(),
-                Message::TabPressed => state.tab_pressed(),
-                Message::SaveComponent => state.save_component(),
-                Message::WindowResized(size) => state.window_resized(size),
-            },
+                command
+            }
         }
     }
 
@@ -511,18 +1091,90 @@ impl Application for IcedTest {
     }
 
     fn subscription(&self) -> Subscription<Self::Message> {
-        iced::event::listen_with(|event, _| match event {
+        // only redraw every frame while nodes are moving on their own
+        let animation = match self {
+            IcedTest::Loaded(state) if state.graph.is_animating() => {
+                window::frames().map(Message::AnimationTick)
+            }
+            _ => Subscription::none(),
+        };
+        // toasts only need to notice when they've been up long enough
+        let toasts = match self {
+            IcedTest::Loaded(state) if !state.toasts.is_empty() => {
+                iced::time::every(TOAST_TICK).map(Message::ToastTick)
+            }
+            _ => Subscription::none(),
+        };
+
+        let events = iced::event::listen_with(|event, status| match event {
             iced::Event::Window(_, window::Event::Resized { width, height }) => Some(
                 Message::WindowResized(Size::new(width as f32, height as f32)),
             ),
-            iced::Event::Keyboard(keyboard::Event::KeyPressed { key, .. }) => match key {
+            // a click can move focus out of an inspector field
+            iced::Event::Mouse(iced::mouse::Event::ButtonPressed(_)) => Some(Message::CheckFocus),
+            iced::Event::Keyboard(keyboard::Event::ModifiersChanged(modifiers)) => {
+                Some(Message::ModifiersChanged(modifiers))
+            }
+            iced::Event::Keyboard(keyboard::Event::KeyPressed { key, modifiers, .. }) => match key {
+                keyboard::Key::Character(c) if modifiers.command() => {
+                    // clipboard and undo keys belong to a focused text input
+                    let is_ignored = status == iced::event::Status::Ignored;
+                    match c.to_lowercase().as_str() {
+                        "c" if is_ignored => Some(Message::CopyPressed),
+                        "d" if is_ignored => Some(Message::DuplicatePressed),
+                        "o" => Some(Message::OpenProject),
+                        "s" if modifiers.shift() => Some(Message::SaveProjectAs),
+                        "s" => Some(Message::SaveProject),
+                        "v" if is_ignored => Some(Message::PastePressed),
+                        "x" if is_ignored => Some(Message::CutPressed),
+                        "z" if is_ignored && modifiers.shift() => Some(Message::RedoPressed),
+                        "z" if is_ignored => Some(Message::UndoPressed),
+                        _ => None,
+                    }
+                }
                 keyboard::Key::Named(keyboard::key::Named::Enter) => Some(Message::EnterPressed),
-                keyboard::Key::Named(keyboard::key::Named::Delete) => Some(Message::DeletePressed),
+                keyboard::Key::Named(keyboard::key::Named::Escape) => Some(Message::ContextMenuClosed),
+                keyboard::Key::Named(keyboard::key::Named::Delete)
+                    if status == iced::event::Status::Ignored =>
+                {
+                    Some(Message::DeletePressed)
+                }
                 keyboard::Key::Named(keyboard::key::Named::Tab) => Some(Message::TabPressed),
+                // view keys are left alone while a text input is using them
+                _ if status == iced::event::Status::Captured => None,
+                keyboard::Key::Character(c) if c.to_lowercase() == "f" => Some(Message::FitAllPressed),
+                keyboard::Key::Character(c) if c.to_lowercase() == "g" => {
+                    Some(if modifiers.shift() {
+                        Message::SnapToggled
+                    } else {
+                        Message::GridToggled
+                    })
+                }
+                keyboard::Key::Character(c) if c.to_lowercase() == "l" => {
+                    Some(Message::AutoLayoutPressed(if modifiers.shift() {
+                        LayoutOrientation::LeftRight
+                    } else {
+                        LayoutOrientation::TopDown
+                    }))
+                }
+                keyboard::Key::Named(keyboard::key::Named::ArrowDown) => {
+                    Some(Message::PanPressed(Vector::new(0.0, -PAN_STEP)))
+                }
+                keyboard::Key::Named(keyboard::key::Named::ArrowLeft) => {
+                    Some(Message::PanPressed(Vector::new(PAN_STEP, 0.0)))
+                }
+                keyboard::Key::Named(keyboard::key::Named::ArrowRight) => {
+                    Some(Message::PanPressed(Vector::new(-PAN_STEP, 0.0)))
+                }
+                keyboard::Key::Named(keyboard::key::Named::ArrowUp) => {
+                    Some(Message::PanPressed(Vector::new(0.0, PAN_STEP)))
+                }
                 _ => None,
             },
             _ => None,
-        })
+        });
+
+        Subscription::batch([animation, toasts, events])
     }
 }
 // Helper function to create the loading view
@@ -539,8 +1191,13 @@ fn loading_view() -> Element<'static, Message, crate::ui::theme::Theme> {
     .into()
 }
 
+fn on_off(label: &str, is_on: bool) -> String {
+    format!("{}: {}", label, if is_on { "On" } else { "Off" })
+}
+
 // Helper function to create the main loaded view
 fn loaded_view(state: &AppState) -> Element<Message, crate::ui::theme::Theme> {
+    let context = state.graph.get_field_context();
     let graph_canvas = GraphCanvas::new(state);
     let graph_container = container(
         Canvas::new(graph_canvas)
@@ -549,10 +1206,94 @@ fn loaded_view(state: &AppState) -> Element<Message, crate::ui::theme::Theme> {
     )
     .width(Length::Fill)
     .height(Length::Fill);
+    let problems = state.graph.get_hovered_problems();
+    let graph_container: Element<Message, crate::ui::theme::Theme> = if problems.is_empty() {
+        graph_container.into()
+    } else {
+        let lines = problems
+            .iter()
+            .fold(Column::new(), |column, problem| column.push(text(problem.to_string())));
+        tooltip(
+            graph_container,
+            container(lines).width(Length::Fixed(TOOLTIP_WIDTH)).padding(6),
+            tooltip::Position::FollowCursor,
+        )
+        .into()
+    };
+    let graph_container = floating_element(graph_container, create_toasts(&state.toasts))
+        .hide(state.toasts.is_empty());
+    let graph_container: Element<Message, crate::ui::theme::Theme> = match &state.context_menu {
+        Some(menu) => {
+            let (menu, offset) = create_context_menu(state, menu);
+            floating_element(graph_container, menu)
+                .anchor(Anchor::NorthWest)
+                .offset(offset)
+                .into()
+        }
+        None => graph_container.into(),
+    };
 
-    let underlay = Row::new().push(graph_container);
+    let menu_bar = Row::new()
+        .spacing(5)
+        .padding(5)
+        .height(Length::Fixed(MENU_BAR_HEIGHT))
+        .push(button("Open").on_press(Message::OpenProject))
+        .push(button("Save").on_press(Message::SaveProject))
+        .push(button("Save As").on_press(Message::SaveProjectAs))
+        .push(button("Layout Top-Down").on_press(Message::AutoLayoutPressed(LayoutOrientation::TopDown)))
+        .push(button("Layout Left-Right").on_press(Message::AutoLayoutPressed(LayoutOrientation::LeftRight)))
+        .push(button(text(on_off("Grid", state.graph.grid.is_visible))).on_press(Message::GridToggled))
+        .push(button(text(on_off("Snap", state.graph.grid.is_snapping))).on_press(Message::SnapToggled))
+        .push(
+            text_input("Spacing", &state.grid_spacing_input)
+                .on_input(Message::GridSpacingChanged)
+                .width(Length::Fixed(60.0)),
+        )
+        .push(
+            button(text(format!("Problems ({})", state.graph.get_problems().len())))
+                .on_press(Message::ProblemsToggled),
+        )
+        .push(button(text(on_off("Inspector", state.inspector.is_open))).on_press(Message::InspectorToggled))
+        .push(button(text(on_off("Parameters", state.is_parameters_open))).on_press(Message::ParametersToggled))
+        .push(pick_list(
+            &UnitSystem::ALL[..],
+            Some(state.graph.unit_system),
+            Message::UnitSystemSelected,
+        ))
+        .push(pick_list(
+            &state.theme_choices[..],
+            Some(state.theme_choice.clone()),
+            Message::ThemeSelected,
+        ));
+
+    let graph_pane: Element<Message, crate::ui::theme::Theme> = if state.inspector.is_open {
+        let split_width = state.get_split_width();
+        let divider = split_width - state.inspector.width - INSPECTOR_DIVIDER_WIDTH / 2.0;
+        Split::new(
+            graph_container,
+            create_inspector_panel(&state.inspector, &context),
+            Some(divider.max(0.0) as u16),
+            Axis::Vertical,
+            Message::InspectorResized,
+        )
+        .spacing(INSPECTOR_DIVIDER_WIDTH)
+        .min_size_second(MIN_INSPECTOR_WIDTH as u16)
+        .into()
+    } else {
+        graph_container
+    };
+
+    let mut content = Row::new().push(graph_pane);
+    if state.is_problems_open {
+        content = content.push(create_problems_panel(&state.graph));
+    }
+    if state.is_parameters_open {
+        content = content.push(create_parameters_panel(&state.graph.parameters));
+    }
 
-    let overlay = if let Some(active_error) = state.active_error {
+    let underlay = Column::new().push(menu_bar).push(content);
+
+    let overlay = if let Some(active_error) = &state.active_error {
         Some(create_error_modal(active_error))
     } else if let Some(active_modal) = state.modal {
         // and there's a DummyComponent for that ActiveModal
@@ -561,11 +1302,7 @@ fn loaded_view(state: &AppState) -> Element<Message, crate::ui::theme::Theme> {
             .components
             .get(&active_modal.dummy_component_id)
         {
-            match dummy {
-                DummyComponent::Base(base) => Some(create_base_modal(base)),
-                DummyComponent::Body(body) => Some(create_body_modal(body)),
-                DummyComponent::Revolute(joint) => Some(create_revolute_modal(joint)),
-            }
+            Some(create_component_modal(dummy, &context))
         } else {
             None
         }
@@ -579,167 +1316,210 @@ fn loaded_view(state: &AppState) -> Element<Message, crate::ui::theme::Theme> {
         .into()
 }
 
-fn create_base_modal(_base: &DummyBase) -> Element<'static, Message, crate::ui::theme::Theme> {
-    let content = Column::new();
-    let footer = Row::new()
-        .spacing(10)
-        .padding(5)
-        .width(Length::Fill)
-        .push(
-            button("Cancel")
-                .width(Length::Fill)
-                .on_press(Message::CloseModal),
-        )
-        .push(
-            button("Ok")
-                .width(Length::Fill)
-                .on_press(Message::SaveComponent),
-        );
+/// The menu's buttons and where it goes over the canvas, kept inside the graph
+fn create_context_menu<'a>(
+    state: &'a AppState,
+    menu: &ContextMenu,
+) -> (Element<'a, Message, crate::ui::theme::Theme>, [f32; 2]) {
+    let map = &state.nodebar.map;
+    let actions = menu.get_actions(&[map.base, map.body, map.revolute]);
 
-    card("Base Information", content)
-        .foot(footer)
-        .max_width(500.0)
+    let mut column = Column::new().spacing(2).padding(5).width(Length::Fill);
+    for action in &actions {
+        let label = match action {
+            ContextAction::AddComponent(dummy_id) => {
+                let kind = state
+                    .nodebar
+                    .nodes
+                    .values()
+                    .find(|nodebarnode| nodebarnode.component_id == *dummy_id)
+                    .map_or("", |nodebarnode| nodebarnode.label.trim_start_matches('+'));
+                format!("Add {}", kind)
+            }
+            ContextAction::AutoLayout(LayoutOrientation::LeftRight) => "Layout left-right".to_string(),
+            ContextAction::AutoLayout(LayoutOrientation::TopDown) => "Layout top-down".to_string(),
+            ContextAction::Delete => "Delete".to_string(),
+            ContextAction::DisconnectAll => "Disconnect all".to_string(),
+            ContextAction::Duplicate => "Duplicate".to_string(),
+            ContextAction::Edit => "Edit".to_string(),
+            ContextAction::FitView => "Fit view".to_string(),
+            ContextAction::Paste => "Paste".to_string(),
+            ContextAction::Rename => "Rename".to_string(),
+            ContextAction::Reverse => "Reverse".to_string(),
+            ContextAction::ToggleOutput => {
+                let is_output = match menu.target {
+                    ContextTarget::Node(node_id) => state
+                        .graph
+                        .nodes
+                        .get(&node_id)
+                        .is_some_and(|graphnode| state.graph.is_output(graphnode.component_id)),
+                    _ => false,
+                };
+                if is_output { "Remove output" } else { "Set as output" }.to_string()
+            }
+        };
+        let mut entry = button(text(label))
+            .width(Length::Fill)
+            .height(Length::Fixed(CONTEXT_MENU_ITEM_HEIGHT));
+        // nothing to paste leaves the entry disabled
+        if *action != ContextAction::Paste || state.graph.can_paste() {
+            entry = entry.on_press(Message::ContextActionSelected(*action));
+        }
+        column = column.push(entry);
+    }
+
+    let bounds = state.graph.bounds;
+    let height = actions.len() as f32 * (CONTEXT_MENU_ITEM_HEIGHT + 2.0) + 10.0;
+    let x = (bounds.x + menu.position.x).min(bounds.x + bounds.width - CONTEXT_MENU_WIDTH);
+    let y = (bounds.y + menu.position.y).min(bounds.y + bounds.height - height);
+    let content = container(column).width(Length::Fixed(CONTEXT_MENU_WIDTH));
+    (content.into(), [x.max(0.0), y.max(0.0)])
+}
+
+/// Stacked in the corner of the graph, newest at the bottom
+fn create_toasts(toasts: &Toasts) -> Element<'_, Message, crate::ui::theme::Theme> {
+    let mut column = Column::new().spacing(5).width(Length::Fixed(TOAST_WIDTH));
+    for toast in toasts.get_toasts() {
+        let content = Row::new()
+            .spacing(5)
+            .align_items(alignment::Alignment::Center)
+            .push(text(&toast.message).style(ui::theme::Text::Error).width(Length::Fill))
+            .push(button("x").on_press(Message::ToastClosed(toast.id)));
+        column = column.push(container(content).padding(10).width(Length::Fill));
+    }
+    column.into()
+}
+
+/// Lists every validation problem, clicking one focuses its node
+fn create_problems_panel(graph: &Graph) -> Element<'_, Message, crate::ui::theme::Theme> {
+    let mut problems = Column::new().spacing(5).padding(5);
+    if graph.get_problems().is_empty() {
+        problems = problems.push(text("No problems found."));
+    }
+    for problem in graph.get_problems() {
+        let entry = button(text(graph.describe_error(problem))).width(Length::Fill);
+        problems = problems.push(match problem.get_component_id() {
+            Some(id) => entry.on_press(Message::ProblemSelected(id)),
+            None => entry,
+        });
+    }
+
+    container(scrollable(problems))
+        .width(Length::Fixed(PROBLEMS_PANEL_WIDTH))
+        .height(Length::Fill)
         .into()
 }
 
-fn create_body_modal(body: &DummyBody) -> Element<Message, crate::ui::theme::Theme> {
-    let create_text_input = |label: &str, value: &str, on_input: fn(String) -> Message| {
-        Row::new()
-            .spacing(10)
-            .push(text(label).width(Length::FillPortion(1)))
+/// One row per parameter, each showing its value or what's wrong with it
+fn create_parameters_panel(parameters: &Parameters) -> Element<'_, Message, crate::ui::theme::Theme> {
+    let mut rows = Column::new().spacing(5).padding(5);
+    for (index, (parameter, result)) in parameters.entries.iter().zip(parameters.evaluate()).enumerate() {
+        let (result, style) = match result {
+            Ok(value) => (format!("= {}", value), ui::theme::Text::Default),
+            Err(error) => (error.get_error_message(), ui::theme::Text::Error),
+        };
+        let row = Row::new()
+            .spacing(5)
+            .align_items(alignment::Alignment::Center)
+            .push(
+                text_input("name", &parameter.name)
+                    .on_input(move |value| Message::ParameterNameChanged(index, value))
+                    .width(Length::FillPortion(2)),
+            )
             .push(
-                text_input(label, value)
-                    .on_input(on_input)
-                    .on_submit(Message::SaveComponent)
-                    .width(Length::FillPortion(4)),
+                text_input("expression", &parameter.expression)
+                    .on_input(move |value| Message::ParameterExpressionChanged(index, value))
+                    .width(Length::FillPortion(3)),
             )
+            .push(button("x").on_press(Message::ParameterRemoved(index)));
+        rows = rows.push(row).push(text(result).style(style));
+    }
+    rows = rows.push(
+        button("Add parameter")
             .width(Length::Fill)
-    };
+            .on_press(Message::ParameterAdded),
+    );
 
-    let content = Column::new()
-        .push(create_text_input(
-            "name",
-            &body.get_name(),
-            Message::BodyNameInputChanged,
-        ))
-        .push(create_text_input(
-            "mass",
-            &body.mass,
-            Message::BodyMassInputChanged,
-        ))
-        .push(create_text_input(
-            "cmx",
-            &body.cmx,
-            Message::BodyCmxInputChanged,
-        ))
-        .push(create_text_input(
-            "cmy",
-            &body.cmy,
-            Message::BodyCmyInputChanged,
-        ))
-        .push(create_text_input(
-            "cmz",
-            &body.cmz,
-            Message::BodyCmzInputChanged,
-        ))
-        .push(create_text_input(
-            "ixx",
-            &body.ixx,
-            Message::BodyIxxInputChanged,
-        ))
-        .push(create_text_input(
-            "iyy",
-            &body.iyy,
-            Message::BodyIyyInputChanged,
-        ))
-        .push(create_text_input(
-            "izz",
-            &body.izz,
-            Message::BodyIzzInputChanged,
-        ))
-        .push(create_text_input(
-            "ixy",
-            &body.ixy,
-            Message::BodyIxyInputChanged,
-        ))
-        .push(create_text_input(
-            "ixz",
-            &body.ixz,
-            Message::BodyIxzInputChanged,
-        ))
-        .push(create_text_input(
-            "iyz",
-            &body.iyz,
-            Message::BodyIyzInputChanged,
-        ));
+    container(scrollable(rows))
+        .width(Length::Fixed(PARAMETERS_PANEL_WIDTH))
+        .height(Length::Fill)
+        .into()
+}
 
-    let footer = Row::new()
-        .spacing(10)
-        .padding(5)
-        .width(Length::Fill)
-        .push(
-            button("Cancel")
-                .width(Length::Fill)
-                .on_press(Message::CloseModal),
-        )
-        .push(
-            button("Ok")
-                .width(Length::Fill)
-                .on_press(Message::SaveComponent),
-        );
+/// The selected component's fields, applied on Enter or when the canvas is clicked
+fn create_inspector_panel<'a>(
+    inspector: &'a Inspector,
+    context: &FieldContext,
+) -> Element<'a, Message, crate::ui::theme::Theme> {
+    let fields: Element<Message, crate::ui::theme::Theme> = match &inspector.dummy {
+        Some(dummy) => create_component_fields(dummy, context).into(),
+        None => text("Select a single component to edit it.").into(),
+    };
 
-    card("Body Information", content)
-        .foot(footer)
-        .max_width(500.0)
+    container(scrollable(Column::new().spacing(5).padding(5).push(fields)))
+        .width(Length::Fill)
+        .height(Length::Fill)
         .into()
 }
 
-fn create_revolute_modal(joint: &DummyRevolute) -> Element<Message, crate::ui::theme::Theme> {
-    let create_text_input = |label: &str, value: &str, on_input: fn(String) -> Message| {
+/// A row for the name and one for each field in the component's schema,
+/// shared by the modal and the inspector
+fn create_component_fields<'a>(
+    dummy: &'a DummyComponent,
+    context: &FieldContext,
+) -> Column<'a, Message, crate::ui::theme::Theme> {
+    let create_row = |label: &str, unit: &str, input: TextInput<'static, Message, crate::ui::theme::Theme>| {
         Row::new()
             .spacing(10)
-            .push(text(label).width(Length::FillPortion(1)))
-            .push(
-                text_input(label, value)
-                    .on_input(on_input)
-                    .on_submit(Message::SaveComponent)
-                    .width(Length::FillPortion(2)),
-            )
+            .align_items(alignment::Alignment::Center)
+            .push(text(label).width(Length::FillPortion(2)))
+            .push(input.on_submit(Message::SaveComponent).width(Length::FillPortion(4)))
+            .push(text(unit).width(Length::FillPortion(1)))
             .width(Length::Fill)
     };
 
-    let content = Column::new()
-        .push(create_text_input(
-            "name",
-            &joint.name,
-            Message::RevoluteNameInputChanged,
-        ))
-        .push(create_text_input(
-            "theta",
-            &joint.theta,
-            Message::RevoluteThetaInputChanged,
-        ))
-        .push(create_text_input(
-            "omega",
-            &joint.omega,
-            Message::RevoluteOmegaInputChanged,
-        ))
-        .push(create_text_input(
-            "constant force",
-            &joint.constant_force,
-            Message::RevoluteConstantForceInputChanged,
-        ))
-        .push(create_text_input(
-            "dampening",
-            &joint.dampening,
-            Message::RevoluteDampeningInputChanged,
-        ))
-        .push(create_text_input(
-            "spring constant",
-            &joint.theta,
-            Message::RevoluteSpringConstantInputChanged,
+    let name_input = text_input("name", &dummy.get_name())
+        .id(text_input::Id::new(NAME_INPUT_ID))
+        .on_input(Message::NameInputChanged);
+    let mut fields = Column::new().spacing(5).push(create_row("name", "", name_input));
+    for (index, (field, value)) in dummy.get_schema().iter().zip(dummy.get_values()).enumerate() {
+        let error = dummy.get_field_error(index, context);
+        let style = match error {
+            Some(_) => ui::theme::TextInput::Error,
+            None => ui::theme::TextInput::Default,
+        };
+        let default = field.to_display_value(field.default, context.unit_system);
+        let input = text_input(&default.to_string(), value)
+            .id(text_input::Id::new(format!("field{}", index)))
+            .on_input(move |value| Message::FieldInputChanged(index, value))
+            .style(style);
+        let range = field.get_display_range(context.unit_system);
+        let help = format!("{} ({})", field.help, range.get_description());
+        let unit = field.get_display_unit(context.unit_system);
+        fields = fields.push(tooltip(
+            create_row(field.name, &unit, input),
+            text(help),
+            tooltip::Position::Top,
         ));
+        if let Some(error) = error {
+            fields = fields.push(text(error.get_error_message()).style(ui::theme::Text::Error));
+        }
+    }
+    fields
+}
 
+fn create_component_modal<'a>(
+    dummy: &'a DummyComponent,
+    context: &FieldContext,
+) -> Element<'a, Message, crate::ui::theme::Theme> {
+    let title = dummy.get_title();
+    // no on_press leaves the button disabled
+    let ok = button("Ok").width(Length::Fill);
+    let ok = if dummy.has_invalid_fields(context) {
+        ok
+    } else {
+        ok.on_press(Message::SaveComponent)
+    };
     let footer = Row::new()
         .spacing(10)
         .padding(5)
@@ -747,21 +1527,17 @@ fn create_revolute_modal(joint: &DummyRevolute) -> Element<Message, crate::ui::t
         .push(
             button("Cancel")
                 .width(Length::Fill)
-                .on_press(crate::Message::CloseModal),
+                .on_press(Message::CloseModal),
         )
-        .push(
-            button("Ok")
-                .width(Length::Fill)
-                .on_press(crate::Message::SaveComponent),
-        );
+        .push(ok);
 
-    card("Revolute Information", content)
+    card(title, create_component_fields(dummy, context))
         .foot(footer)
         .max_width(500.0)
         .into()
 }
 
-fn create_error_modal(error: Errors) -> Element<'static, Message, crate::ui::theme::Theme> {
+fn create_error_modal(error: &Errors) -> Element<'static, Message, crate::ui::theme::Theme> {
     let text = text(error.get_error_message());
     let content = Column::new().push(text);
     let footer = Row::new().spacing(10).padding(5).width(Length::Fill).push(
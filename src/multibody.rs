@@ -1,3 +1,4 @@
+use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
 pub mod base;
@@ -6,6 +7,7 @@ pub mod joints;
 pub mod mass_properties;
 
 use crate::ui::dummies::DummyComponent;
+use crate::ui::schema::FieldContext;
 use base::{Base, BaseErrors};
 use body::{Body, BodyErrors};
 use joints::{
@@ -13,12 +15,15 @@ use joints::{
     Joint,
 };
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct MultibodyMeta {
     component_id: Uuid,
     dummy_id: Uuid,
     from_id: Option<Uuid>,
     name: String,
+    /// Field text as typed, empty for components saved before expressions were kept
+    #[serde(default)]
+    expressions: Vec<String>,
     node_id: Uuid,
     system_id: Option<usize>,
     to_id: Vec<Uuid>,
@@ -32,6 +37,7 @@ impl MultibodyMeta {
         Self {
             component_id,
             dummy_id,
+            expressions: Vec::new(),
             from_id,
             name,
             node_id,
@@ -48,24 +54,53 @@ pub trait MultibodyTrait {
     fn delete_to(&mut self, id: Uuid);
     fn get_component_id(&self) -> Uuid;
     fn get_dummy_id(&self) -> Uuid;
+    /// The text each field was entered as, evaluated again whenever the parameters may have changed
+    fn get_expressions(&self) -> &[String];
     fn get_from_id(&self) -> Option<Uuid>;
     fn get_name(&self) -> &str;
     fn get_node_id(&self) -> Uuid;
     fn get_to_id(&self) -> &Vec<Uuid>;
-    fn inherit_from(&mut self, dummy: &DummyComponent);
+    /// Takes the dummy's name and field values, leaving the component untouched if any are invalid
+    fn inherit_from(
+        &mut self,
+        dummy: &DummyComponent,
+        context: &FieldContext,
+    ) -> Result<(), MultibodyErrors>;
     fn set_component_id(&mut self, id: Uuid);
+    fn set_dummy_id(&mut self, id: Uuid);
     fn set_name(&mut self, name: String);
     fn set_node_id(&mut self, id: Uuid);
     fn set_system_id(&mut self, id: usize);
 }
 
+#[derive(Debug, Clone)]
 pub enum MultibodyErrors {
     Base(BaseErrors),
     Body(BodyErrors),
     Revolute(RevoluteErrors),
 }
 
-#[derive(Debug, Clone)]
+impl std::fmt::Display for MultibodyErrors {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MultibodyErrors::Base(error) => write!(f, "{}", error),
+            MultibodyErrors::Body(error) => write!(f, "{}", error),
+            MultibodyErrors::Revolute(error) => write!(f, "{}", error),
+        }
+    }
+}
+
+impl std::error::Error for MultibodyErrors {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            MultibodyErrors::Base(error) => Some(error),
+            MultibodyErrors::Body(error) => Some(error),
+            MultibodyErrors::Revolute(error) => Some(error),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub enum MultibodyComponent {
     Base(Base),
     Body(Body),
@@ -120,6 +155,14 @@ impl MultibodyTrait for MultibodyComponent {
         }
     }
 
+    fn get_expressions(&self) -> &[String] {
+        match self {
+            MultibodyComponent::Base(base) => base.get_expressions(),
+            MultibodyComponent::Body(body) => body.get_expressions(),
+            MultibodyComponent::Joint(joint) => joint.get_expressions(),
+        }
+    }
+
     fn get_from_id(&self) -> Option<Uuid> {
         match self {
             MultibodyComponent::Base(base) => base.get_from_id(),
@@ -152,11 +195,15 @@ impl MultibodyTrait for MultibodyComponent {
         }
     }
 
-    fn inherit_from(&mut self, dummy: &DummyComponent) {
+    fn inherit_from(
+        &mut self,
+        dummy: &DummyComponent,
+        context: &FieldContext,
+    ) -> Result<(), MultibodyErrors> {
         match self {
-            MultibodyComponent::Base(base) => base.inherit_from(dummy),
-            MultibodyComponent::Body(body) => body.inherit_from(dummy),
-            MultibodyComponent::Joint(joint) => joint.inherit_from(dummy),
+            MultibodyComponent::Base(base) => base.inherit_from(dummy, context),
+            MultibodyComponent::Body(body) => body.inherit_from(dummy, context),
+            MultibodyComponent::Joint(joint) => joint.inherit_from(dummy, context),
         }
     }
 
@@ -168,6 +215,14 @@ impl MultibodyTrait for MultibodyComponent {
         }
     }
 
+    fn set_dummy_id(&mut self, id: Uuid) {
+        match self {
+            MultibodyComponent::Base(base) => base.set_dummy_id(id),
+            MultibodyComponent::Body(body) => body.set_dummy_id(id),
+            MultibodyComponent::Joint(joint) => joint.set_dummy_id(id),
+        }
+    }
+
     fn set_name(&mut self, name: String) {
         match self {
             MultibodyComponent::Base(base) => base.set_name(name),
@@ -198,24 +253,25 @@ impl MultibodyComponent {
         component_id: Uuid,
         dummy: &DummyComponent,
         node_id: Uuid,
+        context: &FieldContext,
     ) -> Result<Self, MultibodyErrors> {
         let component = match dummy {
             DummyComponent::Base(dummy) => {
-                let base = match Base::from_dummy(component_id, dummy, node_id) {
+                let base = match Base::from_dummy(component_id, dummy, node_id, context) {
                     Ok(base) => base,
                     Err(error) => return Err(MultibodyErrors::Base(error)),
                 };
                 MultibodyComponent::Base(base)
             }
             DummyComponent::Body(dummy) => {
-                let body = match Body::from_dummy(component_id, dummy, node_id) {
+                let body = match Body::from_dummy(component_id, dummy, node_id, context) {
                     Ok(body) => body,
                     Err(error) => return Err(MultibodyErrors::Body(error)),
                 };
                 MultibodyComponent::Body(body)
             }
             DummyComponent::Revolute(dummy) => {
-                let revolute = match Revolute::from_dummy(component_id, dummy, node_id) {
+                let revolute = match Revolute::from_dummy(component_id, dummy, node_id, context) {
                     Ok(revolute) => revolute,
                     Err(error) => return Err(MultibodyErrors::Revolute(error)),
                 };
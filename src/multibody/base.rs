@@ -1,15 +1,52 @@
-use super::{MultibodyMeta, MultibodyTrait};
+use super::{MultibodyErrors, MultibodyMeta, MultibodyTrait};
 use crate::ui::dummies::{DummyBase, DummyComponent, DummyErrors, DummyTrait};
+use crate::ui::schema::{
+    parse_values, to_stored_texts, FieldContext, FieldErrors, FieldRange, FieldSchema,
+};
+use iced_test::coordinate_systems::{geodetic::Geodetic, CoordinateSystem};
+use serde::{Deserialize, Serialize};
+use std::f64::consts::FRAC_PI_2;
 use uuid::Uuid;
 
-#[derive(Debug, Clone, Copy)]
+/// The editable fields, in the order `get_values` returns them
+pub const BASE_SCHEMA: [FieldSchema; 3] = [
+    FieldSchema::float("latitude", "rad", 0.0, "Geodetic latitude on the WGS-84 ellipsoid")
+        .with_range(FieldRange::Between(-FRAC_PI_2, FRAC_PI_2)),
+    FieldSchema::float("longitude", "rad", 0.0, "Longitude east of the prime meridian"),
+    FieldSchema::float("altitude", "m", 0.0, "Height above the WGS-84 ellipsoid"),
+];
+
+#[derive(Debug, Clone)]
 pub enum BaseErrors {
     DummyErrors(DummyErrors),
+    /// The name of the field and what's wrong with its text
+    InvalidField(&'static str, FieldErrors),
 }
 
-#[derive(Debug, Clone)]
+impl std::fmt::Display for BaseErrors {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            BaseErrors::DummyErrors(error) => write!(f, "{}", error),
+            BaseErrors::InvalidField(field, error) => write!(f, "{}: {}", field, error),
+        }
+    }
+}
+
+impl std::error::Error for BaseErrors {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            BaseErrors::DummyErrors(error) => Some(error),
+            BaseErrors::InvalidField(_, error) => Some(error),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct Base {
     pub meta: MultibodyMeta,
+    /// Where the base sits in the world, entered as geodetic coordinates.
+    /// Planetary systems place the world frame at the center of the earth (ECEF).
+    pub position: CoordinateSystem,
 }
 
 impl Base {
@@ -17,15 +54,40 @@ impl Base {
         component_id: Uuid,        
         dummy: &DummyBase,
         node_id: Uuid,
+        context: &FieldContext,
     ) -> Result<Self, BaseErrors> {
         if dummy.get_name().is_empty() {
             return Err(BaseErrors::DummyErrors(DummyErrors::NameIsEmpty));
         }
 
-        let meta = MultibodyMeta::new(component_id, dummy.get_id(), dummy.get_name(), node_id);
+        let mut meta = MultibodyMeta::new(component_id, dummy.get_id(), dummy.get_name(), node_id);
+        meta.expressions = to_stored_texts(&BASE_SCHEMA, dummy.get_values(), context.unit_system);
 
-        Ok(Self { meta })
+        Ok(Self {
+            meta,
+            position: position_from_dummy(dummy, context)?,
+        })
     }
+
+    /// Returns the field values in `BASE_SCHEMA` order.
+    /// A base placed in a local system isn't on the earth, so it shows the defaults.
+    pub fn get_values(&self) -> [f64; 3] {
+        match self.position.to_geodetic() {
+            Ok(geodetic) => [geodetic.latitude, geodetic.longitude, geodetic.altitude],
+            Err(_) => BASE_SCHEMA.map(|field| field.default),
+        }
+    }
+}
+
+fn position_from_dummy(
+    dummy: &DummyBase,
+    context: &FieldContext,
+) -> Result<CoordinateSystem, BaseErrors> {
+    let [latitude, longitude, altitude] = parse_values(&BASE_SCHEMA, dummy.get_values(), context)
+        .map_err(|(field, error)| BaseErrors::InvalidField(field, error))?;
+    Ok(CoordinateSystem::Geodetic(Geodetic::new(
+        latitude, longitude, altitude,
+    )))
 }
 
 impl MultibodyTrait for Base {
@@ -51,6 +113,10 @@ impl MultibodyTrait for Base {
         self.meta.dummy_id
     }
 
+    fn get_expressions(&self) -> &[String] {
+        &self.meta.expressions
+    }
+
     fn get_from_id(&self) -> Option<Uuid> {
         self.meta.from_id
     }
@@ -67,17 +133,32 @@ impl MultibodyTrait for Base {
         &self.meta.to_id
     }
 
-    fn inherit_from(&mut self, dummy: &DummyComponent) {
+    fn inherit_from(
+        &mut self,
+        dummy: &DummyComponent,
+        context: &FieldContext,
+    ) -> Result<(), MultibodyErrors> {
         match dummy {
-            DummyComponent::Base(_) => {}
+            DummyComponent::Base(dummy_base) => {
+                self.position =
+                    position_from_dummy(dummy_base, context).map_err(MultibodyErrors::Base)?;
+                self.set_name(dummy.get_name());
+                self.meta.expressions =
+                    to_stored_texts(&BASE_SCHEMA, dummy.get_values(), context.unit_system);
+            }
             _ => {} // error! must be dummy base
         }
+        Ok(())
     }
 
     fn set_component_id(&mut self, id: Uuid) {
         self.meta.component_id = id;
     }
 
+    fn set_dummy_id(&mut self, id: Uuid) {
+        self.meta.dummy_id = id;
+    }
+
     fn set_name(&mut self, name: String) {
         self.meta.name = name;
     }
@@ -1,43 +1,69 @@
 use crate::ui::dummies::{DummyBody, DummyComponent, DummyErrors, DummyTrait};
+use crate::ui::schema::{
+    parse_values, to_stored_texts, FieldContext, FieldErrors, FieldRange, FieldSchema,
+};
 
 use super::{
     mass_properties::{MassProperties, MassPropertiesErrors},
-    MultibodyMeta, MultibodyTrait,
+    MultibodyErrors, MultibodyMeta, MultibodyTrait,
 };
+use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
-#[derive(Debug, Clone, Copy)]
-pub enum BodyField {
-    Name,
-    Mass,
-    Cmx,
-    Cmy,
-    Cmz,
-    Ixx,
-    Iyy,
-    Izz,
-    Ixy,
-    Ixz,
-    Iyz,
-}
-
-#[derive(Debug, Clone)]
+/// The editable fields, in the order `get_values` returns them
+pub const BODY_SCHEMA: [FieldSchema; 10] = [
+    FieldSchema::float("mass", "kg", 1.0, "Total mass of the body").with_range(FieldRange::Positive),
+    FieldSchema::float("cmx", "m", 0.0, "Center of mass along x"),
+    FieldSchema::float("cmy", "m", 0.0, "Center of mass along y"),
+    FieldSchema::float("cmz", "m", 0.0, "Center of mass along z"),
+    FieldSchema::float("ixx", "kg*m^2", 1.0, "Moment of inertia about x").with_range(FieldRange::Positive),
+    FieldSchema::float("iyy", "kg*m^2", 1.0, "Moment of inertia about y").with_range(FieldRange::Positive),
+    FieldSchema::float("izz", "kg*m^2", 1.0, "Moment of inertia about z").with_range(FieldRange::Positive),
+    FieldSchema::float("ixy", "kg*m^2", 0.0, "Product of inertia in the xy-plane"),
+    FieldSchema::float("ixz", "kg*m^2", 0.0, "Product of inertia in the xz-plane"),
+    FieldSchema::float("iyz", "kg*m^2", 0.0, "Product of inertia in the yz-plane"),
+];
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct Body {
     mass_properties: MassProperties,
     meta: MultibodyMeta,    
 }
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone)]
 pub enum BodyErrors {
     DummyErrors(DummyErrors),
+    /// The name of the field and what's wrong with its text
+    InvalidField(&'static str, FieldErrors),
     MassPropertiesErrors(MassPropertiesErrors),
 }
 
+impl std::fmt::Display for BodyErrors {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            BodyErrors::DummyErrors(error) => write!(f, "{}", error),
+            BodyErrors::InvalidField(field, error) => write!(f, "{}: {}", field, error),
+            BodyErrors::MassPropertiesErrors(error) => write!(f, "{}", error),
+        }
+    }
+}
+
+impl std::error::Error for BodyErrors {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            BodyErrors::DummyErrors(error) => Some(error),
+            BodyErrors::InvalidField(_, error) => Some(error),
+            BodyErrors::MassPropertiesErrors(error) => Some(error),
+        }
+    }
+}
+
 impl Body {
     pub fn from_dummy(
         component_id: Uuid,        
         dummy: &DummyBody,
-        node_id: Uuid,        
+        node_id: Uuid,
+        context: &FieldContext,
     ) -> Result<Self, BodyErrors> {        
 
         let name = dummy.get_name();
@@ -46,22 +72,9 @@ impl Body {
             return Err(BodyErrors::DummyErrors(DummyErrors::NameIsEmpty))
         }        
 
-        let meta = MultibodyMeta::new(component_id, dummy.get_id(), name, node_id);
-        let mass_properties = match MassProperties::new(
-            dummy.mass.parse().unwrap_or(1.0),
-            dummy.cmx.parse().unwrap_or(0.0),
-            dummy.cmy.parse().unwrap_or(0.0),
-            dummy.cmz.parse().unwrap_or(0.0),
-            dummy.ixx.parse().unwrap_or(1.0),
-            dummy.iyy.parse().unwrap_or(1.0),
-            dummy.izz.parse().unwrap_or(1.0),
-            dummy.ixy.parse().unwrap_or(0.0),
-            dummy.ixz.parse().unwrap_or(0.0),
-            dummy.iyz.parse().unwrap_or(0.0),
-        ) {
-            Ok(mass_properties) => mass_properties,
-            Err(error) => return Err(BodyErrors::MassPropertiesErrors(error)),
-        };
+        let mut meta = MultibodyMeta::new(component_id, dummy.get_id(), name, node_id);
+        let mass_properties = mass_properties_from_dummy(dummy, context)?;
+        meta.expressions = to_stored_texts(&BODY_SCHEMA, dummy.get_values(), context.unit_system);
 
         Ok(Self {
             meta,
@@ -119,62 +132,33 @@ impl Body {
         self.mass_properties.get_mass()
     }        
 
-    fn set_cmx(&mut self, cmx: f64) {
-        self.mass_properties.set_cmx(cmx);        
-    }
-
-    fn set_cmy(&mut self, cmy: f64) {
-        self.mass_properties.set_cmy(cmy);
-    }
-
-    fn set_cmz(&mut self, cmz: f64) {
-        self.mass_properties.set_cmz(cmz);
-    }
-
-    fn set_ixx(&mut self, ixx: f64) -> Result<(),BodyErrors> {
-        match self.mass_properties.set_ixx(ixx) {
-            Err(error) => Err(BodyErrors::MassPropertiesErrors(error)),
-            Ok(_) => Ok(())
-        }
-    }
-
-    fn set_ixy(&mut self, ixy: f64) {
-        self.mass_properties.set_ixy(ixy);
-    }
-
-    fn set_ixz(&mut self, ixz: f64) {
-        self.mass_properties.set_ixz(ixz);
-    }
-
-    fn set_iyy(&mut self, iyy: f64) -> Result<(),BodyErrors> {
-        match self.mass_properties.set_iyy(iyy) {
-            Err(error) => Err(BodyErrors::MassPropertiesErrors(error)),
-            Ok(_) => Ok(())
-        }
+    /// Returns the field values in `BODY_SCHEMA` order
+    pub fn get_values(&self) -> [f64; 10] {
+        [
+            self.get_mass(),
+            self.get_cmx(),
+            self.get_cmy(),
+            self.get_cmz(),
+            self.get_ixx(),
+            self.get_iyy(),
+            self.get_izz(),
+            self.get_ixy(),
+            self.get_ixz(),
+            self.get_iyz(),
+        ]
     }
+}
 
-    fn set_iyz(&mut self, iyz: f64) -> Result<(),BodyErrors> {
-        match self.mass_properties.set_ixx(iyz) {
-            Err(error) => Err(BodyErrors::MassPropertiesErrors(error)),
-            Ok(_) => Ok(())
-        }
-    }
-
-    fn set_izz(&mut self, izz: f64) -> Result<(),BodyErrors> {
-        match self.mass_properties.set_izz(izz) {
-            Err(error) => Err(BodyErrors::MassPropertiesErrors(error)),
-            Ok(_) => Ok(())
-        }
-    }
-
-    fn set_mass(&mut self, mass: f64) -> Result<(),BodyErrors> {
-        match self.mass_properties.set_mass(mass) {
-            Err(error) => Err(BodyErrors::MassPropertiesErrors(error)),
-            Ok(_) => Ok(())
-        }
-    }
-
-
+/// Parses the dummy's fields into mass properties, without falling back on defaults
+fn mass_properties_from_dummy(
+    dummy: &DummyBody,
+    context: &FieldContext,
+) -> Result<MassProperties, BodyErrors> {
+    let [mass, cmx, cmy, cmz, ixx, iyy, izz, ixy, ixz, iyz] =
+        parse_values(&BODY_SCHEMA, dummy.get_values(), context)
+            .map_err(|(field, error)| BodyErrors::InvalidField(field, error))?;
+    MassProperties::new(mass, cmx, cmy, cmz, ixx, iyy, izz, ixy, ixz, iyz)
+        .map_err(BodyErrors::MassPropertiesErrors)
 }
 
 impl MultibodyTrait for Body {
@@ -200,6 +184,10 @@ impl MultibodyTrait for Body {
         self.meta.dummy_id
     }
 
+    fn get_expressions(&self) -> &[String] {
+        &self.meta.expressions
+    }
+
     fn get_from_id(&self) -> Option<Uuid> {
         self.meta.from_id
     }
@@ -216,30 +204,32 @@ impl MultibodyTrait for Body {
         &self.meta.to_id
     }
 
-    //TODO: handle the errors instead of unwrap
-    fn inherit_from(&mut self, dummy: &DummyComponent) {
+    fn inherit_from(
+        &mut self,
+        dummy: &DummyComponent,
+        context: &FieldContext,
+    ) -> Result<(), MultibodyErrors> {
         match dummy {
             DummyComponent::Body(dummy_body) => {
-                self.set_name(dummy.get_name()); 
-                self.set_mass(dummy_body.mass.parse().unwrap_or(1.0)).unwrap();
-                self.set_cmx(dummy_body.cmx.parse().unwrap_or(0.0));
-                self.set_cmy(dummy_body.cmy.parse().unwrap_or(0.0));
-                self.set_cmz(dummy_body.cmz.parse().unwrap_or(0.0));
-                self.set_ixx(dummy_body.ixx.parse().unwrap_or(1.0)).unwrap();
-                self.set_iyy(dummy_body.iyy.parse().unwrap_or(1.0)).unwrap();
-                self.set_izz(dummy_body.izz.parse().unwrap_or(1.0)).unwrap();
-                self.set_ixy(dummy_body.ixy.parse().unwrap_or(0.0));
-                self.set_ixz(dummy_body.ixz.parse().unwrap_or(0.0));
-                self.set_iyz(dummy_body.iyz.parse().unwrap_or(0.0)).unwrap();
+                self.mass_properties = mass_properties_from_dummy(dummy_body, context)
+                    .map_err(MultibodyErrors::Body)?;
+                self.meta.expressions =
+                    to_stored_texts(&BODY_SCHEMA, dummy.get_values(), context.unit_system);
+                self.set_name(dummy.get_name());
             }
             _ => {} //error! must be a body
         }
+        Ok(())
     }
 
     fn set_component_id(&mut self, id: Uuid) {
         self.meta.component_id = id;
     }
 
+    fn set_dummy_id(&mut self, id: Uuid) {
+        self.meta.dummy_id = id;
+    }
+
     fn set_name(&mut self, name: String) {
         self.meta.name = name;
     }
@@ -1,11 +1,13 @@
-use super::MultibodyTrait;
+use super::{MultibodyErrors, MultibodyTrait};
+use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
 pub mod revolute;
 use crate::ui::dummies::DummyComponent;
+use crate::ui::schema::FieldContext;
 use revolute::Revolute;
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub enum Joint {
     //Floating,
     //Prismatic,
@@ -49,6 +51,12 @@ impl MultibodyTrait for Joint {
         }
     }
 
+    fn get_expressions(&self) -> &[String] {
+        match self {
+            Joint::Revolute(revolute) => revolute.get_expressions(),
+        }
+    }
+
     fn get_from_id(&self) -> Option<Uuid> {
         match self {
             Joint::Revolute(revolute) => revolute.get_from_id(),
@@ -73,9 +81,13 @@ impl MultibodyTrait for Joint {
         }
     }
 
-    fn inherit_from(&mut self, dummy: &DummyComponent) {
+    fn inherit_from(
+        &mut self,
+        dummy: &DummyComponent,
+        context: &FieldContext,
+    ) -> Result<(), MultibodyErrors> {
         match self {
-            Joint::Revolute(joint) => joint.inherit_from(dummy),
+            Joint::Revolute(joint) => joint.inherit_from(dummy, context),
         }
     }
     fn set_component_id(&mut self, id: Uuid) {
@@ -84,6 +96,12 @@ impl MultibodyTrait for Joint {
         }
     }
 
+    fn set_dummy_id(&mut self, id: Uuid) {
+        match self {
+            Joint::Revolute(revolute) => revolute.set_dummy_id(id),
+        }
+    }
+
     fn set_name(&mut self, name: String) {
         match self {
             Joint::Revolute(revolute) => revolute.set_name(name),
@@ -103,7 +121,7 @@ impl MultibodyTrait for Joint {
     }
 }
 
-#[derive(Debug, Default, Clone, Copy)]
+#[derive(Debug, Default, Clone, Copy, Serialize, Deserialize, PartialEq)]
 pub struct JointParameters {
     pub constant_force: f64,
     pub dampening: f64,
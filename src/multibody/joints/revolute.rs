@@ -1,18 +1,23 @@
-use crate::multibody::{joints::JointParameters, MultibodyMeta, MultibodyTrait};
+use crate::multibody::{joints::JointParameters, MultibodyErrors, MultibodyMeta, MultibodyTrait};
 use crate::ui::dummies::{DummyComponent, DummyErrors, DummyRevolute, DummyTrait};
+use crate::ui::schema::{
+    parse_values, to_stored_texts, FieldContext, FieldErrors, FieldRange, FieldSchema,
+};
+use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
-#[derive(Debug, Clone, Copy)]
-pub enum RevoluteField {
-    Name,
-    ConstantForce,
-    Dampening,
-    Omega,
-    SpringConstant,
-    Theta,
-}
-
-#[derive(Debug, Clone, Copy)]
+/// The editable fields, in the order `get_values` returns them
+pub const REVOLUTE_SCHEMA: [FieldSchema; 5] = [
+    FieldSchema::float("theta", "rad", 0.0, "Initial joint angle"),
+    FieldSchema::float("omega", "rad/s", 0.0, "Initial joint rate"),
+    FieldSchema::float("constant force", "N*m", 0.0, "Torque applied at all times"),
+    FieldSchema::float("dampening", "N*m*s/rad", 0.0, "Torque opposing the joint rate")
+        .with_range(FieldRange::NonNegative),
+    FieldSchema::float("spring constant", "N*m/rad", 0.0, "Torque pulling the joint back to zero")
+        .with_range(FieldRange::NonNegative),
+];
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
 pub struct RevoluteState {
     pub theta: f64,
     pub omega: f64,
@@ -24,11 +29,32 @@ impl RevoluteState {
     }
 }
 
+#[derive(Debug, Clone)]
 pub enum RevoluteErrors {
     DummyErrors(DummyErrors),
+    /// The name of the field and what's wrong with its text
+    InvalidField(&'static str, FieldErrors),
 }
 
-#[derive(Debug, Clone)]
+impl std::fmt::Display for RevoluteErrors {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RevoluteErrors::DummyErrors(error) => write!(f, "{}", error),
+            RevoluteErrors::InvalidField(field, error) => write!(f, "{}: {}", field, error),
+        }
+    }
+}
+
+impl std::error::Error for RevoluteErrors {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            RevoluteErrors::DummyErrors(error) => Some(error),
+            RevoluteErrors::InvalidField(_, error) => Some(error),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct Revolute {
     pub meta: MultibodyMeta,
     pub parameters: JointParameters,
@@ -40,6 +66,7 @@ impl Revolute {
         component_id: Uuid,
         dummy: &DummyRevolute,
         node_id: Uuid,
+        context: &FieldContext,
     ) -> Result<Self, RevoluteErrors> {
         let name = dummy.get_name();
 
@@ -47,17 +74,15 @@ impl Revolute {
             return Err(RevoluteErrors::DummyErrors(DummyErrors::NameIsEmpty));
         }
 
-        let meta = MultibodyMeta::new(component_id, dummy.get_id(), name, node_id);
+        let mut meta = MultibodyMeta::new(component_id, dummy.get_id(), name, node_id);
+        meta.expressions =
+            to_stored_texts(&REVOLUTE_SCHEMA, dummy.get_values(), context.unit_system);
 
-        let state = RevoluteState::new(
-            dummy.theta.parse().unwrap_or(0.0),
-            dummy.omega.parse().unwrap_or(0.0),
-        );
-        let parameters = JointParameters::new(
-            dummy.constant_force.parse().unwrap_or(0.0),
-            dummy.dampening.parse().unwrap_or(0.0),
-            dummy.spring_constant.parse().unwrap_or(0.0),
-        );
+        let [theta, omega, constant_force, dampening, spring_constant] =
+            parse_values(&REVOLUTE_SCHEMA, dummy.get_values(), context)
+                .map_err(|(field, error)| RevoluteErrors::InvalidField(field, error))?;
+        let state = RevoluteState::new(theta, omega);
+        let parameters = JointParameters::new(constant_force, dampening, spring_constant);
 
         Ok(Self {
             meta: meta,
@@ -65,6 +90,17 @@ impl Revolute {
             state: state,
         })
     }
+
+    /// Returns the field values in `REVOLUTE_SCHEMA` order
+    pub fn get_values(&self) -> [f64; 5] {
+        [
+            self.state.theta,
+            self.state.omega,
+            self.parameters.constant_force,
+            self.parameters.dampening,
+            self.parameters.spring_constant,
+        ]
+    }
 }
 
 impl MultibodyTrait for Revolute {
@@ -91,6 +127,10 @@ impl MultibodyTrait for Revolute {
         self.meta.dummy_id
     }
 
+    fn get_expressions(&self) -> &[String] {
+        &self.meta.expressions
+    }
+
     fn get_from_id(&self) -> Option<Uuid> {
         self.meta.from_id
     }
@@ -107,17 +147,38 @@ impl MultibodyTrait for Revolute {
         &self.meta.to_id
     }
 
-    fn inherit_from(&mut self, dummy: &DummyComponent) {
+    fn inherit_from(
+        &mut self,
+        dummy: &DummyComponent,
+        context: &FieldContext,
+    ) -> Result<(), MultibodyErrors> {
         match dummy {
-            DummyComponent::Revolute(_) => {}
-            _ => {} // error! must be dummy base
+            DummyComponent::Revolute(dummy_revolute) => {
+                let [theta, omega, constant_force, dampening, spring_constant] =
+                    parse_values(&REVOLUTE_SCHEMA, dummy_revolute.get_values(), context).map_err(
+                        |(field, error)| {
+                            MultibodyErrors::Revolute(RevoluteErrors::InvalidField(field, error))
+                        },
+                    )?;
+                self.set_name(dummy.get_name());
+                self.meta.expressions =
+                    to_stored_texts(&REVOLUTE_SCHEMA, dummy.get_values(), context.unit_system);
+                self.state = RevoluteState::new(theta, omega);
+                self.parameters = JointParameters::new(constant_force, dampening, spring_constant);
+            }
+            _ => {} // error! must be dummy revolute
         }
+        Ok(())
     }
 
     fn set_component_id(&mut self, id: Uuid) {
         self.meta.component_id = id;
     }
 
+    fn set_dummy_id(&mut self, id: Uuid) {
+        self.meta.dummy_id = id;
+    }
+
     fn set_name(&mut self, name: String) {
         self.meta.name = name;
     }
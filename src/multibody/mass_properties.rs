@@ -1,4 +1,6 @@
-#[derive(Debug, Clone, Copy)]
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
 struct CenterOfMass {
     cmx: f64,
     cmy: f64,
@@ -24,24 +26,9 @@ impl CenterOfMass {
     pub fn get_cmz(&self) -> f64 {
         self.cmz
     }
-
-    /// Sets the x-coordinate of the center of mass.
-    pub fn set_cmx(&mut self, cmx: f64) {
-        self.cmx = cmx;
-    }
-
-    /// Sets the y-coordinate of the center of mass.
-    pub fn set_cmy(&mut self, cmy: f64) {
-        self.cmy = cmy;
-    }
-
-    /// Sets the z-coordinate of the center of mass.
-    pub fn set_cmz(&mut self, cmz: f64) {
-        self.cmz = cmz;
-    }
 }
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
 struct Inertia {
     ixx: f64,
     ixy: f64,
@@ -58,6 +45,19 @@ enum InertiaErrors {
     IzzLessThanOrEqualToZero,
 }
 
+impl std::fmt::Display for InertiaErrors {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let moment = match self {
+            InertiaErrors::IxxLessThanOrEqualToZero => "ixx",
+            InertiaErrors::IyyLessThanOrEqualToZero => "iyy",
+            InertiaErrors::IzzLessThanOrEqualToZero => "izz",
+        };
+        write!(f, "{} must be greater than 0.", moment)
+    }
+}
+
+impl std::error::Error for InertiaErrors {}
+
 impl Inertia {
     fn new(
         ixx: f64,
@@ -104,60 +104,11 @@ impl Inertia {
     fn get_iyz(&self) -> f64 {
         self.iyz
     }
-
-    pub fn set_ixx(&mut self, ixx: f64) -> Result<(), InertiaErrors> {
-        if ixx <= 0.0 {
-            return Err(InertiaErrors::IxxLessThanOrEqualToZero);
-        }
-        self.ixx = ixx;
-        Ok(())
-    }
-
-    /// Sets the product of inertia for the xy-plane.
-    pub fn set_ixy(&mut self, ixy: f64) {
-        self.ixy = ixy;
-    }
-
-    /// Sets the product of inertia for the xz-plane.
-    pub fn set_ixz(&mut self, ixz: f64) {
-        self.ixz = ixz;
-    }
-
-    /// Sets the moment of inertia around the y-axis.
-    ///
-    /// # Errors
-    ///
-    /// Returns a `MassPropertiesError::IyyLessThanOrEqualToZero` if `iyy` is less than or equal to zero.
-    pub fn set_iyy(&mut self, iyy: f64) -> Result<(), InertiaErrors> {
-        if iyy <= 0.0 {
-            return Err(InertiaErrors::IyyLessThanOrEqualToZero);
-        }
-        self.iyy = iyy;
-        Ok(())
-    }
-
-    /// Sets the product of inertia for the yz-plane.
-    pub fn set_iyz(&mut self, iyz: f64) {
-        self.iyz = iyz;
-    }
-
-    /// Sets the moment of inertia around the z-axis.
-    ///
-    /// # Errors
-    ///
-    /// Returns a `MassPropertiesError::IzzLessThanOrEqualToZero` if `izz` is less than or equal to zero.
-    pub fn set_izz(&mut self, izz: f64) -> Result<(), InertiaErrors> {
-        if izz <= 0.0 {
-            return Err(InertiaErrors::IzzLessThanOrEqualToZero);
-        }
-        self.izz = izz;
-        Ok(())
-    }
 }
 
 /// Represents the mass properties of an object
 /// Mass, Center of Mass, Inertia
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
 pub struct MassProperties {
     center_of_mass: CenterOfMass,
     mass: f64,
@@ -171,6 +122,24 @@ pub enum MassPropertiesErrors {
     MassLessThanOrEqualToZero,
 }
 
+impl std::fmt::Display for MassPropertiesErrors {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MassPropertiesErrors::Inertia(error) => write!(f, "{}", error),
+            MassPropertiesErrors::MassLessThanOrEqualToZero => write!(f, "mass must be greater than 0."),
+        }
+    }
+}
+
+impl std::error::Error for MassPropertiesErrors {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            MassPropertiesErrors::Inertia(error) => Some(error),
+            MassPropertiesErrors::MassLessThanOrEqualToZero => None,
+        }
+    }
+}
+
 impl MassProperties {
     /// Creates a new `MassProperties` instance.
     ///
@@ -270,83 +239,4 @@ impl MassProperties {
     pub fn get_mass(&self) -> f64 {
         self.mass
     }
-
-    /// Sets the x-coordinate of the center of mass.
-    pub fn set_cmx(&mut self, cmx: f64) {
-        self.set_cmx(cmx);
-    }
-
-    /// Sets the y-coordinate of the center of mass.
-    pub fn set_cmy(&mut self, cmy: f64) {
-        self.set_cmy(cmy);
-    }
-
-    /// Sets the z-coordinate of the center of mass.
-    pub fn set_cmz(&mut self, cmz: f64) {
-        self.set_cmz(cmz);
-    }
-
-    /// Sets the moment of inertia around the x-axis.
-    ///
-    /// # Errors
-    ///
-    /// Returns a `MassPropertiesError::IxxLessThanOrEqualToZero` if `ixx` is less than or equal to zero.
-    pub fn set_ixx(&mut self, ixx: f64) -> Result<(), MassPropertiesErrors> {
-        match self.inertia.set_ixx(ixx) {
-            Ok(_) => Ok(()),
-            Err(error) => Err(MassPropertiesErrors::Inertia(error)),
-        }
-    }
-
-    /// Sets the product of inertia for the xy-plane.
-    pub fn set_ixy(&mut self, ixy: f64) {
-        self.inertia.set_ixy(ixy);        
-    }
-
-    /// Sets the product of inertia for the xz-plane.
-    pub fn set_ixz(&mut self, ixz: f64) {
-        self.inertia.set_ixz(ixz);
-    }
-    /// Sets the moment of inertia around the y-axis.
-    ///
-    /// # Errors
-    ///
-    /// Returns a `MassPropertiesError::IyyLessThanOrEqualToZero` if `iyy` is less than or equal to zero.
-    pub fn set_iyy(&mut self, iyy: f64) -> Result<(), MassPropertiesErrors> {        
-        match self.inertia.set_iyy(iyy) {
-        Ok(_) => Ok(()),
-        Err(error) => Err(MassPropertiesErrors::Inertia(error))
-        }        
-    }
-
-    /// Sets the product of inertia for the yz-plane.
-    pub fn set_iyz(&mut self, iyz: f64) -> Result<(), MassPropertiesErrors> {
-        self.inertia.set_iyz(iyz);
-        Ok(())
-    }
-
-    /// Sets the moment of inertia around the z-axis.
-    ///
-    /// # Errors
-    ///
-    /// Returns a `MassPropertiesError::IzzLessThanOrEqualToZero` if `izz` is less than or equal to zero.
-    pub fn set_izz(&mut self, izz: f64) -> Result<(), MassPropertiesErrors> {        
-        match self.inertia.set_izz(izz) {
-        Ok(_) => Ok(()),
-        Err(error) => Err(MassPropertiesErrors::Inertia(error))
-        }        
-    }
-
-    /// Sets the mass of the object.
-    ///
-    /// # Errors
-    ///
-    /// Returns a `MassPropertiesError::MassLessThanOrEqualToZero` if `mass` is less than or equal to zero.
-    pub fn set_mass(&mut self, mass: f64) -> Result<(), MassPropertiesErrors> {
-        if mass <= 0.0 {
-            return Err(MassPropertiesErrors::MassLessThanOrEqualToZero);
-        }
-        self.mass = mass;
-        Ok(())
-    }
 }
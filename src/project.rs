@@ -0,0 +1,207 @@
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+use uuid::Uuid;
+
+use crate::multibody::MultibodyComponent;
+use crate::ui::canvas::edge::Edge;
+use crate::ui::canvas::graph::GraphNode;
+use crate::ui::canvas::grid::GridSettings;
+use crate::ui::parameters::Parameters;
+use crate::ui::units::UnitSystem;
+
+/// Format version written to every project file.
+/// Bump this when a change makes older files unreadable.
+pub const PROJECT_VERSION: u32 = 1;
+
+#[derive(Debug, Clone, Copy)]
+pub enum ProjectErrors {
+    Io(std::io::ErrorKind),
+    Parse { line: usize, col: usize },
+    Serialize,
+    UnsupportedVersion(u32),
+}
+
+impl std::fmt::Display for ProjectErrors {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ProjectErrors::Io(kind) => write!(f, "Could not read or write the project file ({}).", kind),
+            ProjectErrors::Parse { line, col } => write!(
+                f,
+                "The project file is corrupt and could not be opened at line {}, column {}.",
+                line, col
+            ),
+            ProjectErrors::Serialize => write!(f, "The project could not be converted for saving."),
+            ProjectErrors::UnsupportedVersion(version) => write!(
+                f,
+                "The project file was saved by a newer version ({}) and cannot be opened.",
+                version
+            ),
+        }
+    }
+}
+
+impl std::error::Error for ProjectErrors {}
+
+/// Everything the editor needs to restore a model, saved as RON
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Project {
+    pub version: u32,
+    counter_body: usize,
+    counter_revolute: usize,
+    pub components: HashMap<Uuid, MultibodyComponent>,
+    pub edges: HashMap<Uuid, Edge>,
+    pub nodes: HashMap<Uuid, GraphNode>,
+    /// Files saved before the grid existed get the default settings
+    #[serde(default)]
+    pub grid: GridSettings,
+    #[serde(default)]
+    pub parameters: Parameters,
+    #[serde(default)]
+    pub unit_system: UnitSystem,
+    /// Components the simulation reports results for
+    #[serde(default)]
+    pub outputs: HashSet<Uuid>,
+}
+
+/// Read first so files from a newer version fail with a clear error instead of a parse error
+#[derive(Deserialize)]
+struct ProjectHeader {
+    version: u32,
+}
+
+impl Project {
+    #[allow(clippy::too_many_arguments)]
+    /// `name_counters` holds the last number given to an unnamed component, by name prefix
+    pub fn new(
+        name_counters: &HashMap<String, usize>,
+        components: HashMap<Uuid, MultibodyComponent>,
+        edges: HashMap<Uuid, Edge>,
+        nodes: HashMap<Uuid, GraphNode>,
+        grid: GridSettings,
+        parameters: Parameters,
+        unit_system: UnitSystem,
+        outputs: HashSet<Uuid>,
+    ) -> Self {
+        Self {
+            version: PROJECT_VERSION,
+            counter_body: name_counters.get("body").copied().unwrap_or(0),
+            counter_revolute: name_counters.get("revolute").copied().unwrap_or(0),
+            components,
+            edges,
+            nodes,
+            grid,
+            parameters,
+            unit_system,
+            outputs,
+        }
+    }
+
+    /// The counters `new` was given, by name prefix
+    pub fn get_name_counters(&self) -> HashMap<String, usize> {
+        HashMap::from([
+            ("body".to_string(), self.counter_body),
+            ("revolute".to_string(), self.counter_revolute),
+        ])
+    }
+
+    pub fn from_ron(contents: &str) -> Result<Self, ProjectErrors> {
+        let parse_error = |error: ron::error::SpannedError| ProjectErrors::Parse {
+            line: error.position.line,
+            col: error.position.col,
+        };
+
+        let header: ProjectHeader = ron::from_str(contents).map_err(parse_error)?;
+        if header.version > PROJECT_VERSION {
+            return Err(ProjectErrors::UnsupportedVersion(header.version));
+        }
+        ron::from_str(contents).map_err(parse_error)
+    }
+
+    pub fn to_ron(&self) -> Result<String, ProjectErrors> {
+        ron::ser::to_string_pretty(self, ron::ser::PrettyConfig::default())
+            .map_err(|_| ProjectErrors::Serialize)
+    }
+
+    pub fn load(path: &Path) -> Result<Self, ProjectErrors> {
+        let contents = std::fs::read_to_string(path).map_err(|error| ProjectErrors::Io(error.kind()))?;
+        Self::from_ron(&contents)
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), ProjectErrors> {
+        std::fs::write(path, self.to_ron()?).map_err(|error| ProjectErrors::Io(error.kind()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::multibody::MultibodyTrait;
+    use crate::ui::canvas::edge::EdgeConnection;
+    use crate::ui::canvas::graph::test_utils::add_component;
+    use crate::ui::dummies::{DummyBase, DummyComponent};
+    use crate::ui::parameters::Parameter;
+    use iced::Point;
+
+    #[test]
+    fn test_project_round_trip() {
+        let mut components = HashMap::new();
+        let mut nodes = HashMap::new();
+        let dummy = DummyComponent::Base(DummyBase::new(Uuid::new_v4()));
+        let (component_id, node_id) =
+            add_component(&mut components, &mut nodes, "base", dummy, Point::new(10.0, 20.0));
+        let bounds = nodes[&node_id].node.bounds;
+        let edge_id = Uuid::new_v4();
+        nodes.get_mut(&node_id).unwrap().edges.push(edge_id);
+
+        let edge = Edge::new(
+            EdgeConnection::Node(node_id),
+            EdgeConnection::Point(Point::new(3.0, 4.0)),
+        );
+
+        let name_counters = HashMap::from([("body".to_string(), 2), ("revolute".to_string(), 5)]);
+        let project = Project::new(
+            &name_counters,
+            components,
+            HashMap::from([(edge_id, edge)]),
+            nodes,
+            GridSettings {
+                is_snapping: true,
+                is_visible: false,
+                spacing: 40.0,
+            },
+            Parameters {
+                entries: vec![Parameter {
+                    name: "m".to_string(),
+                    expression: "2 * pi".to_string(),
+                }],
+            },
+            UnitSystem::Imperial,
+            HashSet::from([component_id]),
+        );
+        let loaded = Project::from_ron(&project.to_ron().unwrap()).unwrap();
+
+        assert_eq!(loaded.version, PROJECT_VERSION);
+        assert_eq!(loaded.get_name_counters(), name_counters);
+        assert_eq!(loaded.components[&component_id].get_name(), "base");
+        assert_eq!(loaded.nodes[&node_id].node.bounds, bounds);
+        assert!(loaded.grid.is_snapping && !loaded.grid.is_visible);
+        assert_eq!(loaded.grid.spacing, 40.0);
+        assert_eq!(loaded.parameters.entries[0].expression, "2 * pi");
+        assert_eq!(loaded.unit_system, UnitSystem::Imperial);
+        assert!(loaded.outputs.contains(&component_id));
+        assert!(matches!(
+            loaded.edges[&edge_id].to,
+            EdgeConnection::Point(point) if point == Point::new(3.0, 4.0)
+        ));
+    }
+
+    #[test]
+    fn test_project_newer_version() {
+        let contents = format!("(version: {})", PROJECT_VERSION + 1);
+        assert!(matches!(
+            Project::from_ron(&contents),
+            Err(ProjectErrors::UnsupportedVersion(_))
+        ));
+    }
+}
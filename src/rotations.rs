@@ -1,16 +1,245 @@
+pub mod euler_angles;
+pub mod mrp;
 pub mod quaternion;
 pub mod rotation_matrix;
+use crate::linear_algebra::Vector3;
+use euler_angles::EulerAngles;
+use mrp::Mrp;
 use quaternion::Quaternion;
 use rotation_matrix::RotationMatrix;
+
+/// Common interface for all attitude representations.
+/// `rotate` actively rotates a vector, `transform` expresses it in the rotated frame.
+pub trait RotationTrait {
+    fn compose(&self, rhs: &Self) -> Self;
+    fn identity() -> Self;
+    fn inv(&self) -> Self;
+    fn rotate(&self, v: Vector3) -> Vector3;
+    fn transform(&self, v: Vector3) -> Vector3;
+}
+
 #[derive(Debug, Copy, Clone)]
 pub enum Rotation {
+    EulerAngles(EulerAngles),
+    Mrp(Mrp),
     Quaternion(Quaternion),
     RotationMatrix(RotationMatrix),
-
 }
 
 impl Default for Rotation {
     fn default() -> Self {
         Rotation::Quaternion(Quaternion::identity())
     }
-}
\ No newline at end of file
+}
+
+impl From<Rotation> for Quaternion {
+    fn from(rotation: Rotation) -> Self {
+        match rotation {
+            Rotation::EulerAngles(euler) => Quaternion::from(euler),
+            Rotation::Mrp(mrp) => Quaternion::from(mrp),
+            Rotation::Quaternion(q) => q,
+            Rotation::RotationMatrix(m) => Quaternion::from(m),
+        }
+    }
+}
+
+impl RotationTrait for Rotation {
+    /// Composes in the representation of `self`, converting `rhs` if it differs
+    fn compose(&self, rhs: &Self) -> Self {
+        match (self, rhs) {
+            (Rotation::EulerAngles(a), Rotation::EulerAngles(b)) => {
+                Rotation::EulerAngles(a.compose(b))
+            }
+            (Rotation::Mrp(a), Rotation::Mrp(b)) => Rotation::Mrp(a.compose(b)),
+            (Rotation::Quaternion(a), Rotation::Quaternion(b)) => {
+                Rotation::Quaternion(a.compose(b))
+            }
+            (Rotation::RotationMatrix(a), Rotation::RotationMatrix(b)) => {
+                Rotation::RotationMatrix(a.compose(b))
+            }
+            (Rotation::EulerAngles(a), _) => Rotation::EulerAngles(EulerAngles::from_quaternion(
+                Quaternion::from(*a) * Quaternion::from(*rhs),
+                a.sequence,
+            )),
+            (Rotation::Mrp(a), _) => Rotation::Mrp(a.compose(&Mrp::from(Quaternion::from(*rhs)))),
+            (Rotation::Quaternion(a), _) => Rotation::Quaternion(*a * Quaternion::from(*rhs)),
+            (Rotation::RotationMatrix(a), _) => {
+                Rotation::RotationMatrix(*a * RotationMatrix::from(Quaternion::from(*rhs)))
+            }
+        }
+    }
+
+    fn identity() -> Self {
+        Self::default()
+    }
+
+    fn inv(&self) -> Self {
+        match self {
+            Rotation::EulerAngles(euler) => Rotation::EulerAngles(euler.inv()),
+            Rotation::Mrp(mrp) => Rotation::Mrp(mrp.inv()),
+            Rotation::Quaternion(q) => Rotation::Quaternion(q.inv()),
+            Rotation::RotationMatrix(m) => Rotation::RotationMatrix(m.inv()),
+        }
+    }
+
+    fn rotate(&self, v: Vector3) -> Vector3 {
+        match self {
+            Rotation::EulerAngles(euler) => euler.rotate(v),
+            Rotation::Mrp(mrp) => mrp.rotate(v),
+            Rotation::Quaternion(q) => q.rotate(v),
+            Rotation::RotationMatrix(m) => m.rotate(v),
+        }
+    }
+
+    fn transform(&self, v: Vector3) -> Vector3 {
+        match self {
+            Rotation::EulerAngles(euler) => euler.transform(v),
+            Rotation::Mrp(mrp) => mrp.transform(v),
+            Rotation::Quaternion(q) => q.transform(v),
+            Rotation::RotationMatrix(m) => m.transform(v),
+        }
+    }
+}
+
+impl RotationTrait for EulerAngles {
+    fn compose(&self, rhs: &Self) -> Self {
+        EulerAngles::from_quaternion(
+            Quaternion::from(*self) * Quaternion::from(*rhs),
+            self.sequence,
+        )
+    }
+
+    fn identity() -> Self {
+        EulerAngles::identity()
+    }
+
+    fn inv(&self) -> Self {
+        EulerAngles::inv(self)
+    }
+
+    fn rotate(&self, v: Vector3) -> Vector3 {
+        EulerAngles::rotate(self, v)
+    }
+
+    fn transform(&self, v: Vector3) -> Vector3 {
+        EulerAngles::transform(self, v)
+    }
+}
+
+impl RotationTrait for Mrp {
+    fn compose(&self, rhs: &Self) -> Self {
+        Mrp::compose(self, rhs)
+    }
+
+    fn identity() -> Self {
+        Mrp::identity()
+    }
+
+    fn inv(&self) -> Self {
+        Mrp::inv(self)
+    }
+
+    fn rotate(&self, v: Vector3) -> Vector3 {
+        Mrp::rotate(self, v)
+    }
+
+    fn transform(&self, v: Vector3) -> Vector3 {
+        Mrp::transform(self, v)
+    }
+}
+
+impl RotationTrait for Quaternion {
+    fn compose(&self, rhs: &Self) -> Self {
+        *self * *rhs
+    }
+
+    fn identity() -> Self {
+        Quaternion::identity()
+    }
+
+    fn inv(&self) -> Self {
+        Quaternion::inv(self)
+    }
+
+    fn rotate(&self, v: Vector3) -> Vector3 {
+        Quaternion::rotate(self, v)
+    }
+
+    fn transform(&self, v: Vector3) -> Vector3 {
+        Quaternion::transform(self, v)
+    }
+}
+
+impl RotationTrait for RotationMatrix {
+    fn compose(&self, rhs: &Self) -> Self {
+        *self * *rhs
+    }
+
+    fn identity() -> Self {
+        RotationMatrix::identity()
+    }
+
+    fn inv(&self) -> Self {
+        RotationMatrix::inv(self)
+    }
+
+    fn rotate(&self, v: Vector3) -> Vector3 {
+        RotationMatrix::rotate(self, v)
+    }
+
+    fn transform(&self, v: Vector3) -> Vector3 {
+        RotationMatrix::transform(self, v)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::euler_angles::EulerSequence;
+    use super::*;
+    use crate::test_utils::assert_vector_eq;
+
+    fn all_representations(q: Quaternion) -> Vec<Rotation> {
+        vec![
+            Rotation::EulerAngles(EulerAngles::from_quaternion(q, EulerSequence::XYZ)),
+            Rotation::EulerAngles(EulerAngles::from_quaternion(q, EulerSequence::ZYX)),
+            Rotation::Mrp(Mrp::from(q)),
+            Rotation::Quaternion(q),
+            Rotation::RotationMatrix(RotationMatrix::from(q)),
+        ]
+    }
+
+    #[test]
+    fn test_rotation_representations_agree() {
+        let q = Quaternion::new(0.18119546436307749, 0.4381103371317225, 0.10015469662419728, 0.8747551502773175);
+        let v = Vector3::new(1.0, 2.0, -3.0);
+
+        for rotation in all_representations(q) {
+            assert_vector_eq(rotation.rotate(v), q.rotate(v));
+            assert_vector_eq(rotation.transform(v), q.transform(v));
+            assert_vector_eq(rotation.inv().rotate(v), q.transform(v));
+        }
+    }
+
+    #[test]
+    fn test_rotation_compose() {
+        let q1 = Quaternion::new(0.18119546436307749, 0.4381103371317225, 0.10015469662419728, 0.8747551502773175);
+        let q2 = Quaternion::new(0.4605004692970668, -0.13901506620501594, -0.7574522634418864, -0.44145237314115715);
+        let v = Vector3::new(-0.5, 4.0, 1.0);
+        let expected = q1.rotate(q2.rotate(v));
+
+        for a in all_representations(q1) {
+            for b in all_representations(q2) {
+                assert_vector_eq(a.compose(&b).rotate(v), expected);
+            }
+        }
+    }
+
+    #[test]
+    fn test_rotation_identity() {
+        let v = Vector3::new(1.0, 2.0, 3.0);
+        assert_vector_eq(Rotation::identity().rotate(v), v);
+        assert_vector_eq(EulerAngles::identity().rotate(v), v);
+        assert_vector_eq(Mrp::identity().rotate(v), v);
+        assert_vector_eq(RotationMatrix::identity().rotate(v), v);
+    }
+}
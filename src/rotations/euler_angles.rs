@@ -0,0 +1,106 @@
+use crate::linear_algebra::Vector3;
+use super::quaternion::Quaternion;
+use super::rotation_matrix::RotationMatrix;
+
+/// The order the elementary rotations are applied in, e.g. `ZYX` rotates
+/// about z by `phi`, then about the new y by `theta`, then about the new x by `psi`
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub enum EulerSequence {
+    XYZ,
+    #[default]
+    ZYX,
+}
+
+#[derive(Debug, Default, Clone, Copy)]
+pub struct EulerAngles {
+    pub phi: f64,
+    pub theta: f64,
+    pub psi: f64,
+    pub sequence: EulerSequence,
+}
+
+impl EulerAngles {
+    pub fn new(phi: f64, theta: f64, psi: f64, sequence: EulerSequence) -> Self {
+        Self {
+            phi,
+            theta,
+            psi,
+            sequence,
+        }
+    }
+
+    pub fn identity() -> Self {
+        Self::default()
+    }
+
+    /// Converts the quaternion to euler angles of the given sequence.
+    /// Near gimbal lock the split between `phi` and `psi` is arbitrary.
+    pub fn from_quaternion(q: Quaternion, sequence: EulerSequence) -> Self {
+        let m = RotationMatrix::from(q);
+        match sequence {
+            EulerSequence::XYZ => Self::new(
+                (-m.e23).atan2(m.e33),
+                m.e13.clamp(-1.0, 1.0).asin(),
+                (-m.e12).atan2(m.e11),
+                sequence,
+            ),
+            EulerSequence::ZYX => Self::new(
+                m.e21.atan2(m.e11),
+                (-m.e31).clamp(-1.0, 1.0).asin(),
+                m.e32.atan2(m.e33),
+                sequence,
+            ),
+        }
+    }
+
+    pub fn inv(&self) -> Self {
+        Self::from_quaternion(Quaternion::from(*self).inv(), self.sequence)
+    }
+
+    pub fn rotate(&self, v: Vector3) -> Vector3 {
+        // the first rotation in the sequence is the outermost
+        match self.sequence {
+            EulerSequence::XYZ => rotate_x(self.phi, rotate_y(self.theta, rotate_z(self.psi, v))),
+            EulerSequence::ZYX => rotate_z(self.phi, rotate_y(self.theta, rotate_x(self.psi, v))),
+        }
+    }
+
+    pub fn transform(&self, v: Vector3) -> Vector3 {
+        match self.sequence {
+            EulerSequence::XYZ => {
+                rotate_z(-self.psi, rotate_y(-self.theta, rotate_x(-self.phi, v)))
+            }
+            EulerSequence::ZYX => {
+                rotate_x(-self.psi, rotate_y(-self.theta, rotate_z(-self.phi, v)))
+            }
+        }
+    }
+}
+
+impl From<EulerAngles> for Quaternion {
+    fn from(euler: EulerAngles) -> Self {
+        let qx = |angle: f64| Quaternion::new((angle / 2.0).sin(), 0.0, 0.0, (angle / 2.0).cos());
+        let qy = |angle: f64| Quaternion::new(0.0, (angle / 2.0).sin(), 0.0, (angle / 2.0).cos());
+        let qz = |angle: f64| Quaternion::new(0.0, 0.0, (angle / 2.0).sin(), (angle / 2.0).cos());
+
+        match euler.sequence {
+            EulerSequence::XYZ => qx(euler.phi) * qy(euler.theta) * qz(euler.psi),
+            EulerSequence::ZYX => qz(euler.phi) * qy(euler.theta) * qx(euler.psi),
+        }
+    }
+}
+
+fn rotate_x(angle: f64, v: Vector3) -> Vector3 {
+    let (sin, cos) = angle.sin_cos();
+    Vector3::new(v.e1, cos * v.e2 - sin * v.e3, sin * v.e2 + cos * v.e3)
+}
+
+fn rotate_y(angle: f64, v: Vector3) -> Vector3 {
+    let (sin, cos) = angle.sin_cos();
+    Vector3::new(cos * v.e1 + sin * v.e3, v.e2, -sin * v.e1 + cos * v.e3)
+}
+
+fn rotate_z(angle: f64, v: Vector3) -> Vector3 {
+    let (sin, cos) = angle.sin_cos();
+    Vector3::new(cos * v.e1 - sin * v.e2, sin * v.e1 + cos * v.e2, v.e3)
+}
@@ -0,0 +1,90 @@
+use crate::linear_algebra::Vector3;
+use super::quaternion::Quaternion;
+
+/// Modified Rodrigues Parameters, sigma = e * tan(angle / 4).
+/// Always kept on the short rotation (|sigma| <= 1) by switching to the shadow set.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct Mrp {
+    pub sigma: Vector3,
+}
+
+impl Mrp {
+    pub fn new(s1: f64, s2: f64, s3: f64) -> Self {
+        Self::from_vector(Vector3::new(s1, s2, s3))
+    }
+
+    fn from_vector(sigma: Vector3) -> Self {
+        let mag_squared = sigma.dot(&sigma);
+        if mag_squared > 1.0 {
+            // switch to the shadow set
+            Self {
+                sigma: sigma * (-1.0 / mag_squared),
+            }
+        } else {
+            Self { sigma }
+        }
+    }
+
+    pub fn identity() -> Self {
+        Self::default()
+    }
+
+    pub fn inv(&self) -> Self {
+        Self { sigma: -self.sigma }
+    }
+
+    /// Composes the rotations such that `a.compose(&b).rotate(v) == a.rotate(b.rotate(v))`
+    pub fn compose(&self, rhs: &Self) -> Self {
+        let (s1, s2) = (self.sigma, rhs.sigma);
+        let s1_squared = s1.dot(&s1);
+        let s2_squared = s2.dot(&s2);
+        let denominator = 1.0 + s1_squared * s2_squared - 2.0 * s1.dot(&s2);
+
+        // the composite is a full revolution, which the formula can't represent
+        if denominator.abs() < f64::EPSILON {
+            return Self::from(Quaternion::from(*self) * Quaternion::from(*rhs));
+        }
+
+        Self::from_vector(
+            (s1 * (1.0 - s2_squared) + s2 * (1.0 - s1_squared) + s1.cross(s2) * 2.0)
+                * (1.0 / denominator),
+        )
+    }
+
+    pub fn rotate(&self, v: Vector3) -> Vector3 {
+        let sigma = self.sigma;
+        let mag_squared = sigma.dot(&sigma);
+        let sigma_cross_v = sigma.cross(v);
+        let scale = 1.0 / ((1.0 + mag_squared) * (1.0 + mag_squared));
+
+        v + (sigma.cross(sigma_cross_v) * 8.0 + sigma_cross_v * (4.0 * (1.0 - mag_squared)))
+            * scale
+    }
+
+    pub fn transform(&self, v: Vector3) -> Vector3 {
+        self.inv().rotate(v)
+    }
+}
+
+impl From<Quaternion> for Mrp {
+    fn from(q: Quaternion) -> Self {
+        // use the quaternion with positive scalar so sigma is the short rotation
+        let sign = if q.s < 0.0 { -1.0 } else { 1.0 };
+        let scale = sign / (1.0 + sign * q.s);
+        Self::from_vector(Vector3::new(q.x * scale, q.y * scale, q.z * scale))
+    }
+}
+
+impl From<Mrp> for Quaternion {
+    fn from(mrp: Mrp) -> Self {
+        let sigma = mrp.sigma;
+        let mag_squared = sigma.dot(&sigma);
+        let scale = 2.0 / (1.0 + mag_squared);
+        Quaternion::new(
+            sigma.e1 * scale,
+            sigma.e2 * scale,
+            sigma.e3 * scale,
+            (1.0 - mag_squared) / (1.0 + mag_squared),
+        )
+    }
+}
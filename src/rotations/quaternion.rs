@@ -35,25 +35,20 @@ impl Quaternion {
         }
 
     pub fn inv(&self) -> Self {
-            Self::new(-self.x,-self.y,-self.z,self.s)
+            // already normalized, so the conjugate doesn't need to go through new()
+            Self {x: -self.x, y: -self.y, z: -self.z, s: self.s}
         }    
 
     pub fn transform(&self, v: Vector3) -> Vector3 {
-        let v_mag = v.norm();        
-        let v_augmented = Self::new(v.e1,v.e2,v.e3,0.0);
-        let inv_q = self.inv();
-        let q_tmp = inv_q * v_augmented;
-        let q_tmp = q_tmp * *self;
-        Vector3::new(v_mag * q_tmp.x,v_mag * q_tmp.y,v_mag * q_tmp.z )
+        self.inv().rotate(v)
     }
 
+    /// Rotates v by q v q*, expanded to v + 2s(q x v) + 2q x (q x v)
+    /// so zero vectors don't need to go through new()
     pub fn rotate(&self, v: Vector3) -> Vector3 {
-        let v_mag = v.norm();        
-        let v_augmented = Self::new(v.e1,v.e2,v.e3,0.0);
-        let inv_q = self.inv();
-        let q_tmp = *self * v_augmented;
-        let q_tmp = q_tmp * inv_q;
-        Vector3::new(v_mag * q_tmp.x,v_mag * q_tmp.y,v_mag * q_tmp.z )
+        let q_vector = Vector3::new(self.x, self.y, self.z);
+        let q_cross_v = q_vector.cross(v);
+        v + q_cross_v * (2.0 * self.s) + q_vector.cross(q_cross_v) * 2.0
     }
 
 }
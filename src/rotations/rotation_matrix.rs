@@ -1,21 +1,41 @@
 use std::ops::Mul;
 use crate::linear_algebra::Vector3;
+use super::quaternion::Quaternion;
+
+/// Tolerance used when checking orthogonality and the determinant
+const TOLERANCE: f64 = 1e-6;
+
+#[derive(Debug, Clone, Copy)]
+pub enum RotationMatrixErrors {
+    ColumnHasZeroMagnitude,
+    ColumnsNotOrthogonal,
+    DeterminantNotPositiveOne,
+}
 
 #[derive(Debug, Copy, Clone)]
 pub struct RotationMatrix {
-    e11: f64,
-    e21: f64,
-    e31: f64,
-    e12: f64,
-    e22: f64,
-    e32: f64,
-    e13: f64,
-    e23: f64,
-    e33: f64,
+    pub(super) e11: f64,
+    pub(super) e21: f64,
+    pub(super) e31: f64,
+    pub(super) e12: f64,
+    pub(super) e22: f64,
+    pub(super) e32: f64,
+    pub(super) e13: f64,
+    pub(super) e23: f64,
+    pub(super) e33: f64,
 }
 
 impl RotationMatrix {
-    fn new(
+    /// Creates a new `RotationMatrix` from its elements in column-major order.
+    ///
+    /// Each column is normalized to unit magnitude before the matrix is checked.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `RotationMatrixErrors` if a column has zero magnitude, if the columns
+    /// are not mutually orthogonal within tolerance, or if the determinant is not +1.
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
         e11: f64,
         e21: f64,
         e31: f64,
@@ -25,28 +45,47 @@ impl RotationMatrix {
         e13: f64,
         e23: f64,
         e33: f64,
-    ) -> Self {
-        // check that each column is normalized to magnitude 1.0
-        let check_normalized = |e1:f64,e2:f64,e3:f64| {
-            let mag_squared = e1*e1 + e2*e2 + e3*e3;            
-            // divide by 0.0 protection
-            assert!(mag_squared > f64::EPSILON, "RotationMatrix column vector had 0 magnitude");
-
-            if !((mag_squared-1.0).abs() < f64::EPSILON) {
-                let mag = mag_squared.sqrt();
-                
-                let e1 = e1/mag;
-                let e2 = e2/mag;
-                let e3 = e3/mag;
-            } 
-            (e1,e2,e3)
-        };
+    ) -> Result<Self, RotationMatrixErrors> {
+        let c1 = normalize_column(Vector3::new(e11, e21, e31))?;
+        let c2 = normalize_column(Vector3::new(e12, e22, e32))?;
+        let c3 = normalize_column(Vector3::new(e13, e23, e33))?;
 
-        let (e11,e21,e31) = check_normalized(e11,e21,e31);
-        let (e12,e22,e32) = check_normalized(e12,e22,e32);
-        let (e13,e23,e33) = check_normalized(e13,e23,e33);
+        if c1.dot(&c2).abs() > TOLERANCE
+            || c1.dot(&c3).abs() > TOLERANCE
+            || c2.dot(&c3).abs() > TOLERANCE
+        {
+            return Err(RotationMatrixErrors::ColumnsNotOrthogonal);
+        }
 
-        Self {
+        let matrix = Self::from_columns(c1, c2, c3);
+
+        if (matrix.determinant() - 1.0).abs() > TOLERANCE {
+            return Err(RotationMatrixErrors::DeterminantNotPositiveOne);
+        }
+
+        Ok(matrix)
+    }
+
+    /// Creates a `RotationMatrix` from elements that may have drifted from orthonormal,
+    /// re-orthonormalizing the columns with Gram-Schmidt.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `RotationMatrixErrors` if the columns are degenerate or the
+    /// elements describe a reflection rather than a rotation.
+    #[allow(clippy::too_many_arguments)]
+    pub fn new_orthonormalized(
+        e11: f64,
+        e21: f64,
+        e31: f64,
+        e12: f64,
+        e22: f64,
+        e32: f64,
+        e13: f64,
+        e23: f64,
+        e33: f64,
+    ) -> Result<Self, RotationMatrixErrors> {
+        let drifted = Self {
             e11,
             e21,
             e31,
@@ -56,17 +95,271 @@ impl RotationMatrix {
             e13,
             e23,
             e33,
+        };
+
+        if drifted.determinant() <= 0.0 {
+            return Err(RotationMatrixErrors::DeterminantNotPositiveOne);
+        }
+
+        let (c1, c2, _) = drifted.columns();
+        let c1 = normalize_column(c1)?;
+        let c2 = normalize_column(c2 - c1 * c2.dot(&c1))?;
+        let c3 = c1.cross(c2);
+
+        Ok(Self::from_columns(c1, c2, c3))
+    }
+
+    pub fn identity() -> Self {
+        Self::from_columns(
+            Vector3::new(1.0, 0.0, 0.0),
+            Vector3::new(0.0, 1.0, 0.0),
+            Vector3::new(0.0, 0.0, 1.0),
+        )
+    }
+
+    /// Returns the determinant of the matrix, which is +1 for a proper rotation.
+    pub fn determinant(&self) -> f64 {
+        self.e11 * (self.e22 * self.e33 - self.e23 * self.e32)
+            - self.e12 * (self.e21 * self.e33 - self.e23 * self.e31)
+            + self.e13 * (self.e21 * self.e32 - self.e22 * self.e31)
+    }
+
+    /// Returns the inverse of the rotation, which for an orthonormal matrix is its transpose.
+    pub fn inv(&self) -> Self {
+        self.transpose()
+    }
+
+    /// Re-orthonormalizes a matrix that has drifted, e.g. after many compositions.
+    ///
+    /// # Errors
+    ///
+    /// Same as `new_orthonormalized`, the matrix may have drifted into a degenerate one
+    /// if it was built directly rather than through one of the constructors.
+    pub fn orthonormalize(&self) -> Result<Self, RotationMatrixErrors> {
+        let (c1, c2, c3) = self.columns();
+        Self::new_orthonormalized(
+            c1.e1, c1.e2, c1.e3, c2.e1, c2.e2, c2.e3, c3.e1, c3.e2, c3.e3,
+        )
+    }
+
+    /// Rotates the vector by the matrix, same as `Quaternion::rotate`
+    pub fn rotate(&self, v: Vector3) -> Vector3 {
+        *self * v
+    }
+
+    /// Transforms the vector into the rotated frame, same as `Quaternion::transform`
+    pub fn transform(&self, v: Vector3) -> Vector3 {
+        self.transpose() * v
+    }
+
+    pub fn transpose(&self) -> Self {
+        Self {
+            e11: self.e11,
+            e21: self.e12,
+            e31: self.e13,
+            e12: self.e21,
+            e22: self.e22,
+            e32: self.e23,
+            e13: self.e31,
+            e23: self.e32,
+            e33: self.e33,
+        }
+    }
+
+    fn columns(&self) -> (Vector3, Vector3, Vector3) {
+        (
+            Vector3::new(self.e11, self.e21, self.e31),
+            Vector3::new(self.e12, self.e22, self.e32),
+            Vector3::new(self.e13, self.e23, self.e33),
+        )
+    }
+
+    fn from_columns(c1: Vector3, c2: Vector3, c3: Vector3) -> Self {
+        Self {
+            e11: c1.e1,
+            e21: c1.e2,
+            e31: c1.e3,
+            e12: c2.e1,
+            e22: c2.e2,
+            e32: c2.e3,
+            e13: c3.e1,
+            e23: c3.e2,
+            e33: c3.e3,
         }
     }
 }
 
+fn normalize_column(column: Vector3) -> Result<Vector3, RotationMatrixErrors> {
+    // divide by 0.0 protection
+    if column.norm() < f64::EPSILON {
+        return Err(RotationMatrixErrors::ColumnHasZeroMagnitude);
+    }
+    Ok(column.normalize())
+}
+
+impl Default for RotationMatrix {
+    fn default() -> Self {
+        Self::identity()
+    }
+}
+
 impl Mul<Vector3> for RotationMatrix {
     type Output = Vector3;
     fn mul(self, rhs: Vector3) -> Vector3{
-        Vector3::new(            
+        Vector3::new(
             self.e11 * rhs.e1 + self.e12 * rhs.e2 + self.e13 * rhs.e3,
             self.e21 * rhs.e1 + self.e22 * rhs.e2 + self.e23 * rhs.e3,
             self.e31 * rhs.e1 + self.e32 * rhs.e2 + self.e33 * rhs.e3,
         )
     }
-}
\ No newline at end of file
+}
+
+impl Mul<RotationMatrix> for RotationMatrix {
+    type Output = Self;
+    fn mul(self, rhs: Self) -> Self {
+        let (c1, c2, c3) = rhs.columns();
+        Self::from_columns(self * c1, self * c2, self * c3)
+    }
+}
+
+impl From<Quaternion> for RotationMatrix {
+    fn from(q: Quaternion) -> Self {
+        let (x, y, z, s) = (q.x, q.y, q.z, q.s);
+        Self {
+            e11: 1.0 - 2.0 * (y * y + z * z),
+            e21: 2.0 * (x * y + z * s),
+            e31: 2.0 * (x * z - y * s),
+            e12: 2.0 * (x * y - z * s),
+            e22: 1.0 - 2.0 * (x * x + z * z),
+            e32: 2.0 * (y * z + x * s),
+            e13: 2.0 * (x * z + y * s),
+            e23: 2.0 * (y * z - x * s),
+            e33: 1.0 - 2.0 * (x * x + y * y),
+        }
+    }
+}
+
+impl From<RotationMatrix> for Quaternion {
+    fn from(m: RotationMatrix) -> Self {
+        // Shepperd's method, pivot on the largest term for numerical stability
+        let trace = m.e11 + m.e22 + m.e33;
+        if trace > m.e11 && trace > m.e22 && trace > m.e33 {
+            let s = (1.0 + trace).sqrt() * 2.0;
+            Quaternion::new(
+                (m.e32 - m.e23) / s,
+                (m.e13 - m.e31) / s,
+                (m.e21 - m.e12) / s,
+                0.25 * s,
+            )
+        } else if m.e11 > m.e22 && m.e11 > m.e33 {
+            let s = (1.0 + m.e11 - m.e22 - m.e33).sqrt() * 2.0;
+            Quaternion::new(
+                0.25 * s,
+                (m.e12 + m.e21) / s,
+                (m.e13 + m.e31) / s,
+                (m.e32 - m.e23) / s,
+            )
+        } else if m.e22 > m.e33 {
+            let s = (1.0 + m.e22 - m.e11 - m.e33).sqrt() * 2.0;
+            Quaternion::new(
+                (m.e12 + m.e21) / s,
+                0.25 * s,
+                (m.e23 + m.e32) / s,
+                (m.e13 - m.e31) / s,
+            )
+        } else {
+            let s = (1.0 + m.e33 - m.e11 - m.e22).sqrt() * 2.0;
+            Quaternion::new(
+                (m.e13 + m.e31) / s,
+                (m.e23 + m.e32) / s,
+                0.25 * s,
+                (m.e21 - m.e12) / s,
+            )
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::assert_vector_eq;
+
+    #[test]
+    fn test_rotation_matrix_normalizes_columns() {
+        let m = RotationMatrix::new(2.0, 0.0, 0.0, 0.0, 3.0, 0.0, 0.0, 0.0, 4.0).unwrap();
+
+        assert_eq!(m.e11, 1.0);
+        assert_eq!(m.e22, 1.0);
+        assert_eq!(m.e33, 1.0);
+    }
+
+    #[test]
+    fn test_rotation_matrix_errors() {
+        assert!(matches!(
+            RotationMatrix::new(0.0, 0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 1.0),
+            Err(RotationMatrixErrors::ColumnHasZeroMagnitude)
+        ));
+        assert!(matches!(
+            RotationMatrix::new(1.0, 0.0, 0.0, 1.0, 1.0, 0.0, 0.0, 0.0, 1.0),
+            Err(RotationMatrixErrors::ColumnsNotOrthogonal)
+        ));
+        assert!(matches!(
+            RotationMatrix::new(1.0, 0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, -1.0),
+            Err(RotationMatrixErrors::DeterminantNotPositiveOne)
+        ));
+    }
+
+    #[test]
+    fn test_rotation_matrix_orthonormalize() {
+        let m = RotationMatrix::new_orthonormalized(1.0, 0.01, 0.0, 0.02, 1.0, 0.0, 0.0, 0.0, 1.0)
+            .unwrap();
+        let (c1, c2, c3) = m.columns();
+
+        assert!(c1.dot(&c2).abs() < 1e-12);
+        assert!(c1.dot(&c3).abs() < 1e-12);
+        assert!((m.determinant() - 1.0).abs() < 1e-12);
+
+        let drifted = m * RotationMatrix {
+            e13: 1e-4,
+            ..RotationMatrix::identity()
+        };
+        let m = drifted.orthonormalize().unwrap();
+        assert!((m.determinant() - 1.0).abs() < 1e-12);
+
+        // a matrix that collapsed can't be recovered, so it's reported instead of returned as is
+        let collapsed = RotationMatrix {
+            e12: 1.0,
+            e22: 0.0,
+            ..RotationMatrix::identity()
+        };
+        assert!(collapsed.determinant().abs() < f64::EPSILON);
+        assert!(matches!(
+            collapsed.orthonormalize(),
+            Err(RotationMatrixErrors::DeterminantNotPositiveOne)
+        ));
+    }
+
+    #[test]
+    fn test_rotation_matrix_quaternion_conversion() {
+        let q = Quaternion::new(0.18119546436307749, 0.4381103371317225, 0.10015469662419728, 0.8747551502773175);
+        let m = RotationMatrix::from(q);
+        let v = Vector3::new(1.0, -2.0, 3.0);
+
+        assert_vector_eq(m.rotate(v), q.rotate(v));
+        assert_vector_eq(m.transform(v), q.transform(v));
+
+        let q_back = Quaternion::from(m);
+        assert_vector_eq(q_back.rotate(v), q.rotate(v));
+    }
+
+    #[test]
+    fn test_rotation_matrix_composition() {
+        let q1 = Quaternion::new(0.18119546436307749, 0.4381103371317225, 0.10015469662419728, 0.8747551502773175);
+        let q2 = Quaternion::new(0.4605004692970668, -0.13901506620501594, -0.7574522634418864, -0.44145237314115715);
+        let m = RotationMatrix::from(q1) * RotationMatrix::from(q2);
+        let v = Vector3::new(0.5, 0.25, -1.0);
+
+        assert_vector_eq(m.rotate(v), (q1 * q2).rotate(v));
+        assert_vector_eq((m * m.inv()).rotate(v), v);
+    }
+}
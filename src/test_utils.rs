@@ -0,0 +1,8 @@
+use crate::linear_algebra::Vector3;
+
+/// Compares each element within a tolerance, float math rarely lands on the exact value
+pub fn assert_vector_eq(a: Vector3, b: Vector3) {
+    assert!((a.e1 - b.e1).abs() < 1e-9, "{:?} != {:?}", a, b);
+    assert!((a.e2 - b.e2).abs() < 1e-9, "{:?} != {:?}", a, b);
+    assert!((a.e3 - b.e3).abs() < 1e-9, "{:?} != {:?}", a, b);
+}
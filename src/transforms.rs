@@ -1,8 +1,120 @@
+pub mod reference_frames;
+
+use std::ops::Mul;
 use crate::coordinate_systems::cartesian::Cartesian;
-use crate::rotations::Rotation;
+use crate::linear_algebra::Vector3;
+use crate::rotations::{Rotation, RotationTrait};
 
+/// The pose of a child frame relative to its parent.
+/// `translation` is the child origin in parent coordinates and
+/// `rotation` rotates child axes into parent axes.
 #[derive(Debug, Default, Copy, Clone)]
 pub struct Transform {
     translation: Cartesian,
     rotation: Rotation,
-}
\ No newline at end of file
+}
+
+impl Transform {
+    pub fn new(translation: Cartesian, rotation: Rotation) -> Self {
+        Self {
+            translation,
+            rotation,
+        }
+    }
+
+    pub fn identity() -> Self {
+        Self::default()
+    }
+
+    pub fn get_rotation(&self) -> Rotation {
+        self.rotation
+    }
+
+    pub fn get_translation(&self) -> Cartesian {
+        self.translation
+    }
+
+    /// Composes the transforms such that `a.compose(&b).transform_point(p)`
+    /// equals `a.transform_point(b.transform_point(p))`
+    pub fn compose(&self, rhs: &Self) -> Self {
+        let translation =
+            Vector3::from(self.translation) + self.rotation.rotate(rhs.translation.into());
+        Self {
+            translation: translation.into(),
+            rotation: self.rotation.compose(&rhs.rotation),
+        }
+    }
+
+    pub fn inv(&self) -> Self {
+        let rotation = self.rotation.inv();
+        let translation = -rotation.rotate(self.translation.into());
+        Self {
+            translation: translation.into(),
+            rotation,
+        }
+    }
+
+    /// Maps a point in child coordinates to parent coordinates
+    pub fn transform_point(&self, point: Vector3) -> Vector3 {
+        Vector3::from(self.translation) + self.rotation.rotate(point)
+    }
+
+    /// Maps a free vector (velocity, force, ...) in child coordinates to parent
+    /// coordinates, which only applies the rotation
+    pub fn transform_vector(&self, vector: Vector3) -> Vector3 {
+        self.rotation.rotate(vector)
+    }
+}
+
+impl Mul<Transform> for Transform {
+    type Output = Self;
+    fn mul(self, rhs: Self) -> Self {
+        self.compose(&rhs)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rotations::quaternion::Quaternion;
+    use crate::test_utils::assert_vector_eq;
+
+    fn transform_a() -> Transform {
+        Transform::new(
+            Cartesian::new(1.8, -1.7, 0.1),
+            Rotation::Quaternion(Quaternion::new(0.18119546436307749, 0.4381103371317225, 0.10015469662419728, 0.8747551502773175)),
+        )
+    }
+
+    fn transform_b() -> Transform {
+        Transform::new(
+            Cartesian::new(4.6, 0.5, -0.7),
+            Rotation::Quaternion(Quaternion::new(0.4605004692970668, -0.13901506620501594, -0.7574522634418864, -0.44145237314115715)),
+        )
+    }
+
+    #[test]
+    fn test_transform_compose() {
+        let a = transform_a();
+        let b = transform_b();
+        let p = Vector3::new(1.0, 2.0, 3.0);
+
+        assert_vector_eq(
+            (a * b).transform_point(p),
+            a.transform_point(b.transform_point(p)),
+        );
+        assert_vector_eq(
+            (a * b).transform_vector(p),
+            a.transform_vector(b.transform_vector(p)),
+        );
+    }
+
+    #[test]
+    fn test_transform_inv() {
+        let a = transform_a();
+        let p = Vector3::new(-1.0, 0.5, 3.0);
+
+        assert_vector_eq(a.inv().transform_point(a.transform_point(p)), p);
+        assert_vector_eq((a * a.inv()).transform_point(p), p);
+    }
+}
@@ -0,0 +1,250 @@
+use std::collections::HashMap;
+use super::Transform;
+
+/// Name of the root frame every other frame is ultimately attached to
+pub const WORLD: &str = "world";
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ReferenceFrameType {
+    World,
+    Base,
+    Body,
+    Marker,
+}
+
+#[derive(Debug, Clone)]
+pub enum ReferenceFrameErrors {
+    CannotModifyWorld,
+    FrameHasChildren(String),
+    FrameNotFound(String),
+    NameAlreadyExists(String),
+}
+
+#[derive(Debug, Clone)]
+pub struct ReferenceFrame {
+    frame_type: ReferenceFrameType,
+    parent: Option<String>,
+    transform: Transform,
+}
+
+impl ReferenceFrame {
+    pub fn get_frame_type(&self) -> ReferenceFrameType {
+        self.frame_type
+    }
+
+    pub fn get_parent(&self) -> Option<&str> {
+        self.parent.as_deref()
+    }
+
+    /// Returns the transform from this frame to its parent
+    pub fn get_transform(&self) -> Transform {
+        self.transform
+    }
+}
+
+/// A tree of named frames rooted at `WORLD`.
+/// Parents must exist before their children are added, so the tree can't contain cycles.
+#[derive(Debug, Clone)]
+pub struct ReferenceFrames {
+    frames: HashMap<String, ReferenceFrame>,
+}
+
+impl Default for ReferenceFrames {
+    fn default() -> Self {
+        let mut frames = HashMap::new();
+        frames.insert(
+            WORLD.to_string(),
+            ReferenceFrame {
+                frame_type: ReferenceFrameType::World,
+                parent: None,
+                transform: Transform::identity(),
+            },
+        );
+        Self { frames }
+    }
+}
+
+impl ReferenceFrames {
+    /// Adds a frame positioned by `transform` relative to `parent`.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `ReferenceFrameErrors` if the name is taken or the parent doesn't exist.
+    pub fn add_frame(
+        &mut self,
+        name: &str,
+        frame_type: ReferenceFrameType,
+        parent: &str,
+        transform: Transform,
+    ) -> Result<(), ReferenceFrameErrors> {
+        if self.frames.contains_key(name) {
+            return Err(ReferenceFrameErrors::NameAlreadyExists(name.to_string()));
+        }
+        if !self.frames.contains_key(parent) {
+            return Err(ReferenceFrameErrors::FrameNotFound(parent.to_string()));
+        }
+
+        self.frames.insert(
+            name.to_string(),
+            ReferenceFrame {
+                frame_type,
+                parent: Some(parent.to_string()),
+                transform,
+            },
+        );
+        Ok(())
+    }
+
+    pub fn get_frame(&self, name: &str) -> Option<&ReferenceFrame> {
+        self.frames.get(name)
+    }
+
+    pub fn get_names(&self) -> impl Iterator<Item = &String> {
+        self.frames.keys()
+    }
+
+    /// Returns the transform that maps coordinates in frame `from` to frame `to`
+    pub fn get_transform(&self, from: &str, to: &str) -> Result<Transform, ReferenceFrameErrors> {
+        let from_to_world = self.get_transform_to_world(from)?;
+        let to_to_world = self.get_transform_to_world(to)?;
+        Ok(to_to_world.inv() * from_to_world)
+    }
+
+    /// Returns the transform that maps coordinates in frame `name` to the world frame
+    pub fn get_transform_to_world(&self, name: &str) -> Result<Transform, ReferenceFrameErrors> {
+        let mut frame = self
+            .frames
+            .get(name)
+            .ok_or_else(|| ReferenceFrameErrors::FrameNotFound(name.to_string()))?;
+        let mut transform = frame.transform;
+
+        while let Some(parent) = &frame.parent {
+            frame = self
+                .frames
+                .get(parent)
+                .ok_or_else(|| ReferenceFrameErrors::FrameNotFound(parent.to_string()))?;
+            transform = frame.transform * transform;
+        }
+        Ok(transform)
+    }
+
+    /// Removes a frame that has no children.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `ReferenceFrameErrors` if the frame is the world, doesn't exist, or has children.
+    pub fn remove_frame(&mut self, name: &str) -> Result<ReferenceFrame, ReferenceFrameErrors> {
+        if name == WORLD {
+            return Err(ReferenceFrameErrors::CannotModifyWorld);
+        }
+        if self
+            .frames
+            .values()
+            .any(|frame| frame.parent.as_deref() == Some(name))
+        {
+            return Err(ReferenceFrameErrors::FrameHasChildren(name.to_string()));
+        }
+        self.frames
+            .remove(name)
+            .ok_or_else(|| ReferenceFrameErrors::FrameNotFound(name.to_string()))
+    }
+
+    /// Moves a frame relative to its parent, which also moves all of its children
+    pub fn set_transform(
+        &mut self,
+        name: &str,
+        transform: Transform,
+    ) -> Result<(), ReferenceFrameErrors> {
+        if name == WORLD {
+            return Err(ReferenceFrameErrors::CannotModifyWorld);
+        }
+        match self.frames.get_mut(name) {
+            Some(frame) => {
+                frame.transform = transform;
+                Ok(())
+            }
+            None => Err(ReferenceFrameErrors::FrameNotFound(name.to_string())),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::coordinate_systems::cartesian::Cartesian;
+    use crate::linear_algebra::Vector3;
+    use crate::rotations::{quaternion::Quaternion, Rotation};
+    use crate::test_utils::assert_vector_eq;
+
+    #[test]
+    fn test_reference_frames_transform_between_branches() {
+        // base is 1m up, body1 is 2m along base x, marker sits on a sibling branch
+        let quarter_turn_z = Rotation::Quaternion(Quaternion::new(
+            0.0,
+            0.0,
+            (std::f64::consts::PI / 4.0).sin(),
+            (std::f64::consts::PI / 4.0).cos(),
+        ));
+
+        let mut frames = ReferenceFrames::default();
+        frames
+            .add_frame(
+                "base",
+                ReferenceFrameType::Base,
+                WORLD,
+                Transform::new(Cartesian::new(0.0, 0.0, 1.0), quarter_turn_z),
+            )
+            .unwrap();
+        frames
+            .add_frame(
+                "body1",
+                ReferenceFrameType::Body,
+                "base",
+                Transform::new(Cartesian::new(2.0, 0.0, 0.0), Rotation::default()),
+            )
+            .unwrap();
+        frames
+            .add_frame(
+                "marker",
+                ReferenceFrameType::Marker,
+                WORLD,
+                Transform::new(Cartesian::new(5.0, 5.0, 5.0), Rotation::default()),
+            )
+            .unwrap();
+
+        let body_to_world = frames.get_transform_to_world("body1").unwrap();
+        assert_vector_eq(
+            body_to_world.transform_point(Vector3::default()),
+            Vector3::new(0.0, 2.0, 1.0),
+        );
+
+        let body_to_marker = frames.get_transform("body1", "marker").unwrap();
+        assert_vector_eq(
+            body_to_marker.transform_point(Vector3::default()),
+            Vector3::new(-5.0, -3.0, -4.0),
+        );
+    }
+
+    #[test]
+    fn test_reference_frames_errors() {
+        let mut frames = ReferenceFrames::default();
+        frames
+            .add_frame("base", ReferenceFrameType::Base, WORLD, Transform::identity())
+            .unwrap();
+
+        assert!(matches!(
+            frames.add_frame("base", ReferenceFrameType::Base, WORLD, Transform::identity()),
+            Err(ReferenceFrameErrors::NameAlreadyExists(_))
+        ));
+        assert!(matches!(
+            frames.add_frame("body1", ReferenceFrameType::Body, "missing", Transform::identity()),
+            Err(ReferenceFrameErrors::FrameNotFound(_))
+        ));
+        assert!(matches!(
+            frames.remove_frame(WORLD),
+            Err(ReferenceFrameErrors::CannotModifyWorld)
+        ));
+        assert!(frames.remove_frame("base").is_ok());
+        assert!(frames.get_transform("base", WORLD).is_err());
+    }
+}
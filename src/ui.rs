@@ -1,5 +1,11 @@
 pub mod canvas;
 pub mod dummies;
 pub mod errors;
+pub mod expression;
+pub mod inspector;
 pub mod modals;
-pub mod theme;
\ No newline at end of file
+pub mod parameters;
+pub mod schema;
+pub mod theme;
+pub mod toasts;
+pub mod units;
\ No newline at end of file
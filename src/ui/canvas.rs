@@ -10,11 +10,14 @@ use iced::{
 
 pub mod edge;
 pub mod graph;
+pub mod grid;
 pub mod node;
 pub mod nodebar;
+pub mod view;
 
 use crate::multibody::MultibodyTrait;
 use crate::ui::theme::Theme;
+use view::ViewTransform;
 use crate::Message;
 
 #[derive(Debug)]
@@ -49,8 +52,10 @@ impl<'a> canvas::Program<Message, Theme> for GraphCanvas<'a> {
         bounds: Rectangle,
         cursor: Cursor,
     ) -> (Status, Option<Message>) {
-        if !cursor.is_over(bounds) {
-            return (Status::Ignored, None);
+        // messages carry canvas relative positions, the same space as the graph and nodebar bounds
+        let cursor = match cursor.position_in(bounds) {
+            Some(position) => Cursor::Available(position),
+            None => return (Status::Ignored, None),
         };
 
         match event {
@@ -73,6 +78,9 @@ impl<'a> canvas::Program<Message, Theme> for GraphCanvas<'a> {
                 mouse::Event::CursorMoved { position: _ } => {
                     (Status::Captured, Some(Message::CursorMoved(cursor)))
                 }
+                mouse::Event::WheelScrolled { delta } => {
+                    (Status::Captured, Some(Message::WheelScrolled(cursor, delta)))
+                }
                 _ => (Status::Captured, None),
             },
             _ => (Status::Ignored, None),
@@ -91,19 +99,36 @@ impl<'a> canvas::Program<Message, Theme> for GraphCanvas<'a> {
             // node_bar border
             frame.stroke(
                 &Path::rectangle(Point::ORIGIN, self.app_state.nodebar.bounds.size()),
-                Stroke::default().with_width(2.0),
+                Stroke::default()
+                    .with_color(self.app_state.theme.border)
+                    .with_width(2.0),
             );
 
             // canvas border
             frame.stroke(
                 &Path::rectangle(Point::ORIGIN, frame.size()),
-                Stroke::default().with_width(2.0),
+                Stroke::default()
+                    .with_color(self.app_state.theme.border)
+                    .with_width(2.0),
             );
 
             // create edges (before nodes so nodes clip)
             frame.with_clip(self.app_state.graph.bounds, |frame| {
+                let size = frame.size();
+                self.app_state.graph.grid.draw(
+                    frame,
+                    &self.app_state.theme,
+                    &self.app_state.graph.view,
+                    size,
+                );
+
                 self.app_state.graph.edges.iter().for_each(|(_, edge)| {
-                    edge.draw(frame, &self.app_state.graph.nodes, &self.app_state.theme)
+                    edge.draw(
+                        frame,
+                        &self.app_state.graph.nodes,
+                        &self.app_state.theme,
+                        &self.app_state.graph.view,
+                    )
                 });
             });
 
@@ -114,7 +139,13 @@ impl<'a> canvas::Program<Message, Theme> for GraphCanvas<'a> {
                 .iter()
                 .for_each(|(_, nodebarnode)| {
                     let label = &nodebarnode.label;
-                    nodebarnode.node.draw(frame, &self.app_state.theme, label);
+                    nodebarnode.node.draw(
+                        frame,
+                        &self.app_state.theme,
+                        label,
+                        &ViewTransform::default(),
+                        false,
+                    );
                 });
 
             // create nodes that are clipped (graph)
@@ -127,11 +158,46 @@ impl<'a> canvas::Program<Message, Theme> for GraphCanvas<'a> {
                         if let Some(component) =
                             self.app_state.graph.components.get(&graphnode.component_id)
                         {
-                            graphnode
-                                .node
-                                .draw(frame, &self.app_state.theme, &component.get_name())
+                            graphnode.node.draw(
+                                frame,
+                                &self.app_state.theme,
+                                &component.get_name(),
+                                &self.app_state.graph.view,
+                                true,
+                            );
+                            // outputs get a dot in their top right corner
+                            if self.app_state.graph.is_output(graphnode.component_id) {
+                                let view = &self.app_state.graph.view;
+                                let bounds = graphnode.node.bounds;
+                                let center = Point::new(bounds.x + bounds.width - 10.0, bounds.y + 10.0);
+                                frame.fill(
+                                    &Path::circle(view.to_screen(center), 4.0 * view.zoom),
+                                    self.app_state.theme.success,
+                                );
+                            }
                         }
                     });
+
+                for (start, end) in self.app_state.graph.get_alignment_guides() {
+                    let view = &self.app_state.graph.view;
+                    frame.stroke(
+                        &Path::line(view.to_screen(*start), view.to_screen(*end)),
+                        Stroke::default()
+                            .with_color(self.app_state.theme.highlight)
+                            .with_width(1.0),
+                    );
+                }
+
+                if let Some(selection_box) = self.app_state.graph.get_selection_box() {
+                    let selection_box =
+                        self.app_state.graph.view.rectangle_to_screen(selection_box);
+                    frame.stroke(
+                        &Path::rectangle(selection_box.position(), selection_box.size()),
+                        Stroke::default()
+                            .with_color(self.app_state.theme.highlight)
+                            .with_width(1.0),
+                    );
+                }
             });
         });
         vec![all_content]
@@ -143,9 +209,11 @@ impl<'a> canvas::Program<Message, Theme> for GraphCanvas<'a> {
         bounds: Rectangle,
         cursor: mouse::Cursor,
     ) -> mouse::Interaction {
-        if cursor.is_over(bounds) {
-            if self.app_state.graph.is_clicked {
+        if let Some(position) = cursor.position_in(bounds) {
+            if self.app_state.graph.is_panning {
                 mouse::Interaction::Grabbing
+            } else if self.app_state.graph.is_over_port(Cursor::Available(position)) {
+                mouse::Interaction::Crosshair
             } else {
                 mouse::Interaction::Grab
             }
@@ -1,23 +1,76 @@
-use iced::Point;
+use iced::{Point, Vector};
 use iced::widget::canvas::{Path,stroke::{self,Stroke}};
 use uuid::Uuid;
 use std::collections::HashMap;
 use crate::ui::canvas::graph::GraphNode;
+use crate::ui::canvas::node::NodePort;
+use crate::ui::canvas::view::ViewTransform;
 use crate::ui::theme::Theme;
-use lyon_geom::{euclid::default::Point2D, CubicBezierSegment, Line, LineSegment, QuadraticBezierSegment};
-use lyon_path::PathEvent;
+use lyon_geom::{euclid::default::Point2D, LineSegment, QuadraticBezierSegment};
+use serde::{Deserialize, Serialize};
+
+/// Serde mirror of `iced::Point` so dangling edge ends can be saved
+#[derive(Serialize, Deserialize)]
+#[serde(remote = "Point")]
+pub struct PointDef {
+    pub x: f32,
+    pub y: f32,
+}
+
+/// Serde mirror of `iced::Vector` for the edge bend
+#[derive(Serialize, Deserialize)]
+#[serde(remote = "Vector")]
+pub struct VectorDef {
+    pub x: f32,
+    pub y: f32,
+}
+
+mod option_vector {
+    use super::VectorDef;
+    use iced::Vector;
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    #[derive(Serialize, Deserialize)]
+    struct Wrapper(#[serde(with = "VectorDef")] Vector);
+
+    pub fn serialize<S: Serializer>(value: &Option<Vector>, serializer: S) -> Result<S::Ok, S::Error> {
+        value.map(Wrapper).serialize(serializer)
+    }
 
-#[derive(Debug, Clone)]
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<Vector>, D::Error> {
+        Ok(Option::<Wrapper>::deserialize(deserializer)?.map(|wrapper| wrapper.0))
+    }
+}
+
+/// Radius of the bend handle on a selected edge, in screen pixels
+pub const HANDLE_RADIUS: f32 = 6.0;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum EdgeConnection {
     Node(Uuid),
-    Point(Point),
+    Point(#[serde(with = "PointDef")] Point),
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Edge {
     pub from: EdgeConnection,
     pub to: EdgeConnection,
-    pub control: Option<Point>,
+    /// Offset of the quadratic control point from the middle of the edge, in graph coordinates.
+    /// Relative so the bend follows the nodes around.
+    #[serde(default, with = "option_vector")]
+    pub control: Option<Vector>,
+    #[serde(skip)]
+    pub is_selected: bool,
+    /// Whether the edge being drawn would be accepted where it is, `None` when it's not over a node
+    #[serde(skip)]
+    pub is_valid: Option<bool>,
+}
+
+/// Compares what's saved with the edge, so selecting or hovering isn't a change to it
+impl PartialEq for Edge {
+    fn eq(&self, other: &Self) -> bool {
+        self.from == other.from && self.to == other.to && self.control == other.control
+    }
 }
 
 impl Edge {
@@ -26,26 +79,83 @@ impl Edge {
             from: from,
             to: to,
             control: None,
+            is_selected: false,
+            is_valid: None,
         }
     }
 
-    pub fn draw(&self, frame: &mut iced::widget::canvas::Frame, nodes: &HashMap<Uuid,GraphNode>, theme: &Theme) {
-        let from_point = match self.from {
-            EdgeConnection::Node(id) => nodes.get(&id).unwrap().node.bounds.center(),
-            EdgeConnection::Point(point) => point,
+    /// Returns the ends of the edge, at the output and input ports of its nodes, in graph coordinates
+    pub fn get_endpoints(&self, nodes: &HashMap<Uuid, GraphNode>) -> Option<(Point, Point)> {
+        let point = |connection: &EdgeConnection, port: NodePort| match connection {
+            EdgeConnection::Node(id) => nodes
+                .get(id)
+                .map(|graphnode| graphnode.node.get_port_position(port)),
+            EdgeConnection::Point(point) => Some(*point),
         };
-        
-        let to_point = match self.to {
-            EdgeConnection::Node(id) => {
-                let graphnode = nodes.get(&id).unwrap();
-                let node_path = graphnode.node.calculate_path();
+        Some((
+            point(&self.from, NodePort::Output)?,
+            point(&self.to, NodePort::Input)?,
+        ))
+    }
 
-                find_intersection(from_point,graphnode.node.bounds.center(), &node_path)
-            }
-            EdgeConnection::Point(point) => point,
+    /// Returns the quadratic control point for the given ends
+    pub fn get_control_point(&self, from: Point, to: Point) -> Point {
+        let middle = Point::new((from.x + to.x) / 2.0, (from.y + to.y) / 2.0);
+        middle + self.control.unwrap_or(Vector::new(0.0, 0.0))
+    }
+
+    /// Returns the point halfway along the curve, where the bend handle sits, in graph coordinates
+    pub fn get_handle(&self, nodes: &HashMap<Uuid, GraphNode>) -> Option<Point> {
+        let (from, to) = self.get_endpoints(nodes)?;
+        let control = self.get_control_point(from, to);
+        Some(Point::new(
+            0.25 * from.x + 0.5 * control.x + 0.25 * to.x,
+            0.25 * from.y + 0.5 * control.y + 0.25 * to.y,
+        ))
+    }
+
+    /// Bends the edge so the curve passes through `handle`, in graph coordinates
+    pub fn set_handle(&mut self, handle: Point, nodes: &HashMap<Uuid, GraphNode>) {
+        if let Some((from, to)) = self.get_endpoints(nodes) {
+            let middle = Point::new((from.x + to.x) / 2.0, (from.y + to.y) / 2.0);
+            self.control = Some((handle - middle) * 2.0);
+        }
+    }
+
+    /// Returns the distance from `point` to the curve, in graph coordinates
+    pub fn distance_to(&self, point: Point, nodes: &HashMap<Uuid, GraphNode>) -> Option<f32> {
+        let (from, to) = self.get_endpoints(nodes)?;
+        let control = self.get_control_point(from, to);
+        let curve = QuadraticBezierSegment {
+            from: Point2D::new(from.x, from.y),
+            ctrl: Point2D::new(control.x, control.y),
+            to: Point2D::new(to.x, to.y),
         };
+        let point = Point2D::new(point.x, point.y);
 
-        let control_point = Point::new((from_point.x + to_point.x)/2.0,(from_point.y + to_point.y)/2.0 );
+        let mut distance = f32::INFINITY;
+        curve.for_each_flattened(0.5, &mut |segment: &LineSegment<f32>| {
+            distance = distance.min(segment.distance_to_point(point));
+        });
+        Some(distance)
+    }
+
+    pub fn draw(&self, frame: &mut iced::widget::canvas::Frame, nodes: &HashMap<Uuid,GraphNode>, theme: &Theme, view: &ViewTransform) {
+        // everything is drawn in screen coordinates
+        let (graph_from, graph_to) = match self.get_endpoints(nodes) {
+            Some(endpoints) => endpoints,
+            None => return,
+        };
+        let control_point = view.to_screen(self.get_control_point(graph_from, graph_to));
+        let color = match self.is_valid {
+            Some(true) => theme.success,
+            Some(false) => theme.error,
+            None if self.is_selected => theme.highlight,
+            None => theme.primary,
+        };
+
+        let from_point = view.to_screen(graph_from);
+        let to_point = view.to_screen(graph_to);
 
         let path = Path::new(|p| {
             p.move_to(from_point);
@@ -56,21 +166,36 @@ impl Edge {
             frame.stroke(
                 &path,
                 Stroke {
-                    style: stroke::Style::Solid(theme.primary),
-                    width: 3.0,
+                    style: stroke::Style::Solid(color),
+                    width: 3.0 * view.zoom,
                     ..Stroke::default()
                 },
             );
         });
 
-        // Calculate the direction vector
-        let direction = Point::new(to_point.x - from_point.x, to_point.y - from_point.y);
-        let length = (direction.x.powi(2) + direction.y.powi(2)).sqrt();
+        if self.is_selected {
+            if let Some(handle) = self.get_handle(nodes) {
+                frame.fill(&Path::circle(view.to_screen(handle), HANDLE_RADIUS), color);
+            }
+        }
+
+        // Calculate the direction vector, along the tangent at the end of the curve,
+        // falling back to the chord when the control point sits on the target
+        let mut direction = to_point - control_point;
+        let mut length = (direction.x.powi(2) + direction.y.powi(2)).sqrt();
+        if length < f32::EPSILON {
+            direction = to_point - from_point;
+            length = (direction.x.powi(2) + direction.y.powi(2)).sqrt();
+        }
+        // A zero length edge has no direction to point the arrowhead along
+        if length < f32::EPSILON {
+            return;
+        }
         let unit_direction = Point::new(direction.x / length, direction.y / length);
 
         // Define the arrowhead size
-        let arrowhead_length = 10.0;
-        let arrowhead_width = 5.0;
+        let arrowhead_length = 10.0 * view.zoom;
+        let arrowhead_width = 5.0 * view.zoom;
 
         // Calculate the points of the arrowhead
         let arrow_point1 = Point::new(
@@ -92,93 +217,36 @@ impl Edge {
 
         frame.fill(
             &arrow_path,
-            theme.primary,
+            color,
         );
     }
 }
 
-fn find_intersection(from: Point, to: Point, path: &Path) -> Point {
-    let lyon_path = path.raw();
-
-    for event in lyon_path.iter() {
-        match event {
-            PathEvent::Line { from: start, to: end } => {
-                if let Some(intersection) = line_line_intersection(from, to, start, end) {
-                    return intersection;
-                }
-            }
-            PathEvent::Quadratic { from: start, ctrl, to: end } => {
-                if let Some(intersection) = line_quadratic_intersection(from, to, start, ctrl, end) {
-                    return intersection;
-                }
-            }
-            PathEvent::Cubic { from: start, ctrl1, ctrl2, to: end } => {
-                if let Some(intersection) = line_cubic_intersection(from, to, start, ctrl1, ctrl2, end) {
-                    return intersection;
-                }
-            }
-            _ => {}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ui::canvas::node::Node;
+    use iced::{Rectangle, Size};
+
+    #[test]
+    fn test_edge_bend_and_distance() {
+        let (from_id, to_id) = (Uuid::new_v4(), Uuid::new_v4());
+        let mut nodes = HashMap::new();
+        for (id, x) in [(from_id, 0.0), (to_id, 200.0)] {
+            let bounds = Rectangle::new(Point::new(x, 0.0), Size::new(100.0, 50.0));
+            nodes.insert(id, GraphNode::new(Uuid::new_v4(), Node::new(bounds)));
         }
+        let mut edge = Edge::new(EdgeConnection::Node(from_id), EdgeConnection::Node(to_id));
+
+        // straight from the output port at (50, 50) to the input port at (250, 0)
+        assert_eq!(edge.get_endpoints(&nodes), Some((Point::new(50.0, 50.0), Point::new(250.0, 0.0))));
+        assert_eq!(edge.get_handle(&nodes), Some(Point::new(150.0, 25.0)));
+        assert!(edge.distance_to(Point::new(100.0, 37.5), &nodes).unwrap() < 0.5);
+
+        edge.set_handle(Point::new(150.0, 125.0), &nodes);
+        assert_eq!(edge.control, Some(Vector::new(0.0, 200.0)));
+        assert_eq!(edge.get_handle(&nodes), Some(Point::new(150.0, 125.0)));
+        assert!(edge.distance_to(Point::new(150.0, 125.0), &nodes).unwrap() < 0.5);
+        assert!(edge.distance_to(Point::new(150.0, 25.0), &nodes).unwrap() > 50.0);
     }
-
-    to // Default to the 'to' point if no intersection is found
-}
-
-fn line_line_intersection(from: Point, to: Point, start: Point2D<f32>, end: Point2D<f32>) -> Option<Point> {
-    let line1 = LineSegment {
-        from: Point2D::new(from.x, from.y),
-        to: Point2D::new(to.x, to.y),
-    };
-
-    let line2 = LineSegment {
-        from: start,
-        to: end,
-    };
-
-    if let Some(intersection) = line1.intersection(&line2) {
-        Some(Point::new(intersection.x, intersection.y))
-    } else {
-        None
-    }
-}
-
-fn line_quadratic_intersection(from: Point, to: Point, start: Point2D<f32>, ctrl: Point2D<f32>, end: Point2D<f32>) -> Option<Point> {
-    let line = Line {
-        point: Point2D::new(from.x, from.y),
-        vector: Point2D::new(to.x - from.x, to.y - from.y).to_vector(),
-    };
-
-    let curve = QuadraticBezierSegment {
-        from: start,
-        ctrl,
-        to: end,
-    };
-
-    for t in curve.line_intersections_t(&line) {
-        let intersection = curve.sample(t);
-        return Some(Point::new(intersection.x, intersection.y));
-    }
-
-    None
 }
-
-fn line_cubic_intersection(from: Point, to: Point, start: Point2D<f32>, ctrl1: Point2D<f32>, ctrl2: Point2D<f32>, end: Point2D<f32>) -> Option<Point> {
-    let line = Line {
-        point: Point2D::new(from.x, from.y),
-        vector: Point2D::new(to.x - from.x, to.y - from.y).to_vector(),
-    };
-
-    let curve = CubicBezierSegment {
-        from: start,
-        ctrl1,
-        ctrl2,
-        to: end,
-    };
-
-    for t in curve.line_intersections_t(&line) {
-        let intersection = curve.sample(t);
-        return Some(Point::new(intersection.x, intersection.y));
-    }
-
-    None
-}
\ No newline at end of file
@@ -1,23 +1,70 @@
-use iced::{mouse::Cursor, Point, Rectangle, Size};
-use std::collections::HashMap;
+use iced::{
+    keyboard::Modifiers,
+    mouse::{Cursor, ScrollDelta},
+    Point, Rectangle, Size, Vector,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::time::Instant;
 use uuid::Uuid;
 
-use super::edge::{Edge, EdgeConnection};
-use super::node::Node;
+pub mod clipboard;
+pub mod context_menu;
+pub mod history;
+pub mod layout;
+#[cfg(test)]
+pub mod test_utils;
+pub mod validation;
+
+use super::edge::{Edge, EdgeConnection, HANDLE_RADIUS};
+use super::grid::{self, GridSettings};
+use clipboard::GraphFragment;
+use context_menu::{ContextMenu, ContextTarget};
+use history::{GraphCommand, History};
+use layout::{LayoutAnimation, LayoutOrientation};
+use super::node::{Node, NodePort};
+use super::view::ViewTransform;
 use crate::multibody::{
     joints::Joint, MultibodyComponent, MultibodyErrors, MultibodySystem, MultibodyTrait,
 };
 use crate::ui::dummies::{DummyComponent, DummyTrait};
+use crate::ui::parameters::Parameters;
+use crate::ui::schema::FieldContext;
+use crate::ui::units::UnitSystem;
 use crate::{MouseButton, MouseButtonReleaseEvents};
 
+/// How far a duplicate is placed from the original, in graph coordinates
+const DUPLICATE_OFFSET: f32 = 30.0;
+/// How close node edges or centers need to be to show an alignment guide, in screen pixels
+const ALIGNMENT_TOLERANCE: f32 = 3.0;
+/// How close a click needs to be to an edge to select it, in screen pixels
+const EDGE_PICK_TOLERANCE: f32 = 5.0;
+/// How far outside a port a click still grabs it, in screen pixels
+const PORT_PICK_TOLERANCE: f32 = 3.0;
+/// How far the cursor can move between a right press and release that still opens the context menu,
+/// in screen pixels
+const RIGHT_CLICK_TOLERANCE: f32 = 3.0;
+
 pub enum GraphMessage {
-    EditComponent(Uuid),
+    /// A node was double clicked
+    EditComponent,
+    /// Right clicked without dragging
+    OpenContextMenu(ContextMenu),
 }
 
+#[derive(Debug, Clone)]
 pub enum GraphErrors {
     BodyInvalidId(Uuid),
     BodyMissingFrom(Uuid),
+    /// Another component already has this one's name
+    DuplicateName(Uuid),
+    /// The name is empty or only whitespace
+    EmptyName(Uuid),
     IdNotFound(Uuid),
+    /// The component leads back to itself, so the model isn't a tree
+    InCycle(Uuid),
+    /// A field can't be evaluated with the current parameters
+    InvalidFields(Uuid, MultibodyErrors),
     NoBase,
     NoBaseConnections,
     JointMissingFrom(Uuid),
@@ -26,7 +73,7 @@ pub enum GraphErrors {
     Multibody(MultibodyErrors),
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct GraphNode {
     pub component_id: Uuid,
     pub edges: Vec<Uuid>,
@@ -34,7 +81,7 @@ pub struct GraphNode {
 }
 
 impl GraphNode {
-    fn new(component_id: Uuid, node: Node) -> Self {
+    pub fn new(component_id: Uuid, node: Node) -> Self {
         let edges = Vec::new();
         Self {
             component_id,
@@ -46,39 +93,468 @@ impl GraphNode {
 
 #[derive(Debug)]
 pub struct Graph {
+    /// Lines where the dragged node lines up with others, in graph coordinates
+    alignment_guides: Vec<(Point, Point)>,
     pub bounds: Rectangle,
+    clipboard: Option<GraphFragment>,
     pub components: HashMap<Uuid, MultibodyComponent>,
     current_edge: Option<Uuid>,
+    /// The edge whose bend handle is being dragged
+    dragged_edge: Option<Uuid>,
+    /// How far the cursor has dragged the selection, before snapping
+    drag_offset: Vector,
+    /// Where each dragged node started, so snapping doesn't swallow small movements
+    drag_origins: HashMap<Uuid, Point>,
     pub edges: HashMap<Uuid, Edge>,
-    pub is_clicked: bool,
-    last_cursor_position: Option<Point>,
+    pub grid: GridSettings,
+    history: History,
+    /// The node under the cursor, whose problems show in a tooltip
+    hovered_node: Option<Uuid>,
+    pub is_panning: bool,
     left_clicked_node: Option<Uuid>,
     pub nodes: HashMap<Uuid, GraphNode>,
+    /// Components the simulation reports results for, by id so renaming doesn't affect them
+    outputs: HashSet<Uuid>,
+    /// Call `parameters_changed` after editing them
+    pub parameters: Parameters,
+    /// Screen position within the bounds, not graph coordinates
+    last_cursor_position: Option<Point>,
+    layout_animation: Option<LayoutAnimation>,
+    /// Started when a node is grabbed, pushed to the history if it was actually moved
+    pending_drag: Option<GraphCommand>,
+    /// Everything `validation::validate` found after the last edit
+    problems: Vec<GraphErrors>,
     right_clicked_node: Option<Uuid>,
-    selected_node: Option<Uuid>,
-    //zoom: f32,
+    /// Screen position of the last right press, to tell a click from a drag
+    right_pressed_position: Option<Point>,
+    selected_edge: Option<Uuid>,
+    selected_nodes: HashSet<Uuid>,
+    /// Corners of the box being dragged out, in graph coordinates
+    selection_box: Option<(Point, Point)>,
+    /// Which units fields are shown and typed in
+    pub unit_system: UnitSystem,
+    pub view: ViewTransform,
 }
 
 impl Default for Graph {
     fn default() -> Self {
-        Self {
+        let mut graph = Self {
+            alignment_guides: Vec::new(),
             bounds: Rectangle::new(Point::new(130.0, 0.0), Size::new(870.0, 1000.0)),
+            clipboard: None,
             components: HashMap::new(),
             current_edge: None,
+            dragged_edge: None,
+            drag_offset: Vector::new(0.0, 0.0),
+            drag_origins: HashMap::new(),
             edges: HashMap::new(),
-            is_clicked: false,
+            grid: GridSettings::default(),
+            history: History::default(),
+            hovered_node: None,
+            is_panning: false,
             last_cursor_position: None,
+            layout_animation: None,
             left_clicked_node: None,
             nodes: HashMap::new(),
+            outputs: HashSet::new(),
+            parameters: Parameters::default(),
+            pending_drag: None,
+            problems: Vec::new(),
             right_clicked_node: None,
-            selected_node: None,
-            //zoom: 1.0,
-        }
+            right_pressed_position: None,
+            selected_edge: None,
+            selected_nodes: HashSet::new(),
+            selection_box: None,
+            unit_system: UnitSystem::default(),
+            view: ViewTransform::default(),
+        };
+        // an empty graph already has problems
+        graph.refresh_problems();
+        graph
     }
 }
 
 impl Graph {
+    /// Also settles a running layout animation so the two don't end up interleaved in the history
+    fn begin_command(&mut self, component_ids: &[Uuid], edge_ids: &[Uuid], node_ids: &[Uuid]) -> GraphCommand {
+        self.finish_layout_animation();
+        GraphCommand::begin(
+            &self.components,
+            &self.edges,
+            &self.nodes,
+            component_ids,
+            edge_ids,
+            node_ids,
+        )
+    }
+
+    fn end_command(&mut self, mut command: GraphCommand) {
+        command.end(&self.components, &self.edges, &self.nodes);
+        self.history.push(command);
+        self.refresh_problems();
+    }
+
+    /// Revalidates the graph and marks the nodes of components with problems
+    fn refresh_problems(&mut self) {
+        self.problems = validation::validate(&self.components, &self.get_field_context());
+        for graphnode in self.nodes.values_mut() {
+            graphnode.node.has_error = self
+                .problems
+                .iter()
+                .any(|problem| problem.get_component_id() == Some(graphnode.component_id));
+        }
+    }
+
+    /// Jumps a running layout animation to its end and records it
+    fn finish_layout_animation(&mut self) {
+        if let Some(animation) = self.layout_animation.take() {
+            for (id, center) in animation.get_targets() {
+                if let Some(graphnode) = self.nodes.get_mut(id) {
+                    graphnode.node.translate_to(*center);
+                }
+            }
+            self.end_command(animation.command);
+        }
+    }
+
+    /// Drops any in progress interaction, since it may refer to entries that no longer exist
+    fn clear_interaction(&mut self) {
+        self.alignment_guides.clear();
+        self.current_edge = None;
+        self.dragged_edge = None;
+        self.drag_origins.clear();
+        self.is_panning = false;
+        self.left_clicked_node = None;
+        self.pending_drag = None;
+        self.right_clicked_node = None;
+        self.right_pressed_position = None;
+        self.selected_edge = None;
+        self.selected_nodes.clear();
+        self.selection_box = None;
+        self.sync_selection_flags();
+    }
+
+    /// Highlights exactly the selected nodes and edge
+    fn sync_selection_flags(&mut self) {
+        for (id, graphnode) in &mut self.nodes {
+            graphnode.node.is_selected = self.selected_nodes.contains(id);
+        }
+        for (id, edge) in &mut self.edges {
+            edge.is_selected = self.selected_edge == Some(*id);
+        }
+    }
+
+    /// Returns the closest fully connected edge within picking distance of `position`, in graph coordinates
+    fn get_edge_at(&self, position: Point) -> Option<Uuid> {
+        let tolerance = EDGE_PICK_TOLERANCE / self.view.zoom;
+        self.edges
+            .iter()
+            .filter(|(_, edge)| {
+                matches!(
+                    (&edge.from, &edge.to),
+                    (EdgeConnection::Node(_), EdgeConnection::Node(_))
+                )
+            })
+            .filter_map(|(id, edge)| Some((*id, edge.distance_to(position, &self.nodes)?)))
+            .filter(|(_, distance)| *distance <= tolerance)
+            .min_by(|a, b| a.1.total_cmp(&b.1))
+            .map(|(id, _)| id)
+    }
+
+    /// Removes an edge from the graph and its nodes, and disconnects the components it joined
+    fn remove_edge(&mut self, edge_id: Uuid) {
+        let edge = match self.edges.remove(&edge_id) {
+            Some(edge) => edge,
+            None => return,
+        };
+        for graphnode in self.nodes.values_mut() {
+            graphnode.edges.retain(|id| *id != edge_id);
+        }
+
+        let component_id = |connection: &EdgeConnection| match connection {
+            EdgeConnection::Node(id) => self.nodes.get(id).map(|graphnode| graphnode.component_id),
+            EdgeConnection::Point(_) => None,
+        };
+        if let (Some(from_component_id), Some(to_component_id)) =
+            (component_id(&edge.from), component_id(&edge.to))
+        {
+            if let Some(from_component) = self.components.get_mut(&from_component_id) {
+                from_component.delete_to(to_component_id);
+            }
+            if let Some(to_component) = self.components.get_mut(&to_component_id) {
+                to_component.delete_from();
+            }
+        }
+    }
+
+    /// Returns the cursor position in graph coordinates, if it's over the graph
+    fn cursor_position(&self, cursor: Cursor) -> Option<Point> {
+        cursor
+            .position_in(self.bounds)
+            .map(|position| self.view.to_graph(position))
+    }
+
+    /// Connects the components of two nodes with a new edge, without checking validity
+    fn connect_nodes(&mut self, edge_id: Uuid, from_node_id: Uuid, to_node_id: Uuid) {
+        let (from_component_id, to_component_id) =
+            match (self.nodes.get(&from_node_id), self.nodes.get(&to_node_id)) {
+                (Some(from_node), Some(to_node)) => (from_node.component_id, to_node.component_id),
+                _ => return,
+            };
+        if let Some(from_component) = self.components.get_mut(&from_component_id) {
+            from_component.connect_to(to_component_id);
+        }
+        if let Some(to_component) = self.components.get_mut(&to_component_id) {
+            to_component.connect_from(from_component_id);
+        }
+
+        self.edges.insert(
+            edge_id,
+            Edge::new(
+                EdgeConnection::Node(from_node_id),
+                EdgeConnection::Node(to_node_id),
+            ),
+        );
+        for node_id in [from_node_id, to_node_id] {
+            if let Some(graphnode) = self.nodes.get_mut(&node_id) {
+                graphnode.edges.push(edge_id);
+            }
+        }
+    }
+
+    /// Advances the layout animation, returns true if nodes moved
+    pub fn animation_tick(&mut self, now: Instant) -> bool {
+        let (centers, is_finished) = match &self.layout_animation {
+            Some(animation) => animation.get_centers(now),
+            None => return false,
+        };
+        for (id, center) in centers {
+            if let Some(graphnode) = self.nodes.get_mut(&id) {
+                graphnode.node.translate_to(center);
+            }
+        }
+        if is_finished {
+            self.finish_layout_animation();
+        }
+        true
+    }
+
+    /// Arranges the nodes as a tree growing out from the base, animated and undoable as one step
+    pub fn auto_layout(&mut self, orientation: LayoutOrientation) {
+        self.finish_layout_animation();
+
+        let targets = layout::tree_layout(&self.components, &self.nodes, orientation);
+        let from: HashMap<Uuid, Point> = targets
+            .keys()
+            .filter_map(|id| Some((*id, self.nodes.get(id)?.node.bounds.center())))
+            .collect();
+        if from.iter().all(|(id, center)| targets[id] == *center) {
+            return;
+        }
+
+        let node_ids: Vec<Uuid> = targets.keys().copied().collect();
+        let command = self.begin_command(&[], &[], &node_ids);
+        self.layout_animation = Some(LayoutAnimation::new(command, from, targets));
+    }
+
+    pub fn copy_pressed(&mut self) {
+        if let Some(fragment) = self.copy_selection() {
+            self.clipboard = Some(fragment);
+        }
+    }
+
+    fn copy_selection(&self) -> Option<GraphFragment> {
+        if self.selected_nodes.is_empty() {
+            return None;
+        }
+        let node_ids: Vec<Uuid> = self.selected_nodes.iter().copied().collect();
+        Some(GraphFragment::new(
+            &self.components,
+            &self.edges,
+            &self.nodes,
+            &node_ids,
+        ))
+    }
+
+    pub fn cut_pressed(&mut self) {
+        self.copy_pressed();
+        self.delete_pressed();
+    }
+
+    /// Duplicates everything outboard of a single selected joint and hangs the copy off
+    /// the same inboard body. Any other selection is duplicated as is.
+    pub fn duplicate_pressed(&mut self) {
+        let offset = Vector::new(DUPLICATE_OFFSET, DUPLICATE_OFFSET);
+
+        let selected_joint = match self.selected_nodes.iter().next() {
+            Some(node_id) if self.selected_nodes.len() == 1 => self
+                .nodes
+                .get(node_id)
+                .and_then(|graphnode| self.components.get(&graphnode.component_id))
+                .filter(|component| matches!(component, MultibodyComponent::Joint(_))),
+            _ => None,
+        };
+
+        let joint = match selected_joint {
+            Some(joint) => joint,
+            None => {
+                if let Some(mut fragment) = self.copy_selection() {
+                    fragment.translate_by(offset);
+                    self.insert_fragment(fragment.with_new_ids(), None);
+                }
+                return;
+            }
+        };
+
+        // walk the tree outboard of the joint
+        let inboard_node_id = joint
+            .get_from_id()
+            .and_then(|id| self.components.get(&id))
+            .map(|component| component.get_node_id());
+        let mut component_ids = vec![joint.get_component_id()];
+        let mut node_ids = Vec::new();
+        while let Some(id) = component_ids.pop() {
+            if let Some(component) = self.components.get(&id) {
+                if !node_ids.contains(&component.get_node_id()) {
+                    node_ids.push(component.get_node_id());
+                    component_ids.extend(component.get_to_id());
+                }
+            }
+        }
+
+        let mut fragment =
+            GraphFragment::new(&self.components, &self.edges, &self.nodes, &node_ids)
+                .with_new_ids();
+        fragment.translate_by(offset);
+
+        // the copied joint is the only one whose inboard connection was dropped
+        let root_node_id = fragment
+            .components
+            .values()
+            .find(|component| {
+                matches!(component, MultibodyComponent::Joint(_)) && component.get_from_id().is_none()
+            })
+            .map(|component| component.get_node_id());
+
+        let connection = match (inboard_node_id, root_node_id) {
+            (Some(inboard_node_id), Some(root_node_id)) => Some((inboard_node_id, root_node_id)),
+            _ => None,
+        };
+        self.insert_fragment(fragment, connection);
+    }
+
+    /// Adds the fragment as a single undo step and selects it.
+    /// Names are made unique, a second base is left out,
+    /// and `connection` optionally links an existing node to a pasted one.
+    fn insert_fragment(&mut self, mut fragment: GraphFragment, connection: Option<(Uuid, Uuid)>) {
+        let has_base = self
+            .components
+            .values()
+            .any(|component| matches!(component, MultibodyComponent::Base(_)));
+        if has_base {
+            let node_ids: Vec<Uuid> = fragment
+                .nodes
+                .iter()
+                .filter(|(_, graphnode)| {
+                    !matches!(
+                        fragment.components.get(&graphnode.component_id),
+                        Some(MultibodyComponent::Base(_))
+                    )
+                })
+                .map(|(id, _)| *id)
+                .collect();
+            fragment = GraphFragment::new(
+                &fragment.components,
+                &fragment.edges,
+                &fragment.nodes,
+                &node_ids,
+            );
+        }
+
+        let mut names: HashSet<String> = self
+            .components
+            .values()
+            .map(|component| component.get_name().to_string())
+            .collect();
+        for component in fragment.components.values_mut() {
+            let name = unique_name(component.get_name(), &names);
+            names.insert(name.clone());
+            component.set_name(name);
+        }
+
+        let pasted_node_ids: HashSet<Uuid> = fragment.nodes.keys().copied().collect();
+        let mut component_ids: Vec<Uuid> = fragment.components.keys().copied().collect();
+        let mut edge_ids: Vec<Uuid> = fragment.edges.keys().copied().collect();
+        let mut node_ids: Vec<Uuid> = pasted_node_ids.iter().copied().collect();
+        let connection_edge_id = Uuid::new_v4();
+        if let Some((from_node_id, _)) = connection {
+            if let Some(from_node) = self.nodes.get(&from_node_id) {
+                component_ids.push(from_node.component_id);
+                edge_ids.push(connection_edge_id);
+                node_ids.push(from_node_id);
+            }
+        }
+        let command = self.begin_command(&component_ids, &edge_ids, &node_ids);
+
+        self.components.extend(fragment.components);
+        self.edges.extend(fragment.edges);
+        self.nodes.extend(fragment.nodes);
+        if let Some((from_node_id, to_node_id)) = connection {
+            self.connect_nodes(connection_edge_id, from_node_id, to_node_id);
+        }
+        self.end_command(command);
+
+        self.selected_nodes = pasted_node_ids;
+        self.sync_selection_flags();
+    }
+
+    /// Returns true if a component already uses `name`
+    pub fn is_name_taken(&self, name: &str) -> bool {
+        self.components
+            .values()
+            .any(|component| component.get_name() == name)
+    }
+
+    /// Fails if `name` is blank or a component other than `component_id` already uses it,
+    /// surrounding whitespace doesn't count.
+    /// Everything else refers to components by id, so a rename needs nothing else updated.
+    fn check_name(&self, name: &str, component_id: Uuid) -> Result<(), GraphErrors> {
+        let name = name.trim();
+        if name.is_empty() {
+            return Err(GraphErrors::EmptyName(component_id));
+        }
+        if validation::is_name_taken_by_other(component_id, name, &self.components) {
+            return Err(GraphErrors::DuplicateName(component_id));
+        }
+        Ok(())
+    }
+
+    /// Fields that use a parameter may have become valid or invalid
+    pub fn parameters_changed(&mut self) {
+        self.refresh_problems();
+    }
+
+    pub fn can_paste(&self) -> bool {
+        self.clipboard.is_some()
+    }
+
+    /// Pastes the clipboard centered on the cursor
+    pub fn paste_pressed(&mut self) {
+        let mut fragment = match &self.clipboard {
+            Some(clipboard) => clipboard.with_new_ids(),
+            None => return,
+        };
+
+        if let (Some(bounds), Some(position)) = (fragment.get_bounds(), self.last_cursor_position) {
+            fragment.translate_by(self.view.to_graph(position) - bounds.center());
+        }
+        self.insert_fragment(fragment, None);
+    }
+
+    /// Fields are evaluated again first, so the system uses the current parameters
     pub fn create_multibody_system(&mut self) -> Result<MultibodySystem, GraphErrors> {
+        self.evaluate_parameters()?;
+
         let mut body_counter: usize = 0;
         let mut joint_counter: usize = 0;
 
@@ -116,6 +592,11 @@ impl Graph {
                         return Err(GraphErrors::JointMissingTo(*id));
                     };
 
+                    // ensure the joint leads to a body
+                    if validation::is_missing_outer_body(joint.get_to_id(), &self.components) {
+                        return Err(GraphErrors::JointNoOuterBody(*id));
+                    }
+
                     // if the from id equals the base id, the joint is connected to the base
                     if from_id == base_id {
                         base_joints.push(*id);
@@ -137,6 +618,15 @@ impl Graph {
             return Err(GraphErrors::NoBaseConnections);
         }
 
+        // the traversal below would never finish on a loop
+        if let Some(id) = self
+            .components
+            .keys()
+            .find(|id| validation::is_in_cycle(**id, &self.components))
+        {
+            return Err(GraphErrors::InCycle(*id));
+        }
+
         // recursively clone, identify, and push the componentns to make the multibody tree
         let result = self.traverse_component(
             base,
@@ -157,91 +647,392 @@ impl Graph {
 
     pub fn cursor_moved(&mut self, cursor: Cursor) -> bool {
         let mut redraw = false;
-        let cursor_position = cursor.position_in(self.bounds);
-
-        // Handle left-clicked node dragging
-        if let Some(clicked_node_id) = self.left_clicked_node {
-            if let Some(graphnode) = self.nodes.get_mut(&clicked_node_id) {
-                if let Some(position) = cursor_position {
-                    graphnode.node.translate_to(position);
-                    redraw = true;
-                }
-            }
-        } else if self.is_clicked {
-            // Handle graph translating
-            if let Some(graph_cursor_position) = cursor_position {
-                if let Some(last_position) = self.last_cursor_position {
-                    let delta = graph_cursor_position - last_position;
-                    self.nodes.iter_mut().for_each(|(_, graphnode)| {
-                        graphnode.node.translate_by(delta);
-                    });
-                    redraw = true;
+        self.hovered_node = self.get_snappable_node(cursor);
+        let screen_position = cursor.position_in(self.bounds);
+        let cursor_position = screen_position.map(|position| self.view.to_graph(position));
+
+        if let (Some(screen_position), Some(last_position)) =
+            (screen_position, self.last_cursor_position)
+        {
+            let delta = screen_position - last_position;
+            if let (Some(edge_id), Some(position)) = (self.dragged_edge, cursor_position) {
+                // Handle bending an edge
+                if let Some(edge) = self.edges.get_mut(&edge_id) {
+                    edge.set_handle(position, &self.nodes);
                 }
+                redraw = true;
+            } else if let Some(clicked_node_id) = self.left_clicked_node {
+                // Handle dragging the whole selection
+                self.drag_offset = self.drag_offset + delta * (1.0 / self.view.zoom);
+                self.drag_selection(clicked_node_id);
+                redraw = true;
+            } else if self.is_panning {
+                // Handle graph panning
+                self.view.pan(delta);
+                redraw = true;
             }
         }
 
+        // Handle box selection
+        if let (Some(selection_box), Some(position)) = (&mut self.selection_box, cursor_position) {
+            selection_box.1 = position;
+            redraw = true;
+        }
+
         // Update last cursor position
         if let Some(position) = cursor_position {
-            self.last_cursor_position = Some(position);
+            self.last_cursor_position = screen_position;
 
-            // Handle right-clicked node for edge drawing
-            if let Some(clicked_node_id) = self.right_clicked_node {
-                if let Some(edge_id) = self.current_edge {
-                    if let Some(edge) = self.edges.get_mut(&edge_id) {
-                        edge.to = EdgeConnection::Point(position);
-                    }
-                } else {
-                    let new_edge = Edge::new(
-                        EdgeConnection::Node(clicked_node_id),
-                        EdgeConnection::Point(position),
-                    );
-                    let new_edge_id = Uuid::new_v4();
-                    self.edges.insert(new_edge_id, new_edge);
-                    self.current_edge = Some(new_edge_id);
-
-                    // Add the edge to the from node
-                    if let Some(from_node) = self.nodes.get_mut(&clicked_node_id) {
-                        from_node.edges.push(new_edge_id);
+            if let Some(edge_id) = self.current_edge {
+                // Handle the loose end of the edge being drawn
+                let is_valid = self
+                    .get_pending_connection(edge_id, cursor)
+                    .map(|(from_node_id, to_node_id)| self.is_valid_node_connection(from_node_id, to_node_id));
+                if let Some(edge) = self.edges.get_mut(&edge_id) {
+                    match edge.from {
+                        EdgeConnection::Node(_) => edge.to = EdgeConnection::Point(position),
+                        EdgeConnection::Point(_) => edge.from = EdgeConnection::Point(position),
                     }
+                    edge.is_valid = is_valid;
                 }
                 redraw = true;
+            } else if let Some(clicked_node_id) = self.right_clicked_node {
+                // Handle right-clicked node for edge drawing
+                let new_edge = Edge::new(
+                    EdgeConnection::Node(clicked_node_id),
+                    EdgeConnection::Point(position),
+                );
+                // the edge is only added to its nodes once it's connected
+                let new_edge_id = Uuid::new_v4();
+                self.edges.insert(new_edge_id, new_edge);
+                self.current_edge = Some(new_edge_id);
+                redraw = true;
             }
         }
 
         redraw
     }
 
+    /// Moves the selection to its origins plus the drag offset, snapping each node to the grid
+    fn drag_selection(&mut self, grabbed_node_id: Uuid) {
+        for (id, origin) in &self.drag_origins {
+            if let Some(graphnode) = self.nodes.get_mut(id) {
+                let position = self.grid.snap(*origin + self.drag_offset);
+                graphnode.node.translate_by(position - graphnode.node.bounds.position());
+            }
+        }
+
+        self.alignment_guides = match self.nodes.get(&grabbed_node_id) {
+            Some(grabbed) => grid::alignment_guides(
+                grabbed.node.bounds,
+                self.nodes
+                    .iter()
+                    .filter(|(id, _)| !self.drag_origins.contains_key(id))
+                    .map(|(_, graphnode)| &graphnode.node.bounds),
+                ALIGNMENT_TOLERANCE / self.view.zoom,
+            ),
+            None => Vec::new(),
+        };
+    }
+
     pub fn delete_pressed(&mut self) {
-        if let Some(selected_node_id) = self.selected_node.take() {
-            if let Some(selected_node) = self.nodes.remove(&selected_node_id) {
-                // Remove edges from all nodes and edges collection
-                for edge_id in selected_node.edges {
-                    for node in self.nodes.values_mut() {
-                        node.edges.retain(|x| x != &edge_id);
-                    }
-                    self.edges.remove(&edge_id);
-                }
+        let selected_edge_id = self.selected_edge.take();
+        let selected_node_ids: Vec<Uuid> = self.selected_nodes.drain().collect();
+        if selected_node_ids.is_empty() {
+            if let Some(edge_id) = selected_edge_id {
+                self.delete_edges(&[edge_id]);
+            }
+            return;
+        }
 
-                if let Some(component) = self.components.get(&selected_node.component_id) {
-                    // Remove the component from components
-                    self.components.remove(&selected_node.component_id);
-                }
+        // record every entry the deletion touches so it can be undone
+        let mut edge_ids: Vec<Uuid> = selected_node_ids
+            .iter()
+            .filter_map(|id| self.nodes.get(id))
+            .flat_map(|graphnode| graphnode.edges.iter().copied())
+            .chain(selected_edge_id)
+            .collect();
+        edge_ids.sort();
+        edge_ids.dedup();
+        // neighbors are recorded too, their components get disconnected
+        let node_ids: Vec<Uuid> = self
+            .nodes
+            .iter()
+            .filter(|(id, graphnode)| {
+                selected_node_ids.contains(id)
+                    || graphnode.edges.iter().any(|edge_id| edge_ids.contains(edge_id))
+            })
+            .map(|(id, _)| *id)
+            .collect();
+        let component_ids: Vec<Uuid> = node_ids
+            .iter()
+            .filter_map(|id| self.nodes.get(id))
+            .map(|graphnode| graphnode.component_id)
+            .collect();
+        let command = self.begin_command(&component_ids, &edge_ids, &node_ids);
+
+        // Remove edges while both ends still exist, so their components are disconnected
+        for edge_id in edge_ids {
+            self.remove_edge(edge_id);
+        }
+        for selected_node_id in selected_node_ids {
+            if let Some(selected_node) = self.nodes.remove(&selected_node_id) {
+                self.components.remove(&selected_node.component_id);
             }
         }
+        self.end_command(command);
     }
 
+    /// Deletes edges and disconnects the components they joined, as one undo step
+    fn delete_edges(&mut self, edge_ids: &[Uuid]) {
+        let node_ids: Vec<Uuid> = self
+            .nodes
+            .iter()
+            .filter(|(_, graphnode)| graphnode.edges.iter().any(|id| edge_ids.contains(id)))
+            .map(|(id, _)| *id)
+            .collect();
+        let component_ids: Vec<Uuid> = node_ids
+            .iter()
+            .filter_map(|id| self.nodes.get(id))
+            .map(|graphnode| graphnode.component_id)
+            .collect();
+        let command = self.begin_command(&component_ids, edge_ids, &node_ids);
+        for edge_id in edge_ids {
+            self.remove_edge(*edge_id);
+        }
+        self.end_command(command);
+    }
+
+    /// Deletes every edge of a node but keeps the node
+    pub fn disconnect_node(&mut self, node_id: Uuid) {
+        let edge_ids = match self.nodes.get(&node_id) {
+            Some(graphnode) if !graphnode.edges.is_empty() => graphnode.edges.clone(),
+            _ => return,
+        };
+        self.delete_edges(&edge_ids);
+    }
+
+    /// Swaps the inboard and outboard ends of an edge, as one undo step
+    pub fn reverse_edge(&mut self, edge_id: Uuid) {
+        let (from_node_id, to_node_id) = match self.edges.get(&edge_id) {
+            Some(Edge {
+                from: EdgeConnection::Node(from_node_id),
+                to: EdgeConnection::Node(to_node_id),
+                ..
+            }) => (*from_node_id, *to_node_id),
+            _ => return,
+        };
+        if !self.is_valid_node_connection(to_node_id, from_node_id) {
+            return;
+        }
+        let component_ids: Vec<Uuid> = [from_node_id, to_node_id]
+            .iter()
+            .filter_map(|id| self.nodes.get(id))
+            .map(|graphnode| graphnode.component_id)
+            .collect();
+
+        let command = self.begin_command(&component_ids, &[edge_id], &[from_node_id, to_node_id]);
+        self.remove_edge(edge_id);
+        self.connect_nodes(edge_id, to_node_id, from_node_id);
+        self.end_command(command);
+    }
+
+    /// Nothing changes and no undo step is recorded if any of the dummy's fields are invalid
+    /// or its name is taken
     pub fn edit_component(&mut self, dummy: &DummyComponent, component_id: Uuid) -> Result<(), GraphErrors> {
-        let component = match self.components.get(&component_id) {
-            Some(component) => component,
+        let mut component = match self.components.get(&component_id) {
+            Some(component) => component.clone(),
             None => return Err(GraphErrors::IdNotFound(component_id)),
         };
+        self.check_name(&dummy.get_name(), component_id)?;
+        let dummy = with_trimmed_name(dummy);
+        component
+            .inherit_from(&dummy, &self.get_field_context())
+            .map_err(GraphErrors::Multibody)?;
+        // applying unchanged fields shouldn't leave a step that undoes nothing
+        if self.components.get(&component_id) == Some(&component) {
+            return Ok(());
+        }
 
-        if let Some(component) = self.components.get_mut(&component_id) {
-            component.inherit_from(dummy);
+        let command = self.begin_command(&[component_id], &[], &[]);
+        self.components.insert(component_id, component);
+        self.end_command(command);
+        Ok(())
+    }
+
+    /// Evaluates every component's fields again, so a changed parameter reaches
+    /// everything that uses it. Nothing changes if any of them fail.
+    fn evaluate_parameters(&mut self) -> Result<(), GraphErrors> {
+        let context = self.get_field_context();
+        let mut evaluated = Vec::new();
+        for (id, component) in &self.components {
+            let component = validation::evaluate_component(component, &context)
+                .map_err(|error| GraphErrors::InvalidFields(*id, error))?;
+            evaluated.push((*id, component));
         }
+        self.components.extend(evaluated);
         Ok(())
     }
 
+    /// Zooms and pans so every node is visible, returns true if the view changed
+    pub fn fit_all(&mut self) -> bool {
+        let content = self
+            .nodes
+            .values()
+            .map(|graphnode| graphnode.node.bounds)
+            .reduce(|a, b| a.union(&b));
+
+        match content {
+            Some(content) => {
+                let viewport = Rectangle::new(Point::ORIGIN, self.bounds.size());
+                self.view = ViewTransform::fit(content, viewport, 50.0);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Selects the node of a component and centers the view on it
+    pub fn focus_component(&mut self, component_id: Uuid) {
+        let node_id = match self
+            .nodes
+            .iter()
+            .find(|(_, graphnode)| graphnode.component_id == component_id)
+        {
+            Some((id, _)) => *id,
+            None => return,
+        };
+        let center = self.nodes[&node_id].node.bounds.center();
+        self.view.translation = Vector::new(
+            self.bounds.width / 2.0 - center.x * self.view.zoom,
+            self.bounds.height / 2.0 - center.y * self.view.zoom,
+        );
+        self.selected_edge = None;
+        self.selected_nodes.clear();
+        self.selected_nodes.insert(node_id);
+        self.sync_selection_flags();
+    }
+
+    pub fn get_alignment_guides(&self) -> &[(Point, Point)] {
+        &self.alignment_guides
+    }
+
+    /// `cursor` is relative to the canvas, like the graph bounds
+    pub fn is_over_port(&self, cursor: Cursor) -> bool {
+        self.cursor_position(cursor)
+            .and_then(|position| self.get_port_at(position))
+            .is_some()
+    }
+
+    /// What the dummies' field text is evaluated against
+    pub fn get_field_context(&self) -> FieldContext {
+        FieldContext {
+            unit_system: self.unit_system,
+            variables: self.parameters.get_variables(),
+        }
+    }
+
+    /// The error as shown to the user, after the name of the component it's about
+    pub fn describe_error(&self, error: &GraphErrors) -> String {
+        match error
+            .get_component_id()
+            .and_then(|id| self.components.get(&id))
+        {
+            Some(component) => format!("{}: {}", component.get_name(), error),
+            None => error.to_string(),
+        }
+    }
+
+    /// The node, edge or empty canvas at a screen position within the bounds
+    pub fn get_context_target(&self, screen_position: Point) -> ContextTarget {
+        let position = self.view.to_graph(screen_position);
+        let node_id = self
+            .nodes
+            .iter()
+            .find(|(_, graphnode)| graphnode.node.bounds.contains(position))
+            .map(|(id, _)| *id);
+        match (node_id, self.get_edge_at(position)) {
+            (Some(node_id), _) => ContextTarget::Node(node_id),
+            (None, Some(edge_id)) => ContextTarget::Edge(edge_id),
+            (None, None) => ContextTarget::Canvas,
+        }
+    }
+
+    /// Outputs of components that still exist, the ones of deleted components are left out
+    pub fn get_outputs(&self) -> HashSet<Uuid> {
+        self.outputs
+            .iter()
+            .filter(|id| self.components.contains_key(id))
+            .copied()
+            .collect()
+    }
+
+    pub fn is_output(&self, component_id: Uuid) -> bool {
+        self.outputs.contains(&component_id)
+    }
+
+    /// Recorded as an undo step. Outputs of deleted components are kept,
+    /// so undoing the delete brings them back.
+    pub fn toggle_output(&mut self, component_id: Uuid) {
+        let mut command = self.begin_command(&[], &[], &[]);
+        history::toggle(&mut self.outputs, component_id);
+        command.set_output_toggled(component_id);
+        self.end_command(command);
+    }
+
+    pub fn get_problems(&self) -> &[GraphErrors] {
+        &self.problems
+    }
+
+    /// Returns the problems of the component under the cursor, for its tooltip
+    pub fn get_hovered_problems(&self) -> Vec<&GraphErrors> {
+        let component_id = match self.hovered_node.and_then(|id| self.nodes.get(&id)) {
+            Some(graphnode) => graphnode.component_id,
+            None => return Vec::new(),
+        };
+        self.problems
+            .iter()
+            .filter(|problem| problem.get_component_id() == Some(component_id))
+            .collect()
+    }
+
+    pub fn is_animating(&self) -> bool {
+        self.layout_animation.is_some()
+    }
+
+    /// Returns the component of the only selected node, `None` when none or several are selected
+    pub fn get_selected_component(&self) -> Option<Uuid> {
+        if self.selected_nodes.len() != 1 {
+            return None;
+        }
+        let node_id = self.selected_nodes.iter().next()?;
+        self.nodes.get(node_id).map(|graphnode| graphnode.component_id)
+    }
+
+    /// Returns the box being dragged out for selection, in graph coordinates
+    pub fn get_selection_box(&self) -> Option<Rectangle> {
+        self.selection_box
+            .map(|(start, end)| rectangle_from_corners(start, end))
+    }
+
+    /// Replaces the graph contents, dropping any click, edge drawing or history
+    pub fn load(
+        &mut self,
+        components: HashMap<Uuid, MultibodyComponent>,
+        edges: HashMap<Uuid, Edge>,
+        nodes: HashMap<Uuid, GraphNode>,
+        outputs: HashSet<Uuid>,
+    ) {
+        self.components = components;
+        self.edges = edges;
+        self.nodes = nodes;
+        self.outputs = outputs;
+        self.layout_animation = None;
+        self.history.clear();
+        self.clear_interaction();
+        self.refresh_problems();
+    }
+
     /// Finds a node within snapping distance of the cursor on the graph, if any.
     ///
     /// # Arguments
@@ -258,7 +1049,7 @@ impl Graph {
     /// If such a node is found, its UUID is returned.
     fn get_snappable_node(&self, cursor: Cursor) -> Option<Uuid> {
         // Check if the cursor is within the graph's bounds
-        if let Some(cursor_position) = cursor.position_in(self.bounds) {
+        if let Some(cursor_position) = self.cursor_position(cursor) {
             // Find the first node that the cursor is in snapping distance of
             return self
                 .nodes
@@ -278,6 +1069,46 @@ impl Graph {
         None
     }
 
+    /// Returns the node and port within picking distance of `position`, in graph coordinates
+    fn get_port_at(&self, position: Point) -> Option<(Uuid, NodePort)> {
+        let tolerance = PORT_PICK_TOLERANCE / self.view.zoom;
+        self.nodes.iter().find_map(|(id, graphnode)| {
+            graphnode
+                .node
+                .get_port_at(position, tolerance)
+                .map(|port| (*id, port))
+        })
+    }
+
+    /// Returns the (from, to) nodes the edge being drawn would connect if dropped at the cursor.
+    /// Dropping on either a port or the body of a node counts.
+    fn get_pending_connection(&self, edge_id: Uuid, cursor: Cursor) -> Option<(Uuid, Uuid)> {
+        let edge = self.edges.get(&edge_id)?;
+        let target_node_id = self.get_snappable_node(cursor).or_else(|| {
+            let position = self.cursor_position(cursor)?;
+            self.get_port_at(position).map(|(id, _)| id)
+        })?;
+        match (&edge.from, &edge.to) {
+            (EdgeConnection::Node(from_node_id), EdgeConnection::Point(_)) => {
+                Some((*from_node_id, target_node_id))
+            }
+            (EdgeConnection::Point(_), EdgeConnection::Node(to_node_id)) => {
+                Some((target_node_id, *to_node_id))
+            }
+            _ => None,
+        }
+    }
+
+    fn is_valid_node_connection(&self, from_node_id: Uuid, to_node_id: Uuid) -> bool {
+        match (self.nodes.get(&from_node_id), self.nodes.get(&to_node_id)) {
+            (Some(from_node), Some(to_node)) => {
+                from_node_id != to_node_id
+                    && self.is_valid_connection(&from_node.component_id, &to_node.component_id)
+            }
+            _ => false,
+        }
+    }
+
     fn is_valid_connection(&self, from_component_id: &Uuid, to_component_id: &Uuid) -> bool {
         if let Some(from_component) = self.components.get(from_component_id) {
             if let Some(to_component) = self.components.get(to_component_id) {
@@ -305,28 +1136,91 @@ impl Graph {
         }
     }
 
-    pub fn left_button_pressed(&mut self, cursor: Cursor) {
+    /// A plain click selects the clicked node, or starts a selection box on empty canvas.
+    /// Holding shift or ctrl adds to or removes from the selection instead.
+    pub fn left_button_pressed(&mut self, cursor: Cursor, modifiers: Modifiers) {
         self.left_clicked_node = None;
 
-        if let Some(cursor_position) = cursor.position_in(self.bounds) {
-            self.is_clicked = true;
-            self.last_cursor_position = Some(cursor_position);
-
-            // Clear the nodes' selected flags and determine the clicked node
-            for (id, graphnode) in &mut self.nodes {
-                let node = &mut graphnode.node;
-                node.is_selected = false;
-                node.is_clicked(cursor_position, &MouseButton::Left);
+        let screen_position = match cursor.position_in(self.bounds) {
+            Some(position) => position,
+            None => return,
+        };
+        self.last_cursor_position = Some(screen_position);
+        let cursor_position = self.view.to_graph(screen_position);
+        let toggle = modifiers.shift() || modifiers.command();
 
-                if node.is_left_clicked {
-                    node.is_selected = true;
-                    self.left_clicked_node = Some(*id);
+        // Grabbing the handle of the selected edge bends it
+        if let Some(edge_id) = self.selected_edge {
+            let handle = self
+                .edges
+                .get(&edge_id)
+                .and_then(|edge| edge.get_handle(&self.nodes));
+            if let Some(handle) = handle {
+                if handle.distance(cursor_position) <= HANDLE_RADIUS / self.view.zoom {
+                    self.pending_drag = Some(self.begin_command(&[], &[edge_id], &[]));
+                    self.dragged_edge = Some(edge_id);
+                    return;
                 }
             }
         }
 
-        // Update selected_node based on whether a node was clicked
-        self.selected_node = self.left_clicked_node;
+        // Dragging from a port draws a new edge, from an input port it's drawn backwards
+        if let (false, Some((node_id, port))) = (toggle, self.get_port_at(cursor_position)) {
+            let loose_end = EdgeConnection::Point(cursor_position);
+            let edge = match port {
+                NodePort::Input => Edge::new(loose_end, EdgeConnection::Node(node_id)),
+                NodePort::Output => Edge::new(EdgeConnection::Node(node_id), loose_end),
+            };
+            // the edge is only added to its nodes once it's connected
+            let edge_id = Uuid::new_v4();
+            self.edges.insert(edge_id, edge);
+            self.current_edge = Some(edge_id);
+            return;
+        }
+
+        // Determine the clicked node
+        for (id, graphnode) in &mut self.nodes {
+            graphnode.node.is_clicked(cursor_position, &MouseButton::Left);
+            if graphnode.node.is_left_clicked {
+                self.left_clicked_node = Some(*id);
+            }
+        }
+
+        match self.left_clicked_node {
+            Some(id) if toggle => {
+                if !self.selected_nodes.remove(&id) {
+                    self.selected_nodes.insert(id);
+                }
+                // toggling doesn't start a drag
+                self.left_clicked_node = None;
+            }
+            Some(id) => {
+                self.selected_edge = None;
+                // grabbing a selected node drags the whole selection
+                if !self.selected_nodes.contains(&id) {
+                    self.selected_nodes.clear();
+                    self.selected_nodes.insert(id);
+                }
+                let node_ids: Vec<Uuid> = self.selected_nodes.iter().copied().collect();
+                self.pending_drag = Some(self.begin_command(&[], &[], &node_ids));
+                self.drag_offset = Vector::new(0.0, 0.0);
+                self.drag_origins = node_ids
+                    .iter()
+                    .filter_map(|id| Some((*id, self.nodes.get(id)?.node.bounds.position())))
+                    .collect();
+            }
+            None => {
+                if !toggle {
+                    self.selected_nodes.clear();
+                }
+                self.selected_edge = None;
+                match self.get_edge_at(cursor_position) {
+                    Some(edge_id) if !toggle => self.selected_edge = Some(edge_id),
+                    _ => self.selection_box = Some((cursor_position, cursor_position)),
+                }
+            }
+        }
+        self.sync_selection_flags();
     }
 
     pub fn left_button_released(
@@ -334,50 +1228,50 @@ impl Graph {
         release_event: &MouseButtonReleaseEvents,
         cursor: Cursor,
     ) -> Option<GraphMessage> {
-        self.is_clicked = false;
         let mut message = None;
 
         if let Some(cursor_position) = cursor.position_in(self.bounds) {
             self.last_cursor_position = Some(cursor_position);
         }
 
-        //clear the nodes selected flags, to be reapplied on click
-        self.nodes.iter_mut().for_each(|(_, graphnode)| {
-            graphnode.node.is_selected = false;
-        });
+        self.alignment_guides.clear();
+        self.dragged_edge = None;
+        self.drag_origins.clear();
+        self.complete_edge(cursor);
 
-        if let Some(clicked_node_id) = self.left_clicked_node {
-            if let Some(graphnode) = self.nodes.get_mut(&clicked_node_id) {
-                let clicked_node = &mut graphnode.node;
-                match release_event {
-                    MouseButtonReleaseEvents::DoubleClick => {
-                        clicked_node.is_selected = true;
-                        message = Some(GraphMessage::EditComponent(graphnode.component_id));
-                    }
-                    MouseButtonReleaseEvents::SingleClick => {
-                        clicked_node.is_selected = true;
-                    }
-                    MouseButtonReleaseEvents::Held => {
-                        clicked_node.is_selected = true;
-                    }
-                    MouseButtonReleaseEvents::Nothing => {}
+        // Only drags that moved a node or bent an edge are worth an undo step
+        if let Some(command) = self.pending_drag.take() {
+            if command.changes_node_bounds(&self.nodes) || command.changes_edges(&self.edges) {
+                self.end_command(command);
+            }
+        }
+
+        // Add everything the box touches to the selection
+        if let Some((start, end)) = self.selection_box.take() {
+            let selection_box = rectangle_from_corners(start, end);
+            for (id, graphnode) in &self.nodes {
+                if selection_box.intersects(&graphnode.node.bounds) {
+                    self.selected_nodes.insert(*id);
                 }
             }
-        } else {
-            // Clear all selections if no node was clicked
-            self.nodes
-                .values_mut()
-                .for_each(|graphnode| graphnode.node.is_selected = false);
-            self.selected_node = None;
         }
-        self.left_clicked_node = None;
+
+        if let Some(clicked_node_id) = self.left_clicked_node.take() {
+            if let MouseButtonReleaseEvents::DoubleClick = release_event {
+                if self.nodes.contains_key(&clicked_node_id) {
+                    message = Some(GraphMessage::EditComponent);
+                }
+            }
+        }
+        self.sync_selection_flags();
         message
     }
 
     pub fn right_button_pressed(&mut self, cursor: Cursor) {
         self.right_clicked_node = None;
 
-        if let Some(cursor_position) = cursor.position_in(self.bounds) {
+        if let Some(screen_position) = cursor.position_in(self.bounds) {
+            let cursor_position = self.view.to_graph(screen_position);
             for (id, graphnode) in &mut self.nodes {
                 let node = &mut graphnode.node;
                 node.is_clicked(cursor_position, &MouseButton::Right);
@@ -385,142 +1279,106 @@ impl Graph {
                     self.right_clicked_node = Some(*id);
                 }
             }
-            self.last_cursor_position = Some(cursor_position);
+            // dragging empty canvas with the right button pans
+            self.is_panning = self.right_clicked_node.is_none();
+            self.last_cursor_position = Some(screen_position);
+            self.right_pressed_position = Some(screen_position);
         }
     }
 
     /// Handles the release of the right mouse button, finalizing or canceling an edge creation process.
-    ///
-    /// This function checks if there is an active edge creation process (stored in `self.current_edge`).
-    /// If there is, it attempts to finalize the edge by connecting it to a node near the cursor.
-    /// If any step in this process fails, it gracefully exits by removing the edge and resetting the state.
+    /// Releasing where it was pressed opens the context menu instead, with its target selected.
     ///
     /// # Arguments
     ///
     /// * `cursor` - The current position of the cursor.
-    pub fn right_button_released(&mut self, cursor: Cursor) {
-        // Get the current edge ID if it exists, return if it does not
-        let edge_id = match self.current_edge {
-            Some(id) => id,
-            None => return,
-        };
-
-        // Helper function to clean up in case anything goes wrong
-        let graceful_exit = |graph: &mut Self| {
-            graph.edges.remove(&edge_id);
-            graph.current_edge = None;
-            graph.right_clicked_node = None;
-        };
+    pub fn right_button_released(&mut self, cursor: Cursor) -> Option<GraphMessage> {
+        self.is_panning = false;
+        self.complete_edge(cursor);
+        // a click without a drag never started an edge
+        self.right_clicked_node = None;
 
-        // Get the edge if it exists, return if it does not
-        let edge = match self.edges.get(&edge_id) {
-            Some(edge) => edge,
-            None => {
-                graceful_exit(self);
-                return;
-            }
-        };
+        let pressed_position = self.right_pressed_position.take()?;
+        let position = cursor.position_in(self.bounds)?;
+        if position.distance(pressed_position) > RIGHT_CLICK_TOLERANCE {
+            return None;
+        }
+        let target = self.get_context_target(position);
+        self.select(target);
+        Some(GraphMessage::OpenContextMenu(ContextMenu { position, target }))
+    }
 
-        // Get the from_node_id, return if it is an EdgeConnection::Point
-        let from_node_id = match edge.from {
-            EdgeConnection::Node(id) => id,
-            _ => {
-                graceful_exit(self);
-                return;
+    /// Selects just the target, or clears the selection for the canvas
+    pub fn select(&mut self, target: ContextTarget) {
+        self.selected_nodes.clear();
+        self.selected_edge = None;
+        match target {
+            ContextTarget::Canvas => {}
+            ContextTarget::Edge(edge_id) => self.selected_edge = Some(edge_id),
+            ContextTarget::Node(node_id) => {
+                self.selected_nodes.insert(node_id);
             }
-        };
+        }
+        self.sync_selection_flags();
+    }
 
-        // Get the component ID of the from node, return if it does not exist
-        let from_component_id = match self.nodes.get(&from_node_id).map(|node| node.component_id) {
-            Some(id) => id,
-            None => {
-                graceful_exit(self);
-                return;
-            }
-        };
+    /// Where pastes and new components are placed, a screen position within the bounds
+    pub fn set_cursor_position(&mut self, position: Point) {
+        self.last_cursor_position = Some(position);
+    }
 
-        // Attempt to get the snappable node near the cursor, return if it does not exist
-        let to_node_id = match self.get_snappable_node(cursor) {
+    /// Connects the edge being drawn to the node under the cursor, or drops it
+    /// if there isn't one or the connection isn't valid.
+    fn complete_edge(&mut self, cursor: Cursor) {
+        // Get the current edge ID if it exists, return if it does not
+        let edge_id = match self.current_edge.take() {
             Some(id) => id,
-            None => {
-                graceful_exit(self);
-                return;
-            }
+            None => return,
         };
+        self.right_clicked_node = None;
 
-        // Get the component ID of the to node, return if it does not exist
-        let to_component_id = match self.nodes.get(&to_node_id).map(|node| node.component_id) {
-            Some(id) => id,
+        let connection = self
+            .get_pending_connection(edge_id, cursor)
+            .filter(|(from_node_id, to_node_id)| self.is_valid_node_connection(*from_node_id, *to_node_id));
+        let (from_node_id, to_node_id) = match connection {
+            Some(connection) => connection,
             None => {
-                graceful_exit(self);
+                self.edges.remove(&edge_id);
                 return;
             }
         };
+        let component_ids: Vec<Uuid> = [from_node_id, to_node_id]
+            .iter()
+            .filter_map(|id| self.nodes.get(id))
+            .map(|graphnode| graphnode.component_id)
+            .collect();
 
-        // Check if the connection is valid between the from and to components
-        let valid_connection = self.is_valid_connection(&from_component_id, &to_component_id);
-
-        // Connect the components if the connection is valid
-        if valid_connection {
-            // Get the from component, return if it does not exist
-            let from_component = match self.components.get_mut(&from_component_id) {
-                Some(component) => component,
-                None => {
-                    graceful_exit(self);
-                    return;
-                }
-            };
-            from_component.connect_to(to_component_id);
-
-            // Get the to component, return if it does not exist
-            let to_component = match self.components.get_mut(&to_component_id) {
-                Some(component) => component,
-                None => {
-                    graceful_exit(self);
-                    return;
-                }
-            };
-            to_component.connect_from(from_component_id);
-
-            // Get the to node, return if it does not exist
-            let to_node = match self.nodes.get_mut(&to_node_id) {
-                Some(node) => node,
-                None => {
-                    graceful_exit(self);
-                    return;
-                }
-            };
-            to_node.edges.push(edge_id);
-
-            // Update the edge to connect to the to_node, return if the edge does not exist
-            let edge = match self.edges.get_mut(&edge_id) {
-                Some(edge) => edge,
-                None => {
-                    graceful_exit(self);
-                    return;
-                }
-            };
-            edge.to = EdgeConnection::Node(to_node_id);
-        }
-
-        // Clear the current edge and right-clicked node state
-        self.current_edge = None;
-        self.right_clicked_node = None;
+        // The in progress edge isn't part of the graph yet, so it's recorded as added
+        let mut command = self.begin_command(&component_ids, &[edge_id], &[from_node_id, to_node_id]);
+        command.set_edge_added(edge_id);
+        self.edges.remove(&edge_id);
+        self.connect_nodes(edge_id, from_node_id, to_node_id);
+        self.end_command(command);
     }
 
     pub fn save_component(&mut self, dummy: &DummyComponent) -> Result<(), GraphErrors> {
         // only do this if we can save the node
         if let Some(last_cursor_position) = self.last_cursor_position {
+            let last_cursor_position = self.view.to_graph(last_cursor_position);
             // Generate unique IDs for component, node, and name
             let component_id = Uuid::new_v4();
             let node_id = Uuid::new_v4();
             let name_id = Uuid::new_v4();
+            self.check_name(&dummy.get_name(), component_id)?;
+            let dummy = &with_trimmed_name(dummy);
 
             // Create the new component from it's dummy
-            let new_component = match MultibodyComponent::from_dummy(component_id, dummy, node_id) {
-                Ok(component) => component,
-                Err(error) => return Err(GraphErrors::Multibody(error)),
-            };
+            let context = self.get_field_context();
+            let new_component =
+                match MultibodyComponent::from_dummy(component_id, dummy, node_id, &context) {
+                    Ok(component) => component,
+                    Err(error) => return Err(GraphErrors::Multibody(error)),
+                };
 
             // Calculate the bounds for the new node
             let size = Size::new(100.0, 50.0); // TODO: make width dynamic based on name length
@@ -536,8 +1394,10 @@ impl Graph {
             let graph_node = GraphNode::new(component_id, new_node);
 
             // Insert the new component and node into their respective collections
+            let command = self.begin_command(&[component_id], &[], &[node_id]);
             self.components.insert(component_id, new_component);
             self.nodes.insert(node_id, graph_node);
+            self.end_command(command);
         }
         Ok(())        
     }
@@ -605,8 +1465,203 @@ impl Graph {
         result
     }
 
+    /// Moves the view by `delta` screen pixels
+    pub fn pan(&mut self, delta: Vector) {
+        self.view.pan(delta);
+    }
+
+    /// Returns true if the graph changed
+    pub fn redo(&mut self) -> bool {
+        self.finish_layout_animation();
+        let changed = self.history.redo(
+            &mut self.components,
+            &mut self.edges,
+            &mut self.nodes,
+            &mut self.outputs,
+        );
+        if changed {
+            self.clear_interaction();
+            self.refresh_problems();
+        }
+        changed
+    }
+
+    /// Returns true if the graph changed
+    pub fn undo(&mut self) -> bool {
+        self.finish_layout_animation();
+        let changed = self.history.undo(
+            &mut self.components,
+            &mut self.edges,
+            &mut self.nodes,
+            &mut self.outputs,
+        );
+        if changed {
+            self.clear_interaction();
+            self.refresh_problems();
+        }
+        changed
+    }
+
+    /// Zooms centered on the cursor, returns true if the view changed
+    pub fn wheel_scrolled(&mut self, cursor: Cursor, delta: ScrollDelta) -> bool {
+        let screen_position = match cursor.position_in(self.bounds) {
+            Some(position) => position,
+            None => return false,
+        };
+
+        // a line is a notch on most mice, pixels come from touchpads
+        let steps = match delta {
+            ScrollDelta::Lines { y, .. } => y,
+            ScrollDelta::Pixels { y, .. } => y / 50.0,
+        };
+        self.view.zoom_at(screen_position, 1.1_f32.powf(steps));
+        true
+    }
+
     pub fn window_resized(&mut self, size: Size) {
         self.bounds.height = size.height;
         self.bounds.width = size.width;
     }
 }
+
+/// The dummy with the whitespace around its name removed, as names are stored
+fn with_trimmed_name(dummy: &DummyComponent) -> DummyComponent {
+    let mut dummy = dummy.clone();
+    dummy.set_name(dummy.get_name().trim());
+    dummy
+}
+
+fn rectangle_from_corners(a: Point, b: Point) -> Rectangle {
+    Rectangle::new(
+        Point::new(a.x.min(b.x), a.y.min(b.y)),
+        Size::new((a.x - b.x).abs(), (a.y - b.y).abs()),
+    )
+}
+
+/// Returns `name` if it's free, otherwise bumps its trailing number until it is, e.g. body3 -> body4
+fn unique_name(name: &str, taken: &HashSet<String>) -> String {
+    if !taken.contains(name) {
+        return name.to_string();
+    }
+    let prefix = name.trim_end_matches(|c: char| c.is_ascii_digit());
+    let start = name[prefix.len()..].parse::<usize>().unwrap_or(1);
+    (start + 1..)
+        .map(|number| format!("{}{}", prefix, number))
+        .find(|candidate| !taken.contains(candidate))
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ui::dummies::{DummyBase, DummyBody, DummyRevolute};
+    use test_utils::add_component;
+
+    fn add_body(graph: &mut Graph) -> Uuid {
+        let dummy = DummyComponent::Body(DummyBody::new(Uuid::new_v4()));
+        add_component(&mut graph.components, &mut graph.nodes, "body1", dummy, Point::ORIGIN).0
+    }
+
+    #[test]
+    fn test_duplicate_names_are_rejected() {
+        let mut graph = Graph::default();
+        let first_id = add_body(&mut graph);
+        let second_id = add_body(&mut graph);
+        graph.parameters_changed();
+        assert!(graph
+            .get_problems()
+            .iter()
+            .any(|problem| matches!(problem, GraphErrors::DuplicateName(id) if *id == second_id)));
+
+        let dummy_of = |graph: &Graph, id: Uuid| {
+            DummyComponent::from_component(&graph.components[&id], graph.unit_system)
+        };
+        let mut dummy = dummy_of(&graph, second_id);
+        dummy.set_name(" body2 ");
+        assert!(graph.edit_component(&dummy, second_id).is_ok());
+        assert_eq!(graph.components[&second_id].get_name(), "body2");
+
+        // keeping its own name is fine, taking another's isn't, even with spaces around it
+        let mut dummy = dummy_of(&graph, first_id);
+        assert!(graph.edit_component(&dummy, first_id).is_ok());
+        dummy.set_name("body2 ");
+        assert!(matches!(
+            graph.edit_component(&dummy, first_id),
+            Err(GraphErrors::DuplicateName(id)) if id == first_id
+        ));
+        dummy.set_name("  ");
+        assert!(matches!(
+            graph.edit_component(&dummy, first_id),
+            Err(GraphErrors::EmptyName(id)) if id == first_id
+        ));
+        assert_eq!(graph.components[&first_id].get_name(), "body1");
+    }
+
+    #[test]
+    fn test_reverse_and_disconnect() {
+        let mut graph = Graph::default();
+        let base = DummyComponent::Base(DummyBase::new(Uuid::new_v4()));
+        let joint = DummyComponent::Revolute(DummyRevolute::new(Uuid::new_v4()));
+        let (base_id, base_node_id) =
+            add_component(&mut graph.components, &mut graph.nodes, "base", base, Point::ORIGIN);
+        let (_, joint_node_id) = add_component(
+            &mut graph.components,
+            &mut graph.nodes,
+            "joint",
+            joint,
+            Point::new(200.0, 0.0),
+        );
+        let edge_id = Uuid::new_v4();
+        graph.connect_nodes(edge_id, base_node_id, joint_node_id);
+
+        let edge_ends =
+            |graph: &Graph| match (&graph.edges[&edge_id].from, &graph.edges[&edge_id].to) {
+                (EdgeConnection::Node(from), EdgeConnection::Node(to)) => (*from, *to),
+                _ => panic!("the edge should stay connected"),
+            };
+        graph.reverse_edge(edge_id);
+        assert_eq!(edge_ends(&graph), (joint_node_id, base_node_id));
+        assert!(graph.undo());
+        assert_eq!(edge_ends(&graph), (base_node_id, joint_node_id));
+
+        graph.disconnect_node(joint_node_id);
+        assert!(graph.edges.is_empty());
+        assert!(graph.components[&base_id].get_to_id().is_empty());
+    }
+
+    #[test]
+    fn test_delete_edge_and_nodes_is_one_step() {
+        let mut graph = Graph::default();
+        let base = DummyComponent::Base(DummyBase::new(Uuid::new_v4()));
+        let joint = DummyComponent::Revolute(DummyRevolute::new(Uuid::new_v4()));
+        let (_, base_node_id) =
+            add_component(&mut graph.components, &mut graph.nodes, "base", base, Point::ORIGIN);
+        let (_, joint_node_id) = add_component(
+            &mut graph.components,
+            &mut graph.nodes,
+            "joint",
+            joint,
+            Point::new(200.0, 0.0),
+        );
+        let edge_id = Uuid::new_v4();
+        graph.connect_nodes(edge_id, base_node_id, joint_node_id);
+        let body_id = add_body(&mut graph);
+
+        graph.selected_edge = Some(edge_id);
+        let body_node_id = graph
+            .nodes
+            .iter()
+            .find(|(_, graphnode)| graphnode.component_id == body_id)
+            .map(|(id, _)| *id)
+            .unwrap();
+        graph.selected_nodes.insert(body_node_id);
+        graph.delete_pressed();
+        assert!(graph.edges.is_empty());
+        assert!(!graph.components.contains_key(&body_id));
+
+        assert!(graph.undo());
+        assert!(graph.edges.contains_key(&edge_id));
+        assert!(graph.components.contains_key(&body_id));
+        assert!(!graph.undo());
+    }
+}
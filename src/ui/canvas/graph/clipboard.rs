@@ -0,0 +1,240 @@
+use iced::{Rectangle, Vector};
+use std::collections::HashMap;
+use uuid::Uuid;
+
+use super::GraphNode;
+use crate::multibody::{MultibodyComponent, MultibodyTrait};
+use crate::ui::canvas::edge::{Edge, EdgeConnection};
+
+/// A copied set of nodes with their components and the edges between them.
+/// Connections to anything outside the set are dropped.
+#[derive(Debug, Clone, Default)]
+pub struct GraphFragment {
+    pub components: HashMap<Uuid, MultibodyComponent>,
+    pub edges: HashMap<Uuid, Edge>,
+    pub nodes: HashMap<Uuid, GraphNode>,
+}
+
+impl GraphFragment {
+    pub fn new(
+        components: &HashMap<Uuid, MultibodyComponent>,
+        edges: &HashMap<Uuid, Edge>,
+        nodes: &HashMap<Uuid, GraphNode>,
+        node_ids: &[Uuid],
+    ) -> Self {
+        let mut fragment = Self::default();
+
+        for id in node_ids {
+            if let Some(graphnode) = nodes.get(id) {
+                if let Some(component) = components.get(&graphnode.component_id) {
+                    fragment.nodes.insert(*id, graphnode.clone());
+                    fragment
+                        .components
+                        .insert(graphnode.component_id, component.clone());
+                }
+            }
+        }
+
+        // keep only the edges with both ends in the fragment
+        let is_internal = |connection: &EdgeConnection| match connection {
+            EdgeConnection::Node(id) => node_ids.contains(id),
+            EdgeConnection::Point(_) => false,
+        };
+        for graphnode in fragment.nodes.values_mut() {
+            graphnode.edges.retain(|edge_id| match edges.get(edge_id) {
+                Some(edge) => is_internal(&edge.from) && is_internal(&edge.to),
+                None => false,
+            });
+            for edge_id in &graphnode.edges {
+                fragment.edges.insert(*edge_id, edges[edge_id].clone());
+            }
+        }
+
+        // and only the component connections within the fragment
+        let component_ids: Vec<Uuid> = fragment.components.keys().copied().collect();
+        for component in fragment.components.values_mut() {
+            if let Some(from_id) = component.get_from_id() {
+                if !component_ids.contains(&from_id) {
+                    component.delete_from();
+                }
+            }
+            let external_ids: Vec<Uuid> = component
+                .get_to_id()
+                .iter()
+                .filter(|id| !component_ids.contains(id))
+                .copied()
+                .collect();
+            for id in external_ids {
+                component.delete_to(id);
+            }
+        }
+
+        fragment
+    }
+
+    /// Returns the union of the node bounds, `None` if the fragment is empty
+    pub fn get_bounds(&self) -> Option<Rectangle> {
+        self.nodes
+            .values()
+            .map(|graphnode| graphnode.node.bounds)
+            .reduce(|a, b| a.union(&b))
+    }
+
+    pub fn translate_by(&mut self, delta: Vector) {
+        self.nodes
+            .values_mut()
+            .for_each(|graphnode| graphnode.node.translate_by(delta));
+    }
+
+    /// Returns a copy with fresh ids for every component, node and edge,
+    /// keeping the connections between them. References to anything outside
+    /// the fragment are dropped, like when it was copied.
+    pub fn with_new_ids(&self) -> Self {
+        let ids: HashMap<Uuid, Uuid> = self
+            .components
+            .keys()
+            .chain(self.edges.keys())
+            .chain(self.nodes.keys())
+            .map(|id| (*id, Uuid::new_v4()))
+            .collect();
+
+        let components: HashMap<Uuid, MultibodyComponent> = self
+            .components
+            .iter()
+            .filter_map(|(id, component)| {
+                let mut component = component.clone();
+                component.set_component_id(ids[id]);
+                component.set_node_id(*ids.get(&component.get_node_id())?);
+
+                if let Some(from_id) = component.get_from_id() {
+                    component.delete_from();
+                    if let Some(from_id) = ids.get(&from_id) {
+                        component.connect_from(*from_id);
+                    }
+                }
+                for to_id in component.get_to_id().clone() {
+                    component.delete_to(to_id);
+                    if let Some(to_id) = ids.get(&to_id) {
+                        component.connect_to(*to_id);
+                    }
+                }
+                Some((ids[id], component))
+            })
+            .collect();
+
+        let remap = |connection: &EdgeConnection| match connection {
+            EdgeConnection::Node(id) => ids.get(id).map(|id| EdgeConnection::Node(*id)),
+            EdgeConnection::Point(point) => Some(EdgeConnection::Point(*point)),
+        };
+        let edges: HashMap<Uuid, Edge> = self
+            .edges
+            .iter()
+            .filter_map(|(id, edge)| {
+                let mut edge = edge.clone();
+                edge.from = remap(&edge.from)?;
+                edge.to = remap(&edge.to)?;
+                Some((ids[id], edge))
+            })
+            .collect();
+
+        let nodes = self
+            .nodes
+            .iter()
+            .filter_map(|(id, graphnode)| {
+                let mut graphnode = graphnode.clone();
+                graphnode.component_id = *ids.get(&graphnode.component_id)?;
+                graphnode.edges = graphnode
+                    .edges
+                    .iter()
+                    .filter_map(|edge_id| ids.get(edge_id))
+                    .filter(|edge_id| edges.contains_key(edge_id))
+                    .copied()
+                    .collect();
+                Some((ids[id], graphnode))
+            })
+            .collect();
+
+        Self {
+            components,
+            edges,
+            nodes,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ui::canvas::graph::test_utils::add_component;
+    use crate::ui::dummies::{DummyBase, DummyComponent, DummyRevolute};
+    use iced::Point;
+
+    #[test]
+    fn test_graph_fragment_keeps_internal_connections() {
+        let mut components = HashMap::new();
+        let mut edges = HashMap::new();
+        let mut nodes = HashMap::new();
+
+        // base -> revolute1 -> revolute2
+        let mut ids = Vec::new();
+        for (index, dummy) in [
+            DummyComponent::Base(DummyBase::new(Uuid::new_v4())),
+            DummyComponent::Revolute(DummyRevolute::new(Uuid::new_v4())),
+            DummyComponent::Revolute(DummyRevolute::new(Uuid::new_v4())),
+        ]
+        .into_iter()
+        .enumerate()
+        {
+            ids.push(add_component(
+                &mut components,
+                &mut nodes,
+                &format!("component{}", index),
+                dummy,
+                Point::new(index as f32 * 200.0, 0.0),
+            ));
+        }
+        for pair in ids.windows(2) {
+            let ((from_component, from_node), (to_component, to_node)) = (pair[0], pair[1]);
+            components.get_mut(&from_component).unwrap().connect_to(to_component);
+            components.get_mut(&to_component).unwrap().connect_from(from_component);
+            let edge_id = Uuid::new_v4();
+            edges.insert(
+                edge_id,
+                Edge::new(EdgeConnection::Node(from_node), EdgeConnection::Node(to_node)),
+            );
+            nodes.get_mut(&from_node).unwrap().edges.push(edge_id);
+            nodes.get_mut(&to_node).unwrap().edges.push(edge_id);
+        }
+
+        let fragment = GraphFragment::new(&components, &edges, &nodes, &[ids[1].1, ids[2].1]);
+        assert_eq!(fragment.components.len(), 2);
+        assert_eq!(fragment.edges.len(), 1);
+        assert!(fragment.components[&ids[1].0].get_from_id().is_none());
+        assert_eq!(fragment.components[&ids[1].0].get_to_id(), &vec![ids[2].0]);
+
+        let copy = fragment.with_new_ids();
+        assert!(copy.components.keys().all(|id| !components.contains_key(id)));
+        assert!(copy.nodes.keys().all(|id| !nodes.contains_key(id)));
+
+        // the copied chain is still connected through the new ids
+        let root = copy
+            .components
+            .values()
+            .find(|component| component.get_from_id().is_none())
+            .unwrap();
+        let child_id = root.get_to_id()[0];
+        assert_eq!(copy.components[&child_id].get_from_id(), Some(root.get_component_id()));
+        let edge = copy.edges.values().next().unwrap();
+        assert!(matches!(edge.from, EdgeConnection::Node(id) if id == root.get_node_id()));
+        assert!(copy.nodes[&root.get_node_id()].edges.iter().all(|id| copy.edges.contains_key(id)));
+
+        // a fragment that lost part of itself drops what referred to it instead of panicking
+        let mut broken = fragment.clone();
+        broken.nodes.remove(&ids[2].1);
+        let copy = broken.with_new_ids();
+        assert_eq!(copy.nodes.len(), 1);
+        assert_eq!(copy.components.len(), 1);
+        assert!(copy.edges.is_empty());
+        assert!(copy.nodes.values().all(|graphnode| graphnode.edges.is_empty()));
+    }
+}
@@ -0,0 +1,98 @@
+use iced::Point;
+use uuid::Uuid;
+
+use super::layout::LayoutOrientation;
+
+/// What was under the cursor when the menu was opened
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ContextTarget {
+    Canvas,
+    Edge(Uuid),
+    Node(Uuid),
+}
+
+/// Everything the menu can do, which of them it offers depends on the target
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ContextAction {
+    /// Holds the nodebar dummy of the kind of component to add
+    AddComponent(Uuid),
+    AutoLayout(LayoutOrientation),
+    Delete,
+    DisconnectAll,
+    Duplicate,
+    Edit,
+    FitView,
+    Paste,
+    Rename,
+    Reverse,
+    ToggleOutput,
+}
+
+/// Opened by a right click that didn't drag
+#[derive(Debug, Clone, Copy)]
+pub struct ContextMenu {
+    /// Where it was opened, in screen coordinates within the graph bounds
+    pub position: Point,
+    pub target: ContextTarget,
+}
+
+impl ContextMenu {
+    /// `new_components` are the nodebar dummies offered on empty canvas
+    pub fn get_actions(&self, new_components: &[Uuid]) -> Vec<ContextAction> {
+        match self.target {
+            ContextTarget::Canvas => {
+                let mut actions: Vec<ContextAction> = new_components
+                    .iter()
+                    .map(|id| ContextAction::AddComponent(*id))
+                    .collect();
+                actions.extend([
+                    ContextAction::Paste,
+                    ContextAction::AutoLayout(LayoutOrientation::TopDown),
+                    ContextAction::AutoLayout(LayoutOrientation::LeftRight),
+                    ContextAction::FitView,
+                ]);
+                actions
+            }
+            ContextTarget::Edge(_) => vec![ContextAction::Delete, ContextAction::Reverse],
+            ContextTarget::Node(_) => vec![
+                ContextAction::Edit,
+                ContextAction::Rename,
+                ContextAction::Duplicate,
+                ContextAction::Delete,
+                ContextAction::DisconnectAll,
+                ContextAction::ToggleOutput,
+            ],
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ui::canvas::graph::test_utils::add_component;
+    use crate::ui::canvas::graph::{Graph, GraphMessage};
+    use crate::ui::dummies::{DummyBase, DummyComponent};
+    use iced::mouse::Cursor;
+
+    #[test]
+    fn test_context_menu_actions() {
+        let mut graph = Graph::default();
+        let base = DummyComponent::Base(DummyBase::new(Uuid::new_v4()));
+        let (_, base_node_id) =
+            add_component(&mut graph.components, &mut graph.nodes, "base", base, Point::ORIGIN);
+
+        // cursors are relative to the canvas, the graph starts right of the nodebar
+        let at = |x: f32, y: f32| Cursor::Available(Point::new(graph.bounds.x + x, y));
+        let (on_base, on_canvas, dragged) = (at(50.0, 25.0), at(50.0, 300.0), at(90.0, 300.0));
+
+        graph.right_button_pressed(on_base);
+        let menu = match graph.right_button_released(on_base) {
+            Some(GraphMessage::OpenContextMenu(menu)) => menu,
+            _ => panic!("a right click should open the menu"),
+        };
+        assert_eq!(menu.target, ContextTarget::Node(base_node_id));
+        assert!(menu.get_actions(&[]).contains(&ContextAction::ToggleOutput));
+        graph.right_button_pressed(on_canvas);
+        assert!(graph.right_button_released(dragged).is_none());
+    }
+}
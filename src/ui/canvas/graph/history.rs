@@ -0,0 +1,294 @@
+use std::collections::{HashMap, HashSet};
+use uuid::Uuid;
+
+use super::GraphNode;
+use crate::multibody::MultibodyComponent;
+use crate::ui::canvas::edge::Edge;
+
+/// Oldest commands are dropped once the undo stack grows past this
+const HISTORY_LIMIT: usize = 100;
+
+/// The state of one graph entry before and after a command, `None` if it didn't exist
+#[derive(Debug, Clone)]
+struct Change<T> {
+    id: Uuid,
+    before: Option<T>,
+    after: Option<T>,
+}
+
+fn apply<T: Clone>(map: &mut HashMap<Uuid, T>, id: Uuid, value: &Option<T>) {
+    match value {
+        Some(value) => {
+            map.insert(id, value.clone());
+        }
+        None => {
+            map.remove(&id);
+        }
+    }
+}
+
+/// Adds `id` if it's missing, removes it otherwise
+pub fn toggle(set: &mut HashSet<Uuid>, id: Uuid) {
+    if !set.remove(&id) {
+        set.insert(id);
+    }
+}
+
+fn record<T: Clone>(map: &HashMap<Uuid, T>, ids: &[Uuid]) -> Vec<Change<T>> {
+    ids.iter()
+        .map(|id| Change {
+            id: *id,
+            before: map.get(id).cloned(),
+            after: None,
+        })
+        .collect()
+}
+
+fn finish<T: Clone>(changes: &mut [Change<T>], map: &HashMap<Uuid, T>) {
+    changes
+        .iter_mut()
+        .for_each(|change| change.after = map.get(&change.id).cloned());
+}
+
+/// A reversible graph edit, recorded as the entries it touched.
+/// Call `begin` before mutating the graph and `end` after.
+#[derive(Debug, Clone, Default)]
+pub struct GraphCommand {
+    components: Vec<Change<MultibodyComponent>>,
+    edges: Vec<Change<Edge>>,
+    nodes: Vec<Change<GraphNode>>,
+    /// Components whose output flag the command flipped, flipping them again undoes it
+    toggled_outputs: Vec<Uuid>,
+}
+
+impl GraphCommand {
+    pub fn begin(
+        components: &HashMap<Uuid, MultibodyComponent>,
+        edges: &HashMap<Uuid, Edge>,
+        nodes: &HashMap<Uuid, GraphNode>,
+        component_ids: &[Uuid],
+        edge_ids: &[Uuid],
+        node_ids: &[Uuid],
+    ) -> Self {
+        Self {
+            components: record(components, component_ids),
+            edges: record(edges, edge_ids),
+            nodes: record(nodes, node_ids),
+            toggled_outputs: Vec::new(),
+        }
+    }
+
+    pub fn end(
+        &mut self,
+        components: &HashMap<Uuid, MultibodyComponent>,
+        edges: &HashMap<Uuid, Edge>,
+        nodes: &HashMap<Uuid, GraphNode>,
+    ) {
+        finish(&mut self.components, components);
+        finish(&mut self.edges, edges);
+        finish(&mut self.nodes, nodes);
+    }
+
+    /// Marks an edge as not existing before the command, for edges drawn before they're committed
+    pub fn set_edge_added(&mut self, id: Uuid) {
+        self.edges
+            .iter_mut()
+            .filter(|change| change.id == id)
+            .for_each(|change| change.before = None);
+    }
+
+    pub fn set_output_toggled(&mut self, id: Uuid) {
+        self.toggled_outputs.push(id);
+    }
+
+    /// Returns true if any recorded edge was changed, e.g. bent
+    pub fn changes_edges(&self, edges: &HashMap<Uuid, Edge>) -> bool {
+        self.edges
+            .iter()
+            .any(|change| change.before.as_ref() != edges.get(&change.id))
+    }
+
+    /// Returns true if any recorded node now has different bounds
+    pub fn changes_node_bounds(&self, nodes: &HashMap<Uuid, GraphNode>) -> bool {
+        self.nodes
+            .iter()
+            .any(|change| match (&change.before, nodes.get(&change.id)) {
+                (Some(before), Some(after)) => before.node.bounds != after.node.bounds,
+                _ => false,
+            })
+    }
+
+    fn undo(
+        &self,
+        components: &mut HashMap<Uuid, MultibodyComponent>,
+        edges: &mut HashMap<Uuid, Edge>,
+        nodes: &mut HashMap<Uuid, GraphNode>,
+        outputs: &mut HashSet<Uuid>,
+    ) {
+        self.components
+            .iter()
+            .for_each(|change| apply(components, change.id, &change.before));
+        self.edges
+            .iter()
+            .for_each(|change| apply(edges, change.id, &change.before));
+        self.nodes
+            .iter()
+            .for_each(|change| apply(nodes, change.id, &change.before));
+        self.toggled_outputs.iter().for_each(|id| toggle(outputs, *id));
+    }
+
+    fn redo(
+        &self,
+        components: &mut HashMap<Uuid, MultibodyComponent>,
+        edges: &mut HashMap<Uuid, Edge>,
+        nodes: &mut HashMap<Uuid, GraphNode>,
+        outputs: &mut HashSet<Uuid>,
+    ) {
+        self.components
+            .iter()
+            .for_each(|change| apply(components, change.id, &change.after));
+        self.edges
+            .iter()
+            .for_each(|change| apply(edges, change.id, &change.after));
+        self.nodes
+            .iter()
+            .for_each(|change| apply(nodes, change.id, &change.after));
+        self.toggled_outputs.iter().for_each(|id| toggle(outputs, *id));
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct History {
+    redo_stack: Vec<GraphCommand>,
+    undo_stack: Vec<GraphCommand>,
+}
+
+impl History {
+    pub fn clear(&mut self) {
+        self.redo_stack.clear();
+        self.undo_stack.clear();
+    }
+
+    /// Records a finished command, which invalidates anything that was undone
+    pub fn push(&mut self, command: GraphCommand) {
+        self.redo_stack.clear();
+        self.undo_stack.push(command);
+        if self.undo_stack.len() > HISTORY_LIMIT {
+            self.undo_stack.remove(0);
+        }
+    }
+
+    /// Returns true if there was something to redo
+    pub fn redo(
+        &mut self,
+        components: &mut HashMap<Uuid, MultibodyComponent>,
+        edges: &mut HashMap<Uuid, Edge>,
+        nodes: &mut HashMap<Uuid, GraphNode>,
+        outputs: &mut HashSet<Uuid>,
+    ) -> bool {
+        match self.redo_stack.pop() {
+            Some(command) => {
+                command.redo(components, edges, nodes, outputs);
+                self.undo_stack.push(command);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Returns true if there was something to undo
+    pub fn undo(
+        &mut self,
+        components: &mut HashMap<Uuid, MultibodyComponent>,
+        edges: &mut HashMap<Uuid, Edge>,
+        nodes: &mut HashMap<Uuid, GraphNode>,
+        outputs: &mut HashSet<Uuid>,
+    ) -> bool {
+        match self.undo_stack.pop() {
+            Some(command) => {
+                command.undo(components, edges, nodes, outputs);
+                self.redo_stack.push(command);
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ui::canvas::edge::EdgeConnection;
+    use crate::ui::canvas::node::Node;
+    use iced::{Point, Rectangle, Size, Vector};
+
+    #[test]
+    fn test_history_undo_redo() {
+        let mut components = HashMap::new();
+        let mut edges = HashMap::new();
+        let mut nodes = HashMap::new();
+        let mut outputs = HashSet::new();
+        let mut history = History::default();
+
+        let node_id = Uuid::new_v4();
+        let bounds = Rectangle::new(Point::ORIGIN, Size::new(100.0, 50.0));
+
+        // add a node, then move it
+        let mut command = GraphCommand::begin(&components, &edges, &nodes, &[], &[], &[node_id]);
+        nodes.insert(node_id, GraphNode::new(Uuid::new_v4(), Node::new(bounds)));
+        command.end(&components, &edges, &nodes);
+        history.push(command);
+
+        let mut command = GraphCommand::begin(&components, &edges, &nodes, &[], &[], &[node_id]);
+        nodes.get_mut(&node_id).unwrap().node.bounds.x = 20.0;
+        command.end(&components, &edges, &nodes);
+        history.push(command);
+
+        assert!(history.undo(&mut components, &mut edges, &mut nodes, &mut outputs));
+        assert_eq!(nodes[&node_id].node.bounds.x, 0.0);
+        assert!(history.undo(&mut components, &mut edges, &mut nodes, &mut outputs));
+        assert!(nodes.is_empty());
+        assert!(!history.undo(&mut components, &mut edges, &mut nodes, &mut outputs));
+
+        assert!(history.redo(&mut components, &mut edges, &mut nodes, &mut outputs));
+        assert!(history.redo(&mut components, &mut edges, &mut nodes, &mut outputs));
+        assert_eq!(nodes[&node_id].node.bounds.x, 20.0);
+        assert!(!history.redo(&mut components, &mut edges, &mut nodes, &mut outputs));
+
+        // a new command drops anything left to redo
+        history.undo(&mut components, &mut edges, &mut nodes, &mut outputs);
+        history.push(GraphCommand::default());
+        assert!(!history.redo(&mut components, &mut edges, &mut nodes, &mut outputs));
+
+        // flipping an output is undone by flipping it back
+        let component_id = Uuid::new_v4();
+        let mut command = GraphCommand::default();
+        toggle(&mut outputs, component_id);
+        command.set_output_toggled(component_id);
+        history.push(command);
+        assert!(history.undo(&mut components, &mut edges, &mut nodes, &mut outputs));
+        assert!(outputs.is_empty());
+        assert!(history.redo(&mut components, &mut edges, &mut nodes, &mut outputs));
+        assert!(outputs.contains(&component_id));
+    }
+
+    #[test]
+    fn test_history_ignores_edge_selection() {
+        let components = HashMap::new();
+        let nodes = HashMap::new();
+        let edge_id = Uuid::new_v4();
+        let mut edges = HashMap::from([(
+            edge_id,
+            Edge::new(
+                EdgeConnection::Point(Point::ORIGIN),
+                EdgeConnection::Point(Point::new(10.0, 0.0)),
+            ),
+        )]);
+
+        let command = GraphCommand::begin(&components, &edges, &nodes, &[], &[edge_id], &[]);
+        edges.get_mut(&edge_id).unwrap().is_selected = true;
+        assert!(!command.changes_edges(&edges));
+        edges.get_mut(&edge_id).unwrap().control = Some(Vector::new(0.0, 5.0));
+        assert!(command.changes_edges(&edges));
+    }
+}
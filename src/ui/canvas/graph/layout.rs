@@ -0,0 +1,210 @@
+use iced::{Point, Vector};
+use std::collections::{HashMap, HashSet};
+use std::time::{Duration, Instant};
+use uuid::Uuid;
+
+use super::history::GraphCommand;
+use super::GraphNode;
+use crate::multibody::{MultibodyComponent, MultibodyTrait};
+
+/// Distance between the centers of parent and child levels, in graph coordinates
+const LEVEL_SPACING: f32 = 120.0;
+/// Distance between the centers of neighboring leaves, in graph coordinates
+const SIBLING_SPACING: f32 = 150.0;
+const ANIMATION_DURATION: Duration = Duration::from_millis(300);
+
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum LayoutOrientation {
+    LeftRight,
+    #[default]
+    TopDown,
+}
+
+/// Arranges every tree hanging off a root (the base first, then anything disconnected)
+/// side by side. Leaves take consecutive slots and parents are centered over their children.
+/// The first root keeps its position so the view doesn't jump.
+///
+/// Returns the new center of each node, keyed by node id.
+pub fn tree_layout(
+    components: &HashMap<Uuid, MultibodyComponent>,
+    nodes: &HashMap<Uuid, GraphNode>,
+    orientation: LayoutOrientation,
+) -> HashMap<Uuid, Point> {
+    let mut roots: Vec<&MultibodyComponent> = components
+        .values()
+        .filter(|component| match component.get_from_id() {
+            Some(from_id) => !components.contains_key(&from_id),
+            None => true,
+        })
+        .collect();
+    roots.sort_by(|a, b| {
+        let a_key = (!matches!(a, MultibodyComponent::Base(_)), a.get_name());
+        let b_key = (!matches!(b, MultibodyComponent::Base(_)), b.get_name());
+        a_key.cmp(&b_key)
+    });
+
+    let mut next_slot = 0.0;
+    let mut slots = HashMap::new();
+    let mut visited = HashSet::new();
+    for root in &roots {
+        place(root.get_component_id(), 0, &mut next_slot, components, &mut visited, &mut slots);
+    }
+    // anything left is only reachable through a cycle
+    let mut remaining: Vec<&MultibodyComponent> = components
+        .values()
+        .filter(|component| !visited.contains(&component.get_component_id()))
+        .collect();
+    remaining.sort_by_key(|component| component.get_name());
+    for component in remaining {
+        place(component.get_component_id(), 0, &mut next_slot, components, &mut visited, &mut slots);
+    }
+
+    let mut centers: HashMap<Uuid, Point> = slots
+        .iter()
+        .filter_map(|(component_id, (breadth, depth))| {
+            let node_id = components.get(component_id)?.get_node_id();
+            let breadth = breadth * SIBLING_SPACING;
+            let depth = *depth as f32 * LEVEL_SPACING;
+            let center = match orientation {
+                LayoutOrientation::LeftRight => Point::new(depth, breadth),
+                LayoutOrientation::TopDown => Point::new(breadth, depth),
+            };
+            nodes.contains_key(&node_id).then_some((node_id, center))
+        })
+        .collect();
+
+    let anchor = roots
+        .first()
+        .map(|root| root.get_node_id())
+        .and_then(|node_id| Some((nodes.get(&node_id)?, centers.get(&node_id)?)));
+    if let Some((graphnode, center)) = anchor {
+        let offset: Vector = graphnode.node.bounds.center() - *center;
+        centers.values_mut().for_each(|center| *center = *center + offset);
+    }
+    centers
+}
+
+/// Places a component and everything outboard of it, returning its breadth slot
+fn place(
+    id: Uuid,
+    depth: usize,
+    next_slot: &mut f32,
+    components: &HashMap<Uuid, MultibodyComponent>,
+    visited: &mut HashSet<Uuid>,
+    slots: &mut HashMap<Uuid, (f32, usize)>,
+) -> Option<f32> {
+    if !visited.insert(id) {
+        return None;
+    }
+    let component = components.get(&id)?;
+
+    let child_slots: Vec<f32> = component
+        .get_to_id()
+        .iter()
+        .filter_map(|child_id| place(*child_id, depth + 1, next_slot, components, visited, slots))
+        .collect();
+
+    let breadth = match (child_slots.first(), child_slots.last()) {
+        (Some(first), Some(last)) => (first + last) / 2.0,
+        _ => {
+            let slot = *next_slot;
+            *next_slot += 1.0;
+            slot
+        }
+    };
+    slots.insert(id, (breadth, depth));
+    Some(breadth)
+}
+
+/// Moves nodes from their old centers to the layout, recorded as one undo step once finished
+#[derive(Debug)]
+pub struct LayoutAnimation {
+    pub command: GraphCommand,
+    from: HashMap<Uuid, Point>,
+    start: Instant,
+    to: HashMap<Uuid, Point>,
+}
+
+impl LayoutAnimation {
+    pub fn new(command: GraphCommand, from: HashMap<Uuid, Point>, to: HashMap<Uuid, Point>) -> Self {
+        Self {
+            command,
+            from,
+            start: Instant::now(),
+            to,
+        }
+    }
+
+    /// Returns the node centers at `now` and whether the animation has finished
+    pub fn get_centers(&self, now: Instant) -> (HashMap<Uuid, Point>, bool) {
+        let t = (now.saturating_duration_since(self.start).as_secs_f32()
+            / ANIMATION_DURATION.as_secs_f32())
+        .min(1.0);
+        // ease in and out
+        let eased = t * t * (3.0 - 2.0 * t);
+
+        let centers = self
+            .to
+            .iter()
+            .map(|(id, to)| {
+                let from = self.from.get(id).unwrap_or(to);
+                let center = Point::new(
+                    from.x + (to.x - from.x) * eased,
+                    from.y + (to.y - from.y) * eased,
+                );
+                (*id, center)
+            })
+            .collect();
+        (centers, t >= 1.0)
+    }
+
+    pub fn get_targets(&self) -> &HashMap<Uuid, Point> {
+        &self.to
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ui::canvas::graph::test_utils::add_component;
+    use crate::ui::dummies::{DummyBase, DummyComponent, DummyRevolute};
+
+    #[test]
+    fn test_tree_layout() {
+        let mut components = HashMap::new();
+        let mut nodes = HashMap::new();
+
+        let mut add = |name: &str, dummy: DummyComponent, position: Point| {
+            add_component(&mut components, &mut nodes, name, dummy, position)
+        };
+
+        // base -> (joint1 -> joint3, joint2)
+        let base = add("base", DummyComponent::Base(DummyBase::new(Uuid::new_v4())), Point::ORIGIN);
+        let joints: Vec<(Uuid, Uuid)> = (1..=3)
+            .map(|i| {
+                let dummy = DummyComponent::Revolute(DummyRevolute::new(Uuid::new_v4()));
+                add(&format!("joint{}", i), dummy, Point::new(500.0, 500.0))
+            })
+            .collect();
+        for (from, to) in [(base.0, joints[0].0), (base.0, joints[1].0), (joints[0].0, joints[2].0)] {
+            components.get_mut(&from).unwrap().connect_to(to);
+            components.get_mut(&to).unwrap().connect_from(from);
+        }
+
+        let centers = tree_layout(&components, &nodes, LayoutOrientation::TopDown);
+        assert_eq!(centers.len(), 4);
+
+        // the base stays put and sits centered above its children
+        let base_center = centers[&base.1];
+        assert_eq!(base_center, Point::new(50.0, 25.0));
+        let joint1 = centers[&joints[0].1];
+        let joint2 = centers[&joints[1].1];
+        assert_eq!(joint1.y, base_center.y + LEVEL_SPACING);
+        assert_eq!(joint2.y, joint1.y);
+        assert_eq!((joint1.x + joint2.x) / 2.0, base_center.x);
+        assert_eq!(centers[&joints[2].1].y, joint1.y + LEVEL_SPACING);
+
+        let left_right = tree_layout(&components, &nodes, LayoutOrientation::LeftRight);
+        assert_eq!(left_right[&joints[2].1].x, base_center.x + 2.0 * LEVEL_SPACING);
+    }
+}
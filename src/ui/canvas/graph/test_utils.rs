@@ -0,0 +1,30 @@
+use iced::{Point, Rectangle, Size};
+use std::collections::HashMap;
+use uuid::Uuid;
+
+use super::GraphNode;
+use crate::multibody::MultibodyComponent;
+use crate::ui::canvas::node::Node;
+use crate::ui::dummies::{DummyComponent, DummyTrait};
+use crate::ui::schema::FieldContext;
+
+/// Adds the component made from `dummy` under `name`, with a default sized node at `top_left`.
+/// Pass a graph's `components` and `nodes` to add it to the graph. Returns the component and node ids.
+pub fn add_component(
+    components: &mut HashMap<Uuid, MultibodyComponent>,
+    nodes: &mut HashMap<Uuid, GraphNode>,
+    name: &str,
+    mut dummy: DummyComponent,
+    top_left: Point,
+) -> (Uuid, Uuid) {
+    let (component_id, node_id) = (Uuid::new_v4(), Uuid::new_v4());
+    dummy.set_name(name);
+    let component =
+        MultibodyComponent::from_dummy(component_id, &dummy, node_id, &FieldContext::default())
+            .ok()
+            .unwrap();
+    components.insert(component_id, component);
+    let bounds = Rectangle::new(top_left, Size::new(100.0, 50.0));
+    nodes.insert(node_id, GraphNode::new(component_id, Node::new(bounds)));
+    (component_id, node_id)
+}
@@ -0,0 +1,297 @@
+use std::collections::{HashMap, HashSet};
+use uuid::Uuid;
+
+use super::GraphErrors;
+use crate::multibody::{MultibodyComponent, MultibodyErrors, MultibodyTrait};
+use crate::ui::dummies::DummyComponent;
+use crate::ui::schema::FieldContext;
+
+impl GraphErrors {
+    /// Returns the component the problem is about, `None` for problems with the graph as a whole
+    pub fn get_component_id(&self) -> Option<Uuid> {
+        match self {
+            GraphErrors::BodyInvalidId(id)
+            | GraphErrors::BodyMissingFrom(id)
+            | GraphErrors::DuplicateName(id)
+            | GraphErrors::EmptyName(id)
+            | GraphErrors::IdNotFound(id)
+            | GraphErrors::InCycle(id)
+            | GraphErrors::InvalidFields(id, _)
+            | GraphErrors::JointMissingFrom(id)
+            | GraphErrors::JointMissingTo(id)
+            | GraphErrors::JointNoOuterBody(id) => Some(*id),
+            GraphErrors::NoBase | GraphErrors::NoBaseConnections | GraphErrors::Multibody(_) => {
+                None
+            }
+        }
+    }
+
+    pub fn get_error_message(&self) -> &str {
+        match self {
+            GraphErrors::BodyInvalidId(_) => "Body has an invalid id.",
+            GraphErrors::BodyMissingFrom(_) => "Body is not connected to an inner joint.",
+            GraphErrors::DuplicateName(_) => "Another component has the same name.",
+            GraphErrors::EmptyName(_) => "Component needs a name.",
+            GraphErrors::IdNotFound(_) => "Component could not be found.",
+            GraphErrors::InCycle(_) => "Component is part of a loop, the model has to be a tree.",
+            GraphErrors::InvalidFields(_, _) => {
+                "A field can't be evaluated with the current parameters."
+            }
+            GraphErrors::JointMissingFrom(_) => "Joint is not connected to an inner body.",
+            GraphErrors::JointMissingTo(_) => "Joint is not connected to an outer body.",
+            GraphErrors::JointNoOuterBody(_) => "Joint has no outer body.",
+            GraphErrors::NoBase => "The model needs a base.",
+            GraphErrors::NoBaseConnections => "Nothing is connected to the base.",
+            GraphErrors::Multibody(_) => "A component could not be created.",
+        }
+    }
+}
+
+/// The message followed by what went wrong inside the component, if there's more to say
+impl std::fmt::Display for GraphErrors {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            GraphErrors::InvalidFields(_, error) | GraphErrors::Multibody(error) => {
+                write!(f, "{} {}", self.get_error_message(), error)
+            }
+            _ => write!(f, "{}", self.get_error_message()),
+        }
+    }
+}
+
+impl std::error::Error for GraphErrors {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            GraphErrors::InvalidFields(_, error) | GraphErrors::Multibody(error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+/// The component with its field text evaluated again in `context`
+pub fn evaluate_component(
+    component: &MultibodyComponent,
+    context: &FieldContext,
+) -> Result<MultibodyComponent, MultibodyErrors> {
+    let mut evaluated = component.clone();
+    let dummy = DummyComponent::from_component(component, context.unit_system);
+    evaluated.inherit_from(&dummy, context)?;
+    Ok(evaluated)
+}
+
+/// True if the joint is connected onward but none of its outer components is a body
+pub fn is_missing_outer_body(
+    to_ids: &[Uuid],
+    components: &HashMap<Uuid, MultibodyComponent>,
+) -> bool {
+    !to_ids.is_empty()
+        && !to_ids
+            .iter()
+            .any(|to_id| matches!(components.get(to_id), Some(MultibodyComponent::Body(_))))
+}
+
+/// True if following the outer connections from `id` leads back to it
+pub fn is_in_cycle(id: Uuid, components: &HashMap<Uuid, MultibodyComponent>) -> bool {
+    let mut visited = HashSet::new();
+    let mut stack: Vec<Uuid> = match components.get(&id) {
+        Some(component) => component.get_to_id().clone(),
+        None => return false,
+    };
+    while let Some(next) = stack.pop() {
+        if next == id {
+            return true;
+        }
+        if visited.insert(next) {
+            if let Some(component) = components.get(&next) {
+                stack.extend(component.get_to_id());
+            }
+        }
+    }
+    false
+}
+
+/// True if a component other than `id` is called `name`, surrounding whitespace doesn't count
+pub fn is_name_taken_by_other(
+    id: Uuid,
+    name: &str,
+    components: &HashMap<Uuid, MultibodyComponent>,
+) -> bool {
+    let name = name.trim();
+    components
+        .iter()
+        .any(|(other_id, other)| *other_id != id && other.get_name().trim() == name)
+}
+
+/// Collects every topology and field problem, not just the first one like `create_multibody_system`.
+/// Problems with the whole graph come first, then the rest ordered by component name.
+pub fn validate(
+    components: &HashMap<Uuid, MultibodyComponent>,
+    context: &FieldContext,
+) -> Vec<GraphErrors> {
+    let mut problems = Vec::new();
+
+    let bases: Vec<&MultibodyComponent> = components
+        .values()
+        .filter(|component| matches!(component, MultibodyComponent::Base(_)))
+        .collect();
+    if bases.is_empty() {
+        problems.push(GraphErrors::NoBase);
+    } else if bases.iter().all(|base| base.get_to_id().is_empty()) {
+        problems.push(GraphErrors::NoBaseConnections);
+    }
+
+    for (id, component) in components {
+        if let Err(error) = evaluate_component(component, context) {
+            problems.push(GraphErrors::InvalidFields(*id, error));
+        }
+        // files saved before names had to be unique can still have duplicates
+        if is_name_taken_by_other(*id, component.get_name(), components) {
+            problems.push(GraphErrors::DuplicateName(*id));
+        }
+        match component {
+            MultibodyComponent::Base(_) => {}
+            MultibodyComponent::Body(body) => {
+                if body.get_from_id().is_none() {
+                    problems.push(GraphErrors::BodyMissingFrom(*id));
+                }
+            }
+            MultibodyComponent::Joint(joint) => {
+                if joint.get_from_id().is_none() {
+                    problems.push(GraphErrors::JointMissingFrom(*id));
+                }
+                if joint.get_to_id().is_empty() {
+                    problems.push(GraphErrors::JointMissingTo(*id));
+                } else if is_missing_outer_body(joint.get_to_id(), components) {
+                    problems.push(GraphErrors::JointNoOuterBody(*id));
+                }
+            }
+        }
+        if is_in_cycle(*id, components) {
+            problems.push(GraphErrors::InCycle(*id));
+        }
+    }
+
+    problems.sort_by_key(|problem| {
+        problem
+            .get_component_id()
+            .and_then(|id| components.get(&id))
+            .map(|component| component.get_name().to_string())
+    });
+    problems
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::multibody::joints::Joint;
+    use crate::ui::dummies::{DummyBase, DummyRevolute, DummyTrait};
+
+    #[test]
+    fn test_validate_collects_every_problem() {
+        let mut components = HashMap::new();
+        assert!(matches!(
+            validate(&components, &FieldContext::default())[..],
+            [GraphErrors::NoBase]
+        ));
+
+        let mut ids = Vec::new();
+        for (name, mut dummy) in [
+            ("base", DummyComponent::Base(DummyBase::new(Uuid::new_v4()))),
+            (
+                "joint",
+                DummyComponent::Revolute(DummyRevolute::new(Uuid::new_v4())),
+            ),
+        ] {
+            let id = Uuid::new_v4();
+            dummy.set_name(name);
+            let component = MultibodyComponent::from_dummy(
+                id,
+                &dummy,
+                Uuid::new_v4(),
+                &FieldContext::default(),
+            )
+            .ok()
+            .unwrap();
+            components.insert(id, component);
+            ids.push(id);
+        }
+
+        let problems = validate(&components, &FieldContext::default());
+        assert_eq!(problems.len(), 3);
+        assert!(matches!(problems[0], GraphErrors::NoBaseConnections));
+        assert!(problems[1..]
+            .iter()
+            .all(|problem| problem.get_component_id() == Some(ids[1])));
+
+        // connecting the joint to the base only leaves the missing outer body
+        components.get_mut(&ids[0]).unwrap().connect_to(ids[1]);
+        components.get_mut(&ids[1]).unwrap().connect_from(ids[0]);
+        assert!(matches!(
+            validate(&components, &FieldContext::default())[..],
+            [GraphErrors::JointMissingTo(_)]
+        ));
+
+        // leading the joint back into the base is a loop without an outer body
+        components.get_mut(&ids[1]).unwrap().connect_to(ids[0]);
+        let problems = validate(&components, &FieldContext::default());
+        assert_eq!(problems.len(), 3);
+        assert!(problems
+            .iter()
+            .any(|problem| matches!(problem, GraphErrors::JointNoOuterBody(id) if *id == ids[1])));
+        assert!(ids.iter().all(|id| problems
+            .iter()
+            .any(|problem| matches!(problem, GraphErrors::InCycle(problem_id) if problem_id == id))));
+    }
+
+    #[test]
+    fn test_duplicate_names_ignore_whitespace() {
+        let mut components = HashMap::new();
+        for name in ["joint", " joint "] {
+            let mut dummy = DummyComponent::Revolute(DummyRevolute::new(Uuid::new_v4()));
+            dummy.set_name(name);
+            let id = Uuid::new_v4();
+            let component = MultibodyComponent::from_dummy(
+                id,
+                &dummy,
+                Uuid::new_v4(),
+                &FieldContext::default(),
+            )
+            .ok()
+            .unwrap();
+            components.insert(id, component);
+        }
+
+        let problems = validate(&components, &FieldContext::default());
+        assert!(components.keys().all(|id| problems
+            .iter()
+            .any(|problem| matches!(problem, GraphErrors::DuplicateName(problem_id) if problem_id == id))));
+    }
+
+    #[test]
+    fn test_fields_follow_parameters() {
+        let mut dummy = DummyComponent::Revolute(DummyRevolute::new(Uuid::new_v4()));
+        dummy.set_name("joint");
+        dummy.set_value(0, "angle / 2");
+        let mut context = FieldContext::default();
+        context.variables.insert("angle".to_string(), 1.0);
+        let id = Uuid::new_v4();
+        let component = MultibodyComponent::from_dummy(id, &dummy, Uuid::new_v4(), &context)
+            .ok()
+            .unwrap();
+        assert_eq!(component.get_expressions()[0], "angle / 2");
+
+        context.variables.insert("angle".to_string(), 3.0);
+        match evaluate_component(&component, &context) {
+            Ok(MultibodyComponent::Joint(Joint::Revolute(revolute))) => {
+                assert_eq!(revolute.state.theta, 1.5)
+            }
+            _ => panic!("the joint should evaluate"),
+        }
+
+        // removing the parameter is reported as a problem with the joint
+        let components = HashMap::from([(id, component)]);
+        assert!(validate(&components, &FieldContext::default())
+            .iter()
+            .any(|problem| matches!(problem, GraphErrors::InvalidFields(problem_id, _) if *problem_id == id)));
+    }
+}
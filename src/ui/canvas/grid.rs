@@ -0,0 +1,132 @@
+use iced::{
+    widget::canvas::{Frame, Path, Stroke},
+    Color, Point, Rectangle,
+};
+use serde::{Deserialize, Serialize};
+
+use super::view::ViewTransform;
+use crate::ui::theme::Theme;
+
+/// Grid lines closer together than this many screen pixels are not drawn
+const MIN_SCREEN_SPACING: f32 = 8.0;
+
+/// Background grid and snapping, saved with the project
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(default)]
+pub struct GridSettings {
+    pub is_snapping: bool,
+    pub is_visible: bool,
+    /// Distance between grid lines, in graph coordinates
+    pub spacing: f32,
+}
+
+impl Default for GridSettings {
+    fn default() -> Self {
+        Self {
+            is_snapping: false,
+            is_visible: true,
+            spacing: 25.0,
+        }
+    }
+}
+
+impl GridSettings {
+    /// `size` is the size of the area being drawn, in screen coordinates
+    pub fn draw(&self, frame: &mut Frame, theme: &Theme, view: &ViewTransform, size: iced::Size) {
+        let screen_spacing = self.spacing * view.zoom;
+        if !self.is_visible || screen_spacing < MIN_SCREEN_SPACING {
+            return;
+        }
+
+        let first = view.to_graph(Point::ORIGIN);
+        let last = view.to_graph(Point::new(size.width, size.height));
+        let color = Color {
+            a: 0.15,
+            ..theme.greyed
+        };
+        let stroke = Stroke::default().with_color(color).with_width(1.0);
+
+        let mut x = (first.x / self.spacing).floor() * self.spacing;
+        while x <= last.x {
+            let screen_x = view.to_screen(Point::new(x, 0.0)).x;
+            frame.stroke(
+                &Path::line(Point::new(screen_x, 0.0), Point::new(screen_x, size.height)),
+                stroke.clone(),
+            );
+            x += self.spacing;
+        }
+        let mut y = (first.y / self.spacing).floor() * self.spacing;
+        while y <= last.y {
+            let screen_y = view.to_screen(Point::new(0.0, y)).y;
+            frame.stroke(
+                &Path::line(Point::new(0.0, screen_y), Point::new(size.width, screen_y)),
+                stroke.clone(),
+            );
+            y += self.spacing;
+        }
+    }
+
+    /// Rounds `point` to the nearest grid intersection when snapping is on
+    pub fn snap(&self, point: Point) -> Point {
+        if !self.is_snapping || self.spacing <= 0.0 {
+            return point;
+        }
+        Point::new(
+            (point.x / self.spacing).round() * self.spacing,
+            (point.y / self.spacing).round() * self.spacing,
+        )
+    }
+}
+
+/// Returns the lines, in graph coordinates, along which the left, center or right
+/// (top, center or bottom) of `moving` lines up with one of `others` within `tolerance`
+pub fn alignment_guides<'a>(
+    moving: Rectangle,
+    others: impl Iterator<Item = &'a Rectangle>,
+    tolerance: f32,
+) -> Vec<(Point, Point)> {
+    let vertical = |bounds: &Rectangle| [bounds.x, bounds.center_x(), bounds.x + bounds.width];
+    let horizontal = |bounds: &Rectangle| [bounds.y, bounds.center_y(), bounds.y + bounds.height];
+
+    let mut guides = Vec::new();
+    for other in others {
+        let span = moving.union(other);
+        for x in vertical(&moving) {
+            if vertical(other).iter().any(|other_x| (x - other_x).abs() <= tolerance) {
+                guides.push((Point::new(x, span.y), Point::new(x, span.y + span.height)));
+            }
+        }
+        for y in horizontal(&moving) {
+            if horizontal(other).iter().any(|other_y| (y - other_y).abs() <= tolerance) {
+                guides.push((Point::new(span.x, y), Point::new(span.x + span.width, y)));
+            }
+        }
+    }
+    guides
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use iced::Size;
+
+    #[test]
+    fn test_grid_snap_and_alignment_guides() {
+        let mut grid = GridSettings::default();
+        assert_eq!(grid.snap(Point::new(13.0, 37.0)), Point::new(13.0, 37.0));
+        grid.is_snapping = true;
+        grid.spacing = 10.0;
+        assert_eq!(grid.snap(Point::new(13.0, 37.0)), Point::new(10.0, 40.0));
+
+        // same top edge and center row, shifted sideways
+        let moving = Rectangle::new(Point::new(0.0, 0.0), Size::new(100.0, 50.0));
+        let other = Rectangle::new(Point::new(300.0, 0.0), Size::new(100.0, 50.0));
+        let guides = alignment_guides(moving, [other].iter(), 0.5);
+        assert_eq!(guides.len(), 3);
+        assert!(guides.iter().all(|(start, end)| start.y == end.y));
+        assert_eq!(guides[0], (Point::new(0.0, 0.0), Point::new(400.0, 0.0)));
+
+        let far = Rectangle::new(Point::new(301.0, 200.0), Size::new(100.0, 50.0));
+        assert!(alignment_guides(moving, [far].iter(), 0.5).is_empty());
+    }
+}
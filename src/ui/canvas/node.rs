@@ -1,19 +1,49 @@
 use crate::font::Font;
+use crate::ui::canvas::view::ViewTransform;
 use crate::ui::theme::Theme;
 
 use iced::{
     alignment::{Horizontal, Vertical},
     widget::canvas::{path::Path, stroke, Frame, Stroke, Text},
-    Color, Point, Rectangle, Vector,
+    Point, Rectangle, Vector,
 };
+use serde::{Deserialize, Serialize};
+
+/// Serde mirror of `iced::Rectangle` so node bounds can be saved
+#[derive(Serialize, Deserialize)]
+#[serde(remote = "Rectangle")]
+pub struct RectangleDef {
+    pub x: f32,
+    pub y: f32,
+    pub width: f32,
+    pub height: f32,
+}
+
+/// Radius of the connection ports, in graph coordinates
+pub const PORT_RADIUS: f32 = 5.0;
+
+/// Edges leave a node from its output port and arrive at another's input port
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum NodePort {
+    Input,
+    Output,
+}
 
 //TODO: Think about using MultibodyMeta instead of the individual Uuid fields
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
 pub struct Node {
+    #[serde(with = "RectangleDef")]
     pub bounds: Rectangle,
+    /// Set when the component has a validation problem
+    #[serde(skip)]
+    pub has_error: bool,
+    #[serde(skip)]
     pub is_left_clicked: bool,
+    #[serde(skip)]
     pub is_middle_clicked: bool,
+    #[serde(skip)]
     pub is_right_clicked: bool,
+    #[serde(skip)]
     pub is_selected: bool,    
     //pub label: String,
 }
@@ -26,6 +56,7 @@ impl Node {
     ) -> Self {
         Self {
             bounds: bounds,
+            has_error: false,
             is_left_clicked: false,
             is_middle_clicked: false,
             is_selected: false,
@@ -34,9 +65,10 @@ impl Node {
         }
     }
 
-    pub fn calculate_path(&self) -> Path {
-        let bounds = self.bounds;
-        let corner_radius = 3.0;
+    /// Returns the outline in screen coordinates
+    pub fn calculate_path(&self, view: &ViewTransform) -> Path {
+        let bounds = view.rectangle_to_screen(self.bounds);
+        let corner_radius = 3.0 * view.zoom;
 
         let path = Path::new(|p| {
             p.move_to(Point::new(bounds.x + corner_radius, bounds.y));
@@ -81,12 +113,31 @@ impl Node {
         path
     }
 
-    pub fn draw(&self, frame: &mut Frame, theme: &Theme, label: &str) {
-        let background = self.calculate_path();
+    /// Returns the center of a port, in graph coordinates.
+    /// Inputs sit on the top edge and outputs on the bottom, matching a top-down tree.
+    pub fn get_port_position(&self, port: NodePort) -> Point {
+        match port {
+            NodePort::Input => Point::new(self.bounds.center_x(), self.bounds.y),
+            NodePort::Output => Point::new(self.bounds.center_x(), self.bounds.y + self.bounds.height),
+        }
+    }
+
+    /// Returns the port within `tolerance` of its edge from `position`, both in graph coordinates
+    pub fn get_port_at(&self, position: Point, tolerance: f32) -> Option<NodePort> {
+        [NodePort::Input, NodePort::Output]
+            .into_iter()
+            .find(|port| self.get_port_position(*port).distance(position) <= PORT_RADIUS + tolerance)
+    }
+
+    /// Nodebar nodes are drawn without `ports`, they can't be connected
+    pub fn draw(&self, frame: &mut Frame, theme: &Theme, label: &str, view: &ViewTransform, ports: bool) {
+        let background = self.calculate_path(view);
 
         let node_border_color;
         if self.is_selected {
             node_border_color = theme.highlight;
+        } else if self.has_error {
+            node_border_color = theme.error;
         } else {
             node_border_color = theme.primary;
         }
@@ -98,20 +149,39 @@ impl Node {
                 &background,
                 Stroke {
                     style: stroke::Style::Solid(node_border_color),
-                    width: 5.0,
+                    width: 5.0 * view.zoom,
                     ..Stroke::default()
                 },
             );
             frame.fill(&background, node_background_color);
             frame.fill_text(Text {
                 content: label.to_string(),
-                color: Color::WHITE, //theme.edge_multibody,
+                color: theme.text,
                 font: Font::MONOSPACE,
                 horizontal_alignment: Horizontal::Center,
-                position: self.bounds.center(),
+                position: view.to_screen(self.bounds.center()),
+                size: Text::default().size * view.zoom,
                 vertical_alignment: Vertical::Center,
                 ..Text::default()
             });
+
+            if ports {
+                for port in [NodePort::Input, NodePort::Output] {
+                    let circle = Path::circle(
+                        view.to_screen(self.get_port_position(port)),
+                        PORT_RADIUS * view.zoom,
+                    );
+                    frame.fill(&circle, node_background_color);
+                    frame.stroke(
+                        &circle,
+                        Stroke {
+                            style: stroke::Style::Solid(node_border_color),
+                            width: 2.0 * view.zoom,
+                            ..Stroke::default()
+                        },
+                    );
+                }
+            }
         });
     }
 
@@ -125,6 +195,7 @@ impl Node {
         self.bounds.y = position.y - self.bounds.height / 2.0;
     }
 
+    /// `cursor_position` is in graph coordinates, see `ViewTransform::to_graph`
     pub fn is_clicked(&mut self, cursor_position: Point, mouse_button: &crate::MouseButton) {
         let is_inside = self.bounds.contains(cursor_position);
 
@@ -2,6 +2,7 @@ use iced::{mouse::Cursor, Point, Rectangle, Size};
 use std::collections::HashMap;
 use uuid::Uuid;
 
+use crate::multibody::MultibodyComponent;
 use crate::ui::canvas::node::Node;
 use crate::ui::dummies::{DummyBase, DummyBody, DummyComponent, DummyRevolute};
 use crate::{MouseButton, MouseButtonReleaseEvents};
@@ -105,6 +106,14 @@ impl Default for Nodebar {
 }
 
 impl Nodebar {
+    /// The dummy that creates components of `component`'s kind
+    pub fn get_dummy_id(&self, component: &MultibodyComponent) -> Option<Uuid> {
+        self.components
+            .iter()
+            .find(|(_, dummy)| dummy.is_kind_of(component))
+            .map(|(id, _)| *id)
+    }
+
     pub fn cursor_moved(&mut self, cursor: Cursor) -> bool {
         let mut redraw = false;
         if let Some(clicked_node_id) = self.left_clicked_node {
@@ -0,0 +1,110 @@
+use iced::{Point, Rectangle, Vector};
+
+pub const MAX_ZOOM: f32 = 5.0;
+pub const MIN_ZOOM: f32 = 0.1;
+
+/// Maps graph coordinates, where nodes live, to screen coordinates within the graph bounds.
+/// screen = graph * zoom + translation
+#[derive(Debug, Clone, Copy)]
+pub struct ViewTransform {
+    pub translation: Vector,
+    pub zoom: f32,
+}
+
+impl Default for ViewTransform {
+    fn default() -> Self {
+        Self {
+            translation: Vector::new(0.0, 0.0),
+            zoom: 1.0,
+        }
+    }
+}
+
+impl ViewTransform {
+    /// Fits `content` inside `viewport` with `padding` screen pixels on each side
+    pub fn fit(content: Rectangle, viewport: Rectangle, padding: f32) -> Self {
+        let available_width = (viewport.width - 2.0 * padding).max(1.0);
+        let available_height = (viewport.height - 2.0 * padding).max(1.0);
+        let zoom = (available_width / content.width.max(1.0))
+            .min(available_height / content.height.max(1.0))
+            .clamp(MIN_ZOOM, MAX_ZOOM);
+
+        let content_center = content.center();
+        let translation = Vector::new(
+            viewport.width / 2.0 - content_center.x * zoom,
+            viewport.height / 2.0 - content_center.y * zoom,
+        );
+        Self { translation, zoom }
+    }
+
+    pub fn pan(&mut self, delta: Vector) {
+        self.translation = self.translation + delta;
+    }
+
+    pub fn to_graph(self, screen: Point) -> Point {
+        Point::new(
+            (screen.x - self.translation.x) / self.zoom,
+            (screen.y - self.translation.y) / self.zoom,
+        )
+    }
+
+    pub fn to_screen(self, graph: Point) -> Point {
+        Point::new(
+            graph.x * self.zoom + self.translation.x,
+            graph.y * self.zoom + self.translation.y,
+        )
+    }
+
+    pub fn rectangle_to_screen(&self, graph: Rectangle) -> Rectangle {
+        Rectangle {
+            x: graph.x * self.zoom + self.translation.x,
+            y: graph.y * self.zoom + self.translation.y,
+            width: graph.width * self.zoom,
+            height: graph.height * self.zoom,
+        }
+    }
+
+    /// Scales by `factor` within the zoom limits, keeping the graph point under `screen` fixed
+    pub fn zoom_at(&mut self, screen: Point, factor: f32) {
+        let anchor = self.to_graph(screen);
+        self.zoom = (self.zoom * factor).clamp(MIN_ZOOM, MAX_ZOOM);
+        self.translation = Vector::new(
+            screen.x - anchor.x * self.zoom,
+            screen.y - anchor.y * self.zoom,
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use iced::Size;
+
+    #[test]
+    fn test_view_transform_zoom_at() {
+        let mut view = ViewTransform::default();
+        view.pan(Vector::new(30.0, -10.0));
+
+        let cursor = Point::new(200.0, 150.0);
+        let anchor = view.to_graph(cursor);
+        view.zoom_at(cursor, 2.0);
+
+        assert_eq!(view.zoom, 2.0);
+        assert_eq!(view.to_screen(anchor), cursor);
+
+        view.zoom_at(cursor, 1000.0);
+        assert_eq!(view.zoom, MAX_ZOOM);
+    }
+
+    #[test]
+    fn test_view_transform_fit() {
+        let content = Rectangle::new(Point::new(-500.0, 0.0), Size::new(1000.0, 100.0));
+        let viewport = Rectangle::new(Point::ORIGIN, Size::new(520.0, 400.0));
+        let view = ViewTransform::fit(content, viewport, 10.0);
+
+        assert_eq!(view.zoom, 0.5);
+        let screen = view.rectangle_to_screen(content);
+        assert_eq!(screen.x, 10.0);
+        assert_eq!(screen.center(), Point::new(260.0, 200.0));
+    }
+}
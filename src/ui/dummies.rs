@@ -1,5 +1,9 @@
-use crate::multibody::{joints::Joint, MultibodyComponent, MultibodyTrait};
-use crate::ui::canvas::graph::Graph;
+use crate::multibody::{
+    base::BASE_SCHEMA, body::BODY_SCHEMA, joints::revolute::REVOLUTE_SCHEMA, joints::Joint, MultibodyComponent,
+    MultibodyTrait,
+};
+use crate::ui::schema::{FieldContext, FieldErrors, FieldSchema};
+use crate::ui::units::UnitSystem;
 use uuid::Uuid;
 
 #[derive(Debug, Clone, Copy)]
@@ -7,6 +11,16 @@ pub enum DummyErrors {
     NameIsEmpty,
 }
 
+impl std::fmt::Display for DummyErrors {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DummyErrors::NameIsEmpty => write!(f, "The name can't be empty."),
+        }
+    }
+}
+
+impl std::error::Error for DummyErrors {}
+
 /// DummyComponents are like MultibodyComponents but with String fields
 /// for editing in the text inputs rather than numeric values
 #[derive(Debug, Clone)]
@@ -20,8 +34,123 @@ pub trait DummyTrait {
     fn clear(&mut self);
     fn get_id(&self) -> Uuid;
     fn get_name(&self) -> String;
-    fn inherit_from(&mut self, component_id: &Uuid, graph: &Graph); // these args suck, but has to be this way sicne names is stored separately in graph for performance
+    /// The fields besides the name, `get_values` holds their text in the same order
+    fn get_schema(&self) -> &'static [FieldSchema];
+    fn get_values(&self) -> &[String];
     fn set_name(&mut self, name: &str);
+    fn set_value(&mut self, index: usize, value: &str);
+
+    /// What's wrong with the text of the field at `index`, if anything
+    fn get_field_error(&self, index: usize, context: &FieldContext) -> Option<FieldErrors> {
+        let field = self.get_schema().get(index)?;
+        let value = self.get_values().get(index)?;
+        field.parse(value, context).err()
+    }
+
+    fn has_invalid_fields(&self, context: &FieldContext) -> bool {
+        (0..self.get_schema().len()).any(|index| self.get_field_error(index, context).is_some())
+    }
+}
+
+/// Text for every field of `schema`, starting at the defaults
+fn default_values(schema: &[FieldSchema]) -> Vec<String> {
+    schema.iter().map(FieldSchema::get_default_text).collect()
+}
+
+/// The text the component's fields were entered as, written for `unit_system`.
+/// Components saved before that was kept, or with a field left empty, show their values instead.
+fn get_field_text(
+    component: &MultibodyComponent,
+    schema: &[FieldSchema],
+    values: &[f64],
+    unit_system: UnitSystem,
+) -> Vec<String> {
+    let expressions = component.get_expressions();
+    let texts: Vec<String> = if expressions.len() == values.len() {
+        expressions
+            .iter()
+            .zip(values)
+            .map(|(text, value)| {
+                if text.trim().is_empty() {
+                    value.to_string()
+                } else {
+                    text.clone()
+                }
+            })
+            .collect()
+    } else {
+        values.iter().map(f64::to_string).collect()
+    };
+    schema
+        .iter()
+        .zip(texts)
+        .map(|(field, text)| field.to_display_text(&text, unit_system))
+        .collect()
+}
+
+impl DummyComponent {
+    /// A dummy of the component's kind, holding its name and field text
+    pub fn from_component(component: &MultibodyComponent, unit_system: UnitSystem) -> Self {
+        let id = component.get_dummy_id();
+        let name = component.get_name().to_string();
+        match component {
+            MultibodyComponent::Base(base) => DummyComponent::Base(DummyBase {
+                id,
+                name,
+                values: get_field_text(component, &BASE_SCHEMA, &base.get_values(), unit_system),
+            }),
+            MultibodyComponent::Body(body) => DummyComponent::Body(DummyBody {
+                id,
+                name,
+                values: get_field_text(component, &BODY_SCHEMA, &body.get_values(), unit_system),
+            }),
+            MultibodyComponent::Joint(Joint::Revolute(revolute)) => {
+                DummyComponent::Revolute(DummyRevolute {
+                    id,
+                    name,
+                    values: get_field_text(
+                        component,
+                        &REVOLUTE_SCHEMA,
+                        &revolute.get_values(),
+                        unit_system,
+                    ),
+                })
+            }
+        }
+    }
+
+    /// Start of the name given to a component saved without one
+    pub fn get_name_prefix(&self) -> &'static str {
+        match self {
+            DummyComponent::Base(_) => "base",
+            DummyComponent::Body(_) => "body",
+            DummyComponent::Revolute(_) => "revolute",
+        }
+    }
+
+    /// Heading of the modal that adds a component of this kind
+    pub fn get_title(&self) -> &'static str {
+        match self {
+            DummyComponent::Base(_) => "Base Information",
+            DummyComponent::Body(_) => "Body Information",
+            DummyComponent::Revolute(_) => "Revolute Information",
+        }
+    }
+
+    /// True if `component` is of the kind this dummy creates
+    pub fn is_kind_of(&self, component: &MultibodyComponent) -> bool {
+        matches!(
+            (self, component),
+            (DummyComponent::Base(_), MultibodyComponent::Base(_))
+                | (DummyComponent::Body(_), MultibodyComponent::Body(_))
+                | (DummyComponent::Revolute(_), MultibodyComponent::Joint(Joint::Revolute(_)))
+        )
+    }
+
+    /// Only one component of this kind is allowed, so its name needs no number
+    pub fn is_unique(&self) -> bool {
+        matches!(self, DummyComponent::Base(_))
+    }
 }
 
 impl DummyTrait for DummyComponent {
@@ -49,11 +178,19 @@ impl DummyTrait for DummyComponent {
         }
     }
 
-    fn inherit_from(&mut self, component_id: &Uuid, graph: &Graph) {
+    fn get_schema(&self) -> &'static [FieldSchema] {
+        match self {
+            DummyComponent::Base(component) => component.get_schema(),
+            DummyComponent::Body(component) => component.get_schema(),
+            DummyComponent::Revolute(component) => component.get_schema(),
+        }
+    }
+
+    fn get_values(&self) -> &[String] {
         match self {
-            DummyComponent::Base(dummy) => dummy.inherit_from(component_id, graph),
-            DummyComponent::Body(dummy) => dummy.inherit_from(component_id, graph),
-            DummyComponent::Revolute(dummy) => dummy.inherit_from(component_id, graph),
+            DummyComponent::Base(component) => component.get_values(),
+            DummyComponent::Body(component) => component.get_values(),
+            DummyComponent::Revolute(component) => component.get_values(),
         }
     }
 
@@ -64,19 +201,30 @@ impl DummyTrait for DummyComponent {
             DummyComponent::Revolute(component) => component.set_name(name),
         }
     }
+
+    fn set_value(&mut self, index: usize, value: &str) {
+        match self {
+            DummyComponent::Base(component) => component.set_value(index, value),
+            DummyComponent::Body(component) => component.set_value(index, value),
+            DummyComponent::Revolute(component) => component.set_value(index, value),
+        }
+    }
 }
 
-#[derive(Debug, Default, Clone)]
+/// Field text in `BASE_SCHEMA` order
+#[derive(Debug, Clone)]
 pub struct DummyBase {
     id: Uuid,
     name: String,
+    values: Vec<String>,
 }
 
 impl DummyBase {
     pub fn new(id: Uuid) -> Self {
         Self {
             id: id,
-            ..Default::default()
+            name: String::new(),
+            values: default_values(&BASE_SCHEMA),
         }
     }
 }
@@ -84,6 +232,7 @@ impl DummyBase {
 impl DummyTrait for DummyBase {
     fn clear(&mut self) {
         self.name = String::new();
+        self.values = default_values(&BASE_SCHEMA);
     }
 
     fn get_id(&self) -> Uuid {
@@ -94,43 +243,39 @@ impl DummyTrait for DummyBase {
         self.name.to_string()
     }
 
-    fn inherit_from(&mut self, component_id: &Uuid, graph: &Graph) {
-        if let Some(component) = graph.components.get(component_id) {
-            match component {
-                MultibodyComponent::Base(_) => {
-                    self.set_name(&component.get_name());
-                }
-                _ => {} // TODO: error! must be a base
-            }
-        }
+    fn get_schema(&self) -> &'static [FieldSchema] {
+        &BASE_SCHEMA
+    }
+
+    fn get_values(&self) -> &[String] {
+        &self.values
     }
 
     fn set_name(&mut self, name: &str) {
         self.name = name.to_string();
     }
+
+    fn set_value(&mut self, index: usize, value: &str) {
+        if let Some(text) = self.values.get_mut(index) {
+            *text = value.to_string();
+        }
+    }
 }
 
-#[derive(Default, Debug, Clone)]
+/// Field text in `BODY_SCHEMA` order
+#[derive(Debug, Clone)]
 pub struct DummyBody {
     id: Uuid,
     name: String,
-    pub mass: String,
-    pub cmx: String,
-    pub cmy: String,
-    pub cmz: String,
-    pub ixx: String,
-    pub iyy: String,
-    pub izz: String,
-    pub ixy: String,
-    pub ixz: String,
-    pub iyz: String,
+    values: Vec<String>,
 }
 
 impl DummyBody {
     pub fn new(id: Uuid) -> Self {
         Self {
             id: id,
-            ..Default::default()
+            name: String::new(),
+            values: default_values(&BODY_SCHEMA),
         }
     }
 }
@@ -138,16 +283,7 @@ impl DummyBody {
 impl DummyTrait for DummyBody {
     fn clear(&mut self) {
         self.name = String::new();
-        self.mass = String::new();
-        self.cmx = String::new();
-        self.cmy = String::new();
-        self.cmz = String::new();
-        self.ixx = String::new();
-        self.iyy = String::new();
-        self.izz = String::new();
-        self.ixy = String::new();
-        self.ixz = String::new();
-        self.iyz = String::new();
+        self.values = default_values(&BODY_SCHEMA);
     }
 
     fn get_id(&self) -> Uuid {
@@ -158,48 +294,39 @@ impl DummyTrait for DummyBody {
         self.name.to_string()
     }
 
-    fn inherit_from(&mut self, component_id: &Uuid, graph: &Graph) {
-        if let Some(component) = graph.components.get(component_id) {
-            match component {
-                MultibodyComponent::Body(body) => {
-                    self.set_name(&component.get_name());
-                    self.mass = body.get_mass().to_string();
-                    self.cmx = body.get_cmx().to_string();
-                    self.cmy = body.get_cmy().to_string();
-                    self.cmz = body.get_cmz().to_string();
-                    self.ixx = body.get_ixx().to_string();
-                    self.iyy = body.get_iyy().to_string();
-                    self.izz = body.get_izz().to_string();
-                    self.ixy = body.get_ixy().to_string();
-                    self.ixz = body.get_ixz().to_string();
-                    self.iyz = body.get_iyz().to_string();
-                }
-                _ => {} // TODO: error! must be a base
-            }
-        }
+    fn get_schema(&self) -> &'static [FieldSchema] {
+        &BODY_SCHEMA
+    }
+
+    fn get_values(&self) -> &[String] {
+        &self.values
     }
 
     fn set_name(&mut self, name: &str) {
         self.name = name.to_string();
     }
+
+    fn set_value(&mut self, index: usize, value: &str) {
+        if let Some(text) = self.values.get_mut(index) {
+            *text = value.to_string();
+        }
+    }
 }
 
-#[derive(Default, Debug, Clone)]
+/// Field text in `REVOLUTE_SCHEMA` order
+#[derive(Debug, Clone)]
 pub struct DummyRevolute {
-    pub constant_force: String,
-    pub dampening: String,
     id: Uuid,
     pub name: String,
-    pub omega: String,
-    pub spring_constant: String,
-    pub theta: String,
+    values: Vec<String>,
 }
 
 impl DummyRevolute {
     pub fn new(id: Uuid) -> Self {
         Self {
             id: id,
-            ..Default::default()
+            name: String::new(),
+            values: default_values(&REVOLUTE_SCHEMA),
         }
     }
 }
@@ -207,6 +334,7 @@ impl DummyRevolute {
 impl DummyTrait for DummyRevolute {
     fn clear(&mut self) {
         self.name = String::new();
+        self.values = default_values(&REVOLUTE_SCHEMA);
     }
 
     fn get_id(&self) -> Uuid {
@@ -217,25 +345,21 @@ impl DummyTrait for DummyRevolute {
         self.name.to_string()
     }
 
-    fn inherit_from(&mut self, component_id: &Uuid, graph: &Graph) {
-        if let Some(component) = graph.components.get(component_id) {
-            match component {
-                MultibodyComponent::Joint(joint) => match joint {
-                    Joint::Revolute(revolute) => {
-                        self.set_name(&component.get_name());
-                        self.theta = revolute.state.theta.to_string();
-                        self.omega = revolute.state.omega.to_string();
-                        self.spring_constant = revolute.parameters.spring_constant.to_string();
-                        self.dampening = revolute.parameters.dampening.to_string();
-                        self.constant_force = revolute.parameters.constant_force.to_string();
-                    } //_ => {} //TODO: error! must be a revolute
-                },
-                _ => {} // TODO: error! must be a joint
-            }
-        }
+    fn get_schema(&self) -> &'static [FieldSchema] {
+        &REVOLUTE_SCHEMA
+    }
+
+    fn get_values(&self) -> &[String] {
+        &self.values
     }
 
     fn set_name(&mut self, name: &str) {
         self.name = name.to_string();
     }
+
+    fn set_value(&mut self, index: usize, value: &str) {
+        if let Some(text) = self.values.get_mut(index) {
+            *text = value.to_string();
+        }
+    }
 }
@@ -1,12 +1,20 @@
-#[derive(Debug, Clone, Copy)]
+use crate::project::ProjectErrors;
+use crate::ui::theme::user::ThemeErrors;
+
+#[derive(Debug, Clone)]
 pub enum Errors {
+    Project(ProjectErrors),
+    /// A user theme file that could not be loaded, with its file name
+    Theme(String, ThemeErrors),
     TooManyBases,
 }
 
 impl Errors {
-    pub fn get_error_message(&self) -> &str {
+    pub fn get_error_message(&self) -> String {
         match self {
-            Errors::TooManyBases => "Cannot have more than one base. Delete the old one first if this is intended."
+            Errors::Project(error) => error.to_string(),
+            Errors::Theme(file_name, error) => format!("The theme file {} could not be loaded. {}", file_name, error),
+            Errors::TooManyBases => "Cannot have more than one base. Delete the old one first if this is intended.".to_string(),
         }
     }
 }
@@ -0,0 +1,347 @@
+use std::collections::HashMap;
+
+/// Values of the project parameters, by name
+pub type Variables = HashMap<String, f64>;
+
+/// Names that always mean the same thing, parameters can't take them
+pub const CONSTANTS: [(&str, f64); 3] = [
+    ("e", std::f64::consts::E),
+    ("pi", std::f64::consts::PI),
+    ("tau", std::f64::consts::TAU),
+];
+
+/// Functions and how many arguments they take
+pub const FUNCTIONS: [(&str, usize); 15] = [
+    ("abs", 1),
+    ("acos", 1),
+    ("asin", 1),
+    ("atan", 1),
+    ("atan2", 2),
+    ("cos", 1),
+    ("exp", 1),
+    ("ln", 1),
+    ("log10", 1),
+    ("max", 2),
+    ("min", 2),
+    ("sign", 1),
+    ("sin", 1),
+    ("sqrt", 1),
+    ("tan", 1),
+];
+
+/// How deeply signs, powers and parentheses can nest, deeper text is rejected rather than
+/// overflowing the stack
+const MAX_DEPTH: usize = 100;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ExpressionErrors {
+    Expected(char),
+    TooDeep,
+    UnexpectedCharacter(char),
+    UnexpectedEnd,
+    UnknownFunction(String),
+    UnknownName(String),
+    WrongArgumentCount { function: String, expected: usize },
+}
+
+impl ExpressionErrors {
+    pub fn get_error_message(&self) -> String {
+        match self {
+            ExpressionErrors::Expected(c) => format!("Expected '{}'.", c),
+            ExpressionErrors::TooDeep => "Expression is nested too deeply.".to_string(),
+            ExpressionErrors::UnexpectedCharacter(c) => format!("Unexpected '{}'.", c),
+            ExpressionErrors::UnexpectedEnd => "Expression is incomplete.".to_string(),
+            ExpressionErrors::UnknownFunction(name) => format!("Unknown function '{}'.", name),
+            ExpressionErrors::UnknownName(name) => format!("Unknown parameter '{}'.", name),
+            ExpressionErrors::WrongArgumentCount { function, expected } => {
+                format!("'{}' takes {} argument(s).", function, expected)
+            }
+        }
+    }
+}
+
+/// Is `name` a constant or a function, which parameters can't be called
+pub fn is_reserved(name: &str) -> bool {
+    CONSTANTS.iter().any(|(constant, _)| *constant == name)
+        || FUNCTIONS.iter().any(|(function, _)| *function == name)
+}
+
+/// Evaluates arithmetic like `m*L^2/3` or `2*pi/10`.
+/// Supports `+ - * / ^`, parentheses, the `CONSTANTS`, the `FUNCTIONS` and any name in `variables`.
+pub fn evaluate(text: &str, variables: &Variables) -> Result<f64, ExpressionErrors> {
+    let mut parser = Parser {
+        chars: text.chars().collect(),
+        depth: 0,
+        position: 0,
+        variables: Some(variables),
+    };
+    parser.parse()
+}
+
+/// Would `text` evaluate if every name in it were a parameter
+pub fn is_valid_syntax(text: &str) -> bool {
+    let mut parser = Parser {
+        chars: text.chars().collect(),
+        depth: 0,
+        position: 0,
+        variables: None,
+    };
+    parser.parse().is_ok()
+}
+
+/// Recursive descent, lowest precedence first
+struct Parser<'a> {
+    chars: Vec<char>,
+    /// How many `parse_unary` calls are in progress
+    depth: usize,
+    position: usize,
+    /// `None` accepts any name, for checking syntax only
+    variables: Option<&'a Variables>,
+}
+
+impl Parser<'_> {
+    fn parse(&mut self) -> Result<f64, ExpressionErrors> {
+        let value = self.parse_sum()?;
+        match self.peek() {
+            Some(c) => Err(ExpressionErrors::UnexpectedCharacter(c)),
+            None => Ok(value),
+        }
+    }
+
+    /// The next character that isn't whitespace
+    fn peek(&mut self) -> Option<char> {
+        while self
+            .chars
+            .get(self.position)
+            .is_some_and(|c| c.is_whitespace())
+        {
+            self.position += 1;
+        }
+        self.chars.get(self.position).copied()
+    }
+
+    fn expect(&mut self, expected: char) -> Result<(), ExpressionErrors> {
+        match self.peek() {
+            Some(c) if c == expected => {
+                self.position += 1;
+                Ok(())
+            }
+            _ => Err(ExpressionErrors::Expected(expected)),
+        }
+    }
+
+    fn parse_sum(&mut self) -> Result<f64, ExpressionErrors> {
+        let mut value = self.parse_product()?;
+        loop {
+            match self.peek() {
+                Some('+') => {
+                    self.position += 1;
+                    value += self.parse_product()?;
+                }
+                Some('-') => {
+                    self.position += 1;
+                    value -= self.parse_product()?;
+                }
+                _ => return Ok(value),
+            }
+        }
+    }
+
+    fn parse_product(&mut self) -> Result<f64, ExpressionErrors> {
+        let mut value = self.parse_unary()?;
+        loop {
+            match self.peek() {
+                Some('*') => {
+                    self.position += 1;
+                    value *= self.parse_unary()?;
+                }
+                Some('/') => {
+                    self.position += 1;
+                    value /= self.parse_unary()?;
+                }
+                _ => return Ok(value),
+            }
+        }
+    }
+
+    /// Signs bind looser than powers, so `-2^2` is -4.
+    /// Every recursion, through signs, powers, parentheses or calls, passes through here,
+    /// so this is where the nesting depth is limited.
+    fn parse_unary(&mut self) -> Result<f64, ExpressionErrors> {
+        if self.depth == MAX_DEPTH {
+            return Err(ExpressionErrors::TooDeep);
+        }
+        self.depth += 1;
+        let value = match self.peek() {
+            Some('-') => {
+                self.position += 1;
+                self.parse_unary().map(|value| -value)
+            }
+            Some('+') => {
+                self.position += 1;
+                self.parse_unary()
+            }
+            _ => self.parse_power(),
+        };
+        self.depth -= 1;
+        value
+    }
+
+    /// Right associative, `2^3^2` is 2^9
+    fn parse_power(&mut self) -> Result<f64, ExpressionErrors> {
+        let base = self.parse_primary()?;
+        if self.peek() == Some('^') {
+            self.position += 1;
+            return Ok(base.powf(self.parse_unary()?));
+        }
+        Ok(base)
+    }
+
+    fn parse_primary(&mut self) -> Result<f64, ExpressionErrors> {
+        match self.peek() {
+            Some('(') => {
+                self.position += 1;
+                let value = self.parse_sum()?;
+                self.expect(')')?;
+                Ok(value)
+            }
+            Some(c) if c.is_ascii_digit() || c == '.' => self.parse_number(),
+            Some(c) if c.is_alphabetic() || c == '_' => self.parse_name(),
+            Some(c) => Err(ExpressionErrors::UnexpectedCharacter(c)),
+            None => Err(ExpressionErrors::UnexpectedEnd),
+        }
+    }
+
+    /// Digits with an optional fraction and exponent, like `1.5e-3`
+    fn parse_number(&mut self) -> Result<f64, ExpressionErrors> {
+        let start = self.position;
+        let is_digit =
+            |chars: &[char], index: usize| chars.get(index).is_some_and(char::is_ascii_digit);
+        while is_digit(&self.chars, self.position) || self.chars.get(self.position) == Some(&'.') {
+            self.position += 1;
+        }
+        // only an exponent if digits follow, otherwise the e is left for the next token
+        if matches!(self.chars.get(self.position), Some('e' | 'E')) {
+            let sign = usize::from(matches!(self.chars.get(self.position + 1), Some('+' | '-')));
+            if is_digit(&self.chars, self.position + 1 + sign) {
+                self.position += 1 + sign;
+                while is_digit(&self.chars, self.position) {
+                    self.position += 1;
+                }
+            }
+        }
+        let text: String = self.chars[start..self.position].iter().collect();
+        text.parse()
+            .map_err(|_| ExpressionErrors::UnexpectedCharacter(self.chars[start]))
+    }
+
+    /// A parameter, a constant or a function call
+    fn parse_name(&mut self) -> Result<f64, ExpressionErrors> {
+        let start = self.position;
+        while self
+            .chars
+            .get(self.position)
+            .is_some_and(|c| c.is_alphanumeric() || *c == '_')
+        {
+            self.position += 1;
+        }
+        let name: String = self.chars[start..self.position].iter().collect();
+
+        if self.peek() == Some('(') {
+            self.position += 1;
+            return self.parse_call(name);
+        }
+        let variable = match self.variables {
+            Some(variables) => variables.get(&name).copied(),
+            None => Some(1.0),
+        };
+        if let Some(value) = variable {
+            return Ok(value);
+        }
+        match CONSTANTS.iter().find(|(constant, _)| *constant == name) {
+            Some((_, value)) => Ok(*value),
+            None => Err(ExpressionErrors::UnknownName(name)),
+        }
+    }
+
+    /// Called after the opening parenthesis
+    fn parse_call(&mut self, function: String) -> Result<f64, ExpressionErrors> {
+        let expected = match FUNCTIONS.iter().find(|(name, _)| *name == function) {
+            Some((_, count)) => *count,
+            None => return Err(ExpressionErrors::UnknownFunction(function)),
+        };
+        let mut arguments = vec![self.parse_sum()?];
+        while self.peek() == Some(',') {
+            self.position += 1;
+            arguments.push(self.parse_sum()?);
+        }
+        self.expect(')')?;
+        if arguments.len() != expected {
+            return Err(ExpressionErrors::WrongArgumentCount { function, expected });
+        }
+
+        let x = arguments[0];
+        let value = match function.as_str() {
+            "abs" => x.abs(),
+            "acos" => x.acos(),
+            "asin" => x.asin(),
+            "atan" => x.atan(),
+            "atan2" => x.atan2(arguments[1]),
+            "cos" => x.cos(),
+            "exp" => x.exp(),
+            "ln" => x.ln(),
+            "log10" => x.log10(),
+            "max" => x.max(arguments[1]),
+            "min" => x.min(arguments[1]),
+            "sign" => x.signum(),
+            "sin" => x.sin(),
+            "sqrt" => x.sqrt(),
+            "tan" => x.tan(),
+            _ => unreachable!("every function in FUNCTIONS is evaluated"),
+        };
+        Ok(value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_evaluate() {
+        let variables = Variables::from([("m".to_string(), 3.0), ("L".to_string(), 2.0)]);
+        let eval = |text: &str| evaluate(text, &variables);
+
+        assert_eq!(eval("1.5e-3 * 2"), Ok(0.003));
+        assert_eq!(eval("m*L^2/3"), Ok(4.0));
+        assert_eq!(eval("-2^2"), Ok(-4.0));
+        assert_eq!(eval("2^3^2"), Ok(512.0));
+        assert_eq!(eval("2*pi/10"), Ok(2.0 * std::f64::consts::PI / 10.0));
+        assert_eq!(eval("2*e"), Ok(2.0 * std::f64::consts::E));
+        assert_eq!(eval("max(m, L) + sqrt(4)"), Ok(5.0));
+
+        assert_eq!(eval("m*"), Err(ExpressionErrors::UnexpectedEnd));
+        assert_eq!(eval("(m"), Err(ExpressionErrors::Expected(')')));
+        assert_eq!(eval("m)"), Err(ExpressionErrors::UnexpectedCharacter(')')));
+        assert_eq!(
+            eval("k*2"),
+            Err(ExpressionErrors::UnknownName("k".to_string()))
+        );
+        assert_eq!(
+            eval("sqrt(1, 2)"),
+            Err(ExpressionErrors::WrongArgumentCount {
+                function: "sqrt".to_string(),
+                expected: 1
+            })
+        );
+
+        let nested = |open: &str, close: &str, count: usize| {
+            format!("{}1{}", open.repeat(count), close.repeat(count))
+        };
+        assert_eq!(eval(&nested("(", ")", 50)), Ok(1.0));
+        assert_eq!(eval(&nested("(", ")", 10_000)), Err(ExpressionErrors::TooDeep));
+        assert_eq!(eval(&nested("-", "", 10_000)), Err(ExpressionErrors::TooDeep));
+        assert_eq!(eval(&nested("sqrt(", ")", 10_000)), Err(ExpressionErrors::TooDeep));
+        assert_eq!(eval(&nested("2^", "", 10_000)), Err(ExpressionErrors::TooDeep));
+    }
+}
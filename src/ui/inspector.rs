@@ -0,0 +1,173 @@
+use iced::advanced::widget::{
+    operation::{Focusable, Outcome},
+    Id, Operation,
+};
+use iced::Rectangle;
+use uuid::Uuid;
+
+use crate::ui::canvas::graph::Graph;
+use crate::ui::dummies::DummyComponent;
+
+pub const MIN_INSPECTOR_WIDTH: f32 = 150.0;
+
+/// Docked panel with the fields of the selected component.
+/// Edits are kept in its own dummy until they're applied to the graph.
+#[derive(Debug)]
+pub struct Inspector {
+    component_id: Option<Uuid>,
+    pub dummy: Option<DummyComponent>,
+    /// Fields were typed into but haven't been applied to the graph yet
+    pub is_dirty: bool,
+    pub is_open: bool,
+    pub width: f32,
+}
+
+impl Default for Inspector {
+    fn default() -> Self {
+        Self {
+            component_id: None,
+            dummy: None,
+            is_dirty: false,
+            is_open: true,
+            width: 300.0,
+        }
+    }
+}
+
+impl Inspector {
+    pub fn get_component_id(&self) -> Option<Uuid> {
+        self.component_id
+    }
+
+    /// Shows `component_id`, dropping any edits that weren't applied, so settle them first
+    pub fn select(&mut self, component_id: Option<Uuid>, graph: &Graph) {
+        self.component_id = component_id;
+        self.is_dirty = false;
+        self.dummy = None;
+        self.refresh(graph);
+    }
+
+    /// Drops the edits that weren't applied and reloads the fields from the graph
+    pub fn discard(&mut self, graph: &Graph) {
+        self.is_dirty = false;
+        self.refresh(graph);
+    }
+
+    /// Reloads the fields from the graph, unless the user is in the middle of editing them
+    pub fn refresh(&mut self, graph: &Graph) {
+        if self.is_dirty {
+            return;
+        }
+        self.dummy = self
+            .component_id
+            .and_then(|id| graph.components.get(&id))
+            .map(|component| DummyComponent::from_component(component, graph.unit_system));
+        if self.dummy.is_none() {
+            self.component_id = None;
+        }
+    }
+}
+
+/// Reports the focused widget, or `None` when nothing with an id is focused.
+/// Unlike `focusable::find_focused` it also reports losing focus, so a field can be applied then.
+pub fn find_focused<T: 'static>(on_found: fn(Option<Id>) -> T) -> impl Operation<T> {
+    struct FindFocused<T> {
+        focused: Option<Id>,
+        on_found: fn(Option<Id>) -> T,
+    }
+
+    impl<T> Operation<T> for FindFocused<T> {
+        fn container(
+            &mut self,
+            _id: Option<&Id>,
+            _bounds: Rectangle,
+            operate_on_children: &mut dyn FnMut(&mut dyn Operation<T>),
+        ) {
+            operate_on_children(self);
+        }
+
+        fn focusable(&mut self, state: &mut dyn Focusable, id: Option<&Id>) {
+            if state.is_focused() {
+                self.focused = id.cloned();
+            }
+        }
+
+        fn finish(&self) -> Outcome<T> {
+            Outcome::Some((self.on_found)(self.focused.clone()))
+        }
+    }
+
+    FindFocused {
+        focused: None,
+        on_found,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::multibody::MultibodyComponent;
+    use crate::ui::canvas::graph::test_utils::add_component;
+    use crate::ui::dummies::{DummyBody, DummyTrait};
+    use iced::Point;
+
+    fn add_body(graph: &mut Graph) -> Uuid {
+        let dummy = DummyComponent::Body(DummyBody::new(Uuid::new_v4()));
+        add_component(&mut graph.components, &mut graph.nodes, "body1", dummy, Point::ORIGIN).0
+    }
+
+    #[test]
+    fn test_inspector_keeps_unapplied_edits() {
+        let mut graph = Graph::default();
+        let component_id = add_body(&mut graph);
+
+        let mut inspector = Inspector::default();
+        inspector.select(Some(component_id), &graph);
+        assert_eq!(inspector.get_component_id(), Some(component_id));
+        assert_eq!(inspector.dummy.as_ref().unwrap().get_name(), "body1");
+
+        inspector.dummy.as_mut().unwrap().set_name("renamed");
+        inspector.is_dirty = true;
+        inspector.refresh(&graph);
+        assert_eq!(inspector.dummy.as_ref().unwrap().get_name(), "renamed");
+
+        // a deleted component empties the panel
+        graph.components.remove(&component_id);
+        inspector.select(Some(component_id), &graph);
+        assert!(inspector.dummy.is_none());
+        assert_eq!(inspector.get_component_id(), None);
+    }
+
+    #[test]
+    fn test_invalid_fields_are_not_applied() {
+        let mut graph = Graph::default();
+        let component_id = add_body(&mut graph);
+        let mut inspector = Inspector::default();
+        inspector.select(Some(component_id), &graph);
+
+        // mass is the first body field and has to be positive
+        let dummy = inspector.dummy.as_mut().unwrap();
+        dummy.set_value(0, "-2");
+        assert!(dummy.has_invalid_fields(&graph.get_field_context()));
+        assert!(graph.edit_component(dummy, component_id).is_err());
+        match graph.components.get(&component_id) {
+            Some(MultibodyComponent::Body(body)) => assert_eq!(body.get_mass(), 1.0),
+            _ => panic!("the body should still be there"),
+        }
+
+        dummy.set_value(0, "2");
+        assert!(graph.edit_component(dummy, component_id).is_ok());
+        match graph.components.get(&component_id) {
+            Some(MultibodyComponent::Body(body)) => assert_eq!(body.get_mass(), 2.0),
+            _ => panic!("the body should still be there"),
+        }
+
+        // applying it again changes nothing, so one undo goes back past the edit
+        assert!(graph.edit_component(dummy, component_id).is_ok());
+        assert!(graph.undo());
+        match graph.components.get(&component_id) {
+            Some(MultibodyComponent::Body(body)) => assert_eq!(body.get_mass(), 1.0),
+            _ => panic!("the body should still be there"),
+        }
+    }
+}
@@ -0,0 +1,130 @@
+use serde::{Deserialize, Serialize};
+
+use crate::ui::expression::{self, ExpressionErrors, Variables};
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ParameterErrors {
+    DuplicateName,
+    Expression(ExpressionErrors),
+    InvalidName,
+    ReservedName,
+}
+
+impl ParameterErrors {
+    pub fn get_error_message(&self) -> String {
+        match self {
+            ParameterErrors::DuplicateName => "Another parameter has this name.".to_string(),
+            ParameterErrors::Expression(error) => error.get_error_message(),
+            ParameterErrors::InvalidName => {
+                "Names start with a letter and only use letters, digits and _.".to_string()
+            }
+            ParameterErrors::ReservedName => {
+                "Constants and functions can't be used as names.".to_string()
+            }
+        }
+    }
+}
+
+/// A named value that component fields can use in their expressions
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Parameter {
+    pub name: String,
+    pub expression: String,
+}
+
+/// The project's parameters, in the order they're listed.
+/// A parameter can use the ones above it, which also rules out cycles.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Parameters {
+    pub entries: Vec<Parameter>,
+}
+
+impl Parameters {
+    /// Evaluates every parameter, one result per entry.
+    /// A broken parameter is left out of the variables the ones below it see.
+    pub fn evaluate(&self) -> Vec<Result<f64, ParameterErrors>> {
+        let mut variables = Variables::new();
+        let mut results = Vec::new();
+        for (index, parameter) in self.entries.iter().enumerate() {
+            let result = evaluate_parameter(parameter, &self.entries[..index], &variables);
+            if let Ok(value) = result {
+                variables.insert(parameter.name.clone(), value);
+            }
+            results.push(result);
+        }
+        results
+    }
+
+    /// The values of every parameter that evaluates
+    pub fn get_variables(&self) -> Variables {
+        self.entries
+            .iter()
+            .zip(self.evaluate())
+            .filter_map(|(parameter, result)| Some((parameter.name.clone(), result.ok()?)))
+            .collect()
+    }
+}
+
+/// `above` are the parameters listed before this one, `variables` those of them that evaluated
+fn evaluate_parameter(
+    parameter: &Parameter,
+    above: &[Parameter],
+    variables: &Variables,
+) -> Result<f64, ParameterErrors> {
+    let name = parameter.name.as_str();
+    let mut chars = name.chars();
+    let is_identifier = chars.next().is_some_and(char::is_alphabetic)
+        && chars.all(|c| c.is_alphanumeric() || c == '_');
+    if !is_identifier {
+        return Err(ParameterErrors::InvalidName);
+    }
+    if expression::is_reserved(name) {
+        return Err(ParameterErrors::ReservedName);
+    }
+    if above.iter().any(|other| other.name == name) {
+        return Err(ParameterErrors::DuplicateName);
+    }
+    expression::evaluate(&parameter.expression, variables).map_err(ParameterErrors::Expression)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parameter(name: &str, expression: &str) -> Parameter {
+        Parameter {
+            name: name.to_string(),
+            expression: expression.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_parameters_evaluate_in_order() {
+        let parameters = Parameters {
+            entries: vec![
+                parameter("m", "2"),
+                parameter("L", "m * 1.5"),
+                // only parameters above can be used
+                parameter("k", "n + 1"),
+                parameter("n", "3"),
+                parameter("m", "4"),
+                parameter("pi", "3"),
+            ],
+        };
+
+        let results = parameters.evaluate();
+        assert_eq!(results[1], Ok(3.0));
+        assert_eq!(
+            results[2],
+            Err(ParameterErrors::Expression(ExpressionErrors::UnknownName(
+                "n".to_string()
+            )))
+        );
+        assert_eq!(results[4], Err(ParameterErrors::DuplicateName));
+        assert_eq!(results[5], Err(ParameterErrors::ReservedName));
+
+        let variables = parameters.get_variables();
+        assert_eq!(variables.len(), 3);
+        assert_eq!(variables["m"], 2.0);
+    }
+}
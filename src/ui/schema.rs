@@ -0,0 +1,314 @@
+use crate::ui::expression::{self, ExpressionErrors, Variables};
+use crate::ui::units::{self, UnitErrors, UnitSystem};
+
+/// How a field's text is read
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum FieldKind {
+    Float,
+}
+
+/// Values a field accepts
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum FieldRange {
+    Any,
+    /// Inclusive of both ends
+    Between(f64, f64),
+    NonNegative,
+    Positive,
+}
+
+impl FieldRange {
+    pub fn contains(&self, value: f64) -> bool {
+        match self {
+            FieldRange::Any => value.is_finite(),
+            FieldRange::Between(min, max) => value.is_finite() && (*min..=*max).contains(&value),
+            FieldRange::NonNegative => value.is_finite() && value >= 0.0,
+            FieldRange::Positive => value.is_finite() && value > 0.0,
+        }
+    }
+
+    pub fn get_description(&self) -> String {
+        match self {
+            FieldRange::Any => "any number".to_string(),
+            FieldRange::Between(min, max) => {
+                // rounded so converted bounds don't show float noise
+                let round = |value: f64| (value * 1e4).round() / 1e4;
+                format!("between {} and {}", round(*min), round(*max))
+            }
+            FieldRange::NonNegative => "at least 0".to_string(),
+            FieldRange::Positive => "greater than 0".to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum FieldErrors {
+    Empty,
+    Expression(ExpressionErrors),
+    OutOfRange(FieldRange),
+    Unit(UnitErrors),
+}
+
+impl FieldErrors {
+    pub fn get_error_message(&self) -> String {
+        match self {
+            FieldErrors::Empty => "A value is required.".to_string(),
+            FieldErrors::Expression(error) => error.get_error_message(),
+            FieldErrors::OutOfRange(range) => format!("Must be {}.", range.get_description()),
+            FieldErrors::Unit(error) => error.get_error_message(),
+        }
+    }
+}
+
+impl std::fmt::Display for FieldErrors {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.get_error_message())
+    }
+}
+
+impl std::error::Error for FieldErrors {}
+
+/// What field text is evaluated against
+#[derive(Debug, Clone, Default)]
+pub struct FieldContext {
+    /// Numbers typed without a unit are in this system's units
+    pub unit_system: UnitSystem,
+    pub variables: Variables,
+}
+
+/// Describes one editable field of a component, the editor builds its
+/// form row, parsing and validation from this alone
+#[derive(Debug, Clone, Copy)]
+pub struct FieldSchema {
+    /// What new components start with
+    pub default: f64,
+    pub help: &'static str,
+    pub kind: FieldKind,
+    pub name: &'static str,
+    pub range: FieldRange,
+    /// The SI unit values are kept in
+    pub unit: &'static str,
+}
+
+impl FieldSchema {
+    pub const fn float(
+        name: &'static str,
+        unit: &'static str,
+        default: f64,
+        help: &'static str,
+    ) -> Self {
+        Self {
+            default,
+            help,
+            kind: FieldKind::Float,
+            name,
+            range: FieldRange::Any,
+            unit,
+        }
+    }
+
+    pub const fn with_range(mut self, range: FieldRange) -> Self {
+        self.range = range;
+        self
+    }
+
+    /// Returns the value in SI units. The text is an expression that can use the context's
+    /// variables, optionally followed by a unit like `30 deg` or `m*L^2/3 lbm*in^2`.
+    /// Without one it's in the context's unit system, empty text is an error.
+    pub fn parse(&self, value: &str, context: &FieldContext) -> Result<f64, FieldErrors> {
+        let value = value.trim();
+        if value.is_empty() {
+            return Err(FieldErrors::Empty);
+        }
+        let (value, unit) = units::split_unit(value).map_err(FieldErrors::Unit)?;
+        let number = match self.kind {
+            FieldKind::Float => {
+                expression::evaluate(value, &context.variables).map_err(FieldErrors::Expression)?
+            }
+        };
+
+        let display_unit = self.get_display_unit(context.unit_system);
+        let unit = match unit {
+            Some(unit) => unit,
+            None => units::parse_unit(&display_unit).map_err(FieldErrors::Unit)?,
+        };
+        let si_unit = units::parse_unit(self.unit).map_err(FieldErrors::Unit)?;
+        let number = unit
+            .convert(number, &si_unit)
+            .ok_or(FieldErrors::Unit(UnitErrors::WrongDimension(display_unit)))?;
+        if !self.range.contains(number) {
+            return Err(FieldErrors::OutOfRange(self.get_display_range(context.unit_system)));
+        }
+        Ok(number)
+    }
+
+    /// The default with its SI unit written out, so it means the same in any unit system
+    pub fn get_default_text(&self) -> String {
+        if self.unit.is_empty() {
+            return self.default.to_string();
+        }
+        format!("{} {}", self.default, self.unit)
+    }
+
+    /// The unit the field is shown and typed in
+    pub fn get_display_unit(&self, unit_system: UnitSystem) -> String {
+        unit_system.get_display_unit(self.unit)
+    }
+
+    /// The range with its bounds in the display unit, for describing it
+    pub fn get_display_range(&self, unit_system: UnitSystem) -> FieldRange {
+        match self.range {
+            FieldRange::Between(min, max) => FieldRange::Between(
+                self.to_display_value(min, unit_system),
+                self.to_display_value(max, unit_system),
+            ),
+            range => range,
+        }
+    }
+
+    /// An SI value in the display unit
+    pub fn to_display_value(self, value: f64, unit_system: UnitSystem) -> f64 {
+        let display_unit = units::parse_unit(&self.get_display_unit(unit_system));
+        match (units::parse_unit(self.unit), display_unit) {
+            (Ok(si_unit), Ok(display_unit)) => {
+                si_unit.convert(value, &display_unit).unwrap_or(value)
+            }
+            _ => value,
+        }
+    }
+
+    /// Text as typed in `unit_system`, rewritten to mean the same whatever the system.
+    /// Stored text without a unit is in SI, so any other system writes its unit out.
+    pub fn to_stored_text(self, text: &str, unit_system: UnitSystem) -> String {
+        with_unit(text, &self.get_display_unit(unit_system), unit_system)
+    }
+
+    /// Stored text as it would be typed in `unit_system`, the reverse of `to_stored_text`
+    pub fn to_display_text(self, text: &str, unit_system: UnitSystem) -> String {
+        with_unit(text, self.unit, unit_system)
+    }
+}
+
+/// Writes `unit` after text that doesn't have one, unless the system is SI where
+/// bare numbers already mean the same thing typed and stored
+fn with_unit(text: &str, unit: &str, unit_system: UnitSystem) -> String {
+    let text = text.trim();
+    let is_bare = matches!(units::split_unit(text), Ok((_, None)));
+    if unit_system == UnitSystem::Si || text.is_empty() || unit.is_empty() || !is_bare {
+        return text.to_string();
+    }
+    format!("{} {}", text, unit)
+}
+
+/// Every field's text as typed in `unit_system`, ready to keep with the component
+pub fn to_stored_texts(
+    schema: &[FieldSchema],
+    values: &[String],
+    unit_system: UnitSystem,
+) -> Vec<String> {
+    schema
+        .iter()
+        .zip(values)
+        .map(|(field, text)| field.to_stored_text(text, unit_system))
+        .collect()
+}
+
+/// Parses every field of a component, in schema order.
+/// Fails with the name of the first invalid field, a missing one is empty.
+pub fn parse_values<const N: usize>(
+    schema: &[FieldSchema; N],
+    values: &[String],
+    context: &FieldContext,
+) -> Result<[f64; N], (&'static str, FieldErrors)> {
+    let mut parsed = [0.0; N];
+    for (index, field) in schema.iter().enumerate() {
+        let value = values.get(index).map(String::as_str).unwrap_or_default();
+        parsed[index] = field
+            .parse(value, context)
+            .map_err(|error| (field.name, error))?;
+    }
+    Ok(parsed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::f64::consts::FRAC_PI_2;
+
+    #[test]
+    fn test_field_schema_parse() {
+        let context = FieldContext {
+            unit_system: UnitSystem::Si,
+            variables: Variables::from([("m".to_string(), 3.0)]),
+        };
+        let mass = FieldSchema::float("mass", "kg", 1.0, "").with_range(FieldRange::Positive);
+        assert_eq!(mass.parse("", &context), Err(FieldErrors::Empty));
+        assert_eq!(mass.parse(&mass.get_default_text(), &context), Ok(1.0));
+        assert_eq!(mass.parse(" 2.5 ", &context), Ok(2.5));
+        assert_eq!(mass.parse("m / 2", &context), Ok(1.5));
+        assert_eq!(mass.parse("m / 2 g", &context), Ok(1.5e-3));
+        assert_eq!(
+            mass.parse("heavy", &context),
+            Err(FieldErrors::Expression(ExpressionErrors::UnknownName(
+                "heavy".to_string()
+            )))
+        );
+        assert_eq!(
+            mass.parse("m - 3", &context),
+            Err(FieldErrors::OutOfRange(FieldRange::Positive))
+        );
+        assert_eq!(
+            mass.parse("1 / 0", &context),
+            Err(FieldErrors::OutOfRange(FieldRange::Positive))
+        );
+        assert_eq!(
+            mass.parse("2 m", &context),
+            Err(FieldErrors::Unit(UnitErrors::WrongDimension(
+                "kg".to_string()
+            )))
+        );
+
+        let schema = [mass, FieldSchema::float("cmx", "m", 0.0, "")];
+        assert_eq!(
+            parse_values(&schema, &["2".to_string(), "0".to_string()], &context),
+            Ok([2.0, 0.0])
+        );
+        assert_eq!(
+            parse_values(&schema, &["2".to_string()], &context),
+            Err(("cmx", FieldErrors::Empty))
+        );
+        assert_eq!(
+            parse_values(&schema, &["-1".to_string()], &context),
+            Err(("mass", FieldErrors::OutOfRange(FieldRange::Positive)))
+        );
+
+        let range = FieldRange::Between(-FRAC_PI_2, FRAC_PI_2);
+        let latitude = FieldSchema::float("latitude", "rad", 0.0, "").with_range(range);
+        assert_eq!(latitude.parse("-pi / 2", &context), Ok(-FRAC_PI_2));
+        assert_eq!(latitude.parse("2", &context), Err(FieldErrors::OutOfRange(range)));
+        assert_eq!(range.get_description(), "between -1.5708 and 1.5708");
+        assert_eq!(
+            latitude.get_display_range(UnitSystem::Imperial).get_description(),
+            "between -90 and 90"
+        );
+    }
+
+    #[test]
+    fn test_field_schema_units() {
+        let imperial = FieldContext {
+            unit_system: UnitSystem::Imperial,
+            ..Default::default()
+        };
+        let mass = FieldSchema::float("mass", "kg", 1.0, "");
+        assert_eq!(mass.get_display_unit(UnitSystem::Imperial), "lbm");
+        assert_eq!(mass.parse("2", &imperial), Ok(2.0 * 0.45359237));
+        assert_eq!(mass.parse("2 kg", &imperial), Ok(2.0));
+
+        // bare numbers are stored in SI, so other systems write their unit out
+        assert_eq!(mass.to_stored_text("2", UnitSystem::Imperial), "2 lbm");
+        assert_eq!(mass.to_stored_text("2 g", UnitSystem::Imperial), "2 g");
+        assert_eq!(mass.to_stored_text("2", UnitSystem::Si), "2");
+        assert_eq!(mass.to_display_text("2", UnitSystem::Imperial), "2 kg");
+        assert!((mass.to_display_value(1.0, UnitSystem::Imperial) - 2.20462262).abs() < 1e-8);
+    }
+}
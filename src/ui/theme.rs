@@ -1,5 +1,7 @@
 use iced::{application, Color};
 
+pub mod user;
+
 macro_rules! color {
     ($red:expr, $green:expr, $blue:expr) => {
         Color::from_rgb(
@@ -10,7 +12,7 @@ macro_rules! color {
     };
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub struct Theme {
     pub background: Color,
     pub error: Color,
@@ -22,6 +24,8 @@ pub struct Theme {
     pub shadow: Color,
     pub primary: Color,
     pub highlight: Color,
+    /// Connections that would be accepted
+    pub success: Color,
 }
 
 impl Theme {
@@ -38,9 +42,114 @@ impl Theme {
         //highlight: color!(212, 207, 40),
         primary: color!(235 / 2, 161 / 2, 66 / 2),
         highlight: color!(235, 161, 66),
+        success: color!(80, 200, 120),
+    };
+
+    pub const DRACULA: Self = Self {
+        background: color!(40, 42, 54),
+        error: color!(255, 85, 85),
+        node_background: color!(33, 34, 44),
+        text_background: color!(68, 71, 90),
+        text: color!(248, 248, 242),
+        greyed: color!(98, 114, 164),
+        border: color!(25, 26, 33),
+        shadow: color!(25, 26, 33),
+        primary: color!(189, 147, 249),
+        highlight: color!(255, 121, 198),
+        success: color!(80, 250, 123),
+    };
+
+    pub const CYBERPUNK: Self = Self {
+        background: color!(20, 16, 32),
+        error: color!(255, 56, 100),
+        node_background: color!(13, 10, 24),
+        text_background: color!(40, 30, 60),
+        text: color!(242, 233, 255),
+        greyed: color!(150, 130, 180),
+        border: color!(0, 0, 0),
+        shadow: color!(10, 5, 20),
+        primary: color!(140, 30, 255),
+        highlight: color!(255, 41, 117),
+        success: color!(0, 229, 255),
+    };
+
+    pub const LIGHT: Self = Self {
+        background: color!(245, 245, 245),
+        error: color!(200, 40, 40),
+        node_background: color!(255, 255, 255),
+        text_background: color!(230, 230, 230),
+        text: color!(30, 30, 30),
+        greyed: color!(120, 120, 120),
+        border: color!(180, 180, 180),
+        shadow: color!(200, 200, 200),
+        primary: color!(66, 133, 244),
+        highlight: color!(235, 140, 30),
+        success: color!(46, 160, 67),
+    };
+
+    pub const HIGH_CONTRAST: Self = Self {
+        background: color!(0, 0, 0),
+        error: color!(255, 0, 0),
+        node_background: color!(0, 0, 0),
+        text_background: color!(30, 30, 30),
+        text: color!(255, 255, 255),
+        greyed: color!(200, 200, 200),
+        border: color!(255, 255, 255),
+        shadow: color!(0, 0, 0),
+        primary: color!(255, 255, 0),
+        highlight: color!(0, 255, 255),
+        success: color!(0, 255, 0),
     };
 }
 
+/// The palettes that can be picked from the menu
+#[derive(Debug, Clone, Default, PartialEq)]
+pub enum ThemeChoice {
+    Cyberpunk,
+    Dracula,
+    HighContrast,
+    Light,
+    #[default]
+    Orange,
+    /// Loaded from the themes directory, see `user::load_themes`
+    User(String, Theme),
+}
+
+impl ThemeChoice {
+    pub const ALL: [Self; 5] = [
+        Self::Orange,
+        Self::Dracula,
+        Self::Cyberpunk,
+        Self::Light,
+        Self::HighContrast,
+    ];
+
+    pub fn get_theme(&self) -> Theme {
+        match self {
+            Self::Cyberpunk => Theme::CYBERPUNK,
+            Self::Dracula => Theme::DRACULA,
+            Self::HighContrast => Theme::HIGH_CONTRAST,
+            Self::Light => Theme::LIGHT,
+            Self::Orange => Theme::ORANGE,
+            Self::User(_, theme) => *theme,
+        }
+    }
+}
+
+impl std::fmt::Display for ThemeChoice {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            Self::Cyberpunk => "Cyberpunk",
+            Self::Dracula => "Dracula",
+            Self::HighContrast => "High Contrast",
+            Self::Light => "Light",
+            Self::Orange => "Orange",
+            Self::User(name, _) => name,
+        };
+        write!(f, "{}", name)
+    }
+}
+
 impl Default for Theme {
     fn default() -> Self {
         Self::ORANGE
@@ -88,13 +197,22 @@ impl iced::widget::container::StyleSheet for Theme {
     }
 }
 
+#[derive(Debug, Clone, Copy, Default)]
+pub enum Text {
+    #[default]
+    Default,
+    Error,
+}
+
 impl iced::widget::text::StyleSheet for Theme {
-    type Style = ();
+    type Style = Text;
 
-    fn appearance(&self, _style: Self::Style) -> iced::widget::text::Appearance {
-        iced::widget::text::Appearance {
-            color: Some(self.text),
-        }
+    fn appearance(&self, style: Self::Style) -> iced::widget::text::Appearance {
+        let color = match style {
+            Text::Default => self.text,
+            Text::Error => self.error,
+        };
+        iced::widget::text::Appearance { color: Some(color) }
     }
 }
 
@@ -142,14 +260,26 @@ impl iced_aw::style::card::StyleSheet for Theme {
     }
 }
 
+/// Invalid inputs get an error colored border
+#[derive(Debug, Clone, Copy, Default)]
+pub enum TextInput {
+    #[default]
+    Default,
+    Error,
+}
+
 impl iced::widget::text_input::StyleSheet for Theme {
-    type Style = Application;
+    type Style = TextInput;
 
-    fn active(&self, _style: &Self::Style) -> iced::widget::text_input::Appearance {
+    fn active(&self, style: &Self::Style) -> iced::widget::text_input::Appearance {
+        let color = match style {
+            TextInput::Default => self.border,
+            TextInput::Error => self.error,
+        };
         iced::widget::text_input::Appearance {
             background: iced::Background::Color(self.text_background),
             border: iced::Border {
-                color: Color::BLACK,
+                color,
                 width: 1.0,
                 radius: 0.0.into(),
             },
@@ -157,8 +287,15 @@ impl iced::widget::text_input::StyleSheet for Theme {
         }
     }
 
-    fn focused(&self, _style: &Self::Style) -> iced::widget::text_input::Appearance {
-        let border = iced::Border::default();
+    fn focused(&self, style: &Self::Style) -> iced::widget::text_input::Appearance {
+        let border = match style {
+            TextInput::Default => iced::Border::default(),
+            TextInput::Error => iced::Border {
+                color: self.error,
+                width: 1.0,
+                radius: 0.0.into(),
+            },
+        };
 
         iced::widget::text_input::Appearance {
             background: iced::Background::Color(self.text_background),
@@ -210,3 +347,111 @@ impl iced_aw::style::modal::StyleSheet for Theme {
         }
     }
 }
+
+impl iced::widget::pick_list::StyleSheet for Theme {
+    type Style = ();
+
+    fn active(&self, _style: &Self::Style) -> iced::widget::pick_list::Appearance {
+        iced::widget::pick_list::Appearance {
+            text_color: self.text,
+            placeholder_color: self.greyed,
+            handle_color: self.text,
+            background: iced::Background::Color(self.node_background),
+            border: iced::Border {
+                color: self.border,
+                width: 1.0,
+                radius: 2.0.into(),
+            },
+        }
+    }
+
+    fn hovered(&self, style: &Self::Style) -> iced::widget::pick_list::Appearance {
+        iced::widget::pick_list::Appearance {
+            border: iced::Border {
+                color: self.highlight,
+                width: 1.0,
+                radius: 2.0.into(),
+            },
+            ..self.active(style)
+        }
+    }
+}
+
+impl iced::overlay::menu::StyleSheet for Theme {
+    type Style = ();
+
+    fn appearance(&self, _style: &Self::Style) -> iced::overlay::menu::Appearance {
+        iced::overlay::menu::Appearance {
+            text_color: self.text,
+            background: iced::Background::Color(self.node_background),
+            border: iced::Border {
+                color: self.border,
+                width: 1.0,
+                radius: 0.0.into(),
+            },
+            selected_text_color: self.node_background,
+            selected_background: iced::Background::Color(self.highlight),
+        }
+    }
+}
+
+impl iced::widget::scrollable::StyleSheet for Theme {
+    type Style = ();
+
+    fn active(&self, _style: &Self::Style) -> iced::widget::scrollable::Appearance {
+        iced::widget::scrollable::Appearance {
+            container: iced::widget::container::Appearance::default(),
+            scrollbar: iced::widget::scrollable::Scrollbar {
+                background: Some(iced::Background::Color(self.text_background)),
+                border: iced::Border::with_radius(2.0),
+                scroller: iced::widget::scrollable::Scroller {
+                    color: self.greyed,
+                    border: iced::Border::with_radius(2.0),
+                },
+            },
+            gap: None,
+        }
+    }
+
+    fn hovered(
+        &self,
+        style: &Self::Style,
+        is_mouse_over_scrollbar: bool,
+    ) -> iced::widget::scrollable::Appearance {
+        let mut appearance = self.active(style);
+        if is_mouse_over_scrollbar {
+            appearance.scrollbar.scroller.color = self.highlight;
+        }
+        appearance
+    }
+}
+
+impl iced_aw::style::split::StyleSheet for Theme {
+    type Style = ();
+
+    fn active(&self, _style: &Self::Style) -> iced_aw::style::split::Appearance {
+        iced_aw::style::split::Appearance {
+            background: Some(iced::Background::Color(self.background)),
+            border_width: 0.0,
+            border_color: self.border,
+            divider_background: iced::Background::Color(self.border),
+            divider_border_width: 0.0,
+            divider_border_color: self.border,
+            ..iced_aw::style::split::Appearance::default()
+        }
+    }
+
+    fn hovered(&self, style: &Self::Style) -> iced_aw::style::split::Appearance {
+        iced_aw::style::split::Appearance {
+            divider_background: iced::Background::Color(self.greyed),
+            ..self.active(style)
+        }
+    }
+
+    fn dragged(&self, style: &Self::Style) -> iced_aw::style::split::Appearance {
+        iced_aw::style::split::Appearance {
+            divider_background: iced::Background::Color(self.highlight),
+            ..self.active(style)
+        }
+    }
+}
@@ -0,0 +1,202 @@
+use iced::Color;
+use serde::Deserialize;
+use std::path::{Path, PathBuf};
+
+use super::Theme;
+
+/// A theme's name and colors
+pub type NamedTheme = (String, Theme);
+
+#[derive(Debug, Clone)]
+pub enum ThemeErrors {
+    Io(std::io::ErrorKind),
+    InvalidColor { field: &'static str, value: String },
+    Parse { line: usize, col: usize },
+}
+
+/// What's wrong inside the file, `Errors::Theme` says which file it is
+impl std::fmt::Display for ThemeErrors {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ThemeErrors::Io(kind) => write!(f, "Could not read the file ({}).", kind),
+            ThemeErrors::InvalidColor { field, value } => write!(
+                f,
+                "Invalid color \"{}\" for {}. Use #rgb, #rrggbb or #rrggbbaa.",
+                value, field
+            ),
+            ThemeErrors::Parse { line, col } => {
+                write!(f, "Invalid syntax at line {}, column {}.", line, col)
+            }
+        }
+    }
+}
+
+impl std::error::Error for ThemeErrors {}
+
+/// A theme as written in a RON file, e.g.
+/// `(name: "Team", background: "#1e1e1e", primary: "#007acc")`.
+/// Colors are `#rgb`, `#rrggbb` or `#rrggbbaa`, anything left out comes from the default theme.
+#[derive(Debug, Deserialize)]
+struct ThemeFile {
+    name: String,
+    background: Option<String>,
+    border: Option<String>,
+    error: Option<String>,
+    greyed: Option<String>,
+    highlight: Option<String>,
+    node_background: Option<String>,
+    primary: Option<String>,
+    shadow: Option<String>,
+    success: Option<String>,
+    text: Option<String>,
+    text_background: Option<String>,
+}
+
+/// Returns the directory user themes are read from, `<config dir>/jds/themes`
+pub fn get_themes_dir() -> Option<PathBuf> {
+    let config_dir = if cfg!(target_os = "windows") {
+        std::env::var_os("APPDATA").map(PathBuf::from)
+    } else if cfg!(target_os = "macos") {
+        std::env::var_os("HOME").map(|home| PathBuf::from(home).join("Library/Application Support"))
+    } else {
+        std::env::var_os("XDG_CONFIG_HOME")
+            .map(PathBuf::from)
+            .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))
+    };
+    config_dir.map(|dir| dir.join("jds").join("themes"))
+}
+
+/// Reads every `.ron` file in `dir`, sorted by file name.
+/// Returns the themes that loaded and the file name and error of those that didn't.
+/// A missing directory just means there are no user themes.
+pub fn load_themes(dir: &Path) -> (Vec<NamedTheme>, Vec<(String, ThemeErrors)>) {
+    let mut paths: Vec<PathBuf> = match std::fs::read_dir(dir) {
+        Ok(entries) => entries
+            .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+            .filter(|path| path.extension().is_some_and(|extension| extension == "ron"))
+            .collect(),
+        Err(_) => return (Vec::new(), Vec::new()),
+    };
+    paths.sort();
+
+    let mut themes = Vec::new();
+    let mut errors = Vec::new();
+    for path in paths {
+        let file_name = path
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_default();
+        let result = std::fs::read_to_string(&path)
+            .map_err(|error| ThemeErrors::Io(error.kind()))
+            .and_then(|contents| from_ron(&contents));
+        match result {
+            Ok(theme) => themes.push(theme),
+            Err(error) => errors.push((file_name, error)),
+        }
+    }
+    (themes, errors)
+}
+
+/// Parses a theme file, returning its name and colors
+pub fn from_ron(contents: &str) -> Result<NamedTheme, ThemeErrors> {
+    let options =
+        ron::Options::default().with_default_extension(ron::extensions::Extensions::IMPLICIT_SOME);
+    let file: ThemeFile = options
+        .from_str(contents)
+        .map_err(|error| ThemeErrors::Parse {
+            line: error.position.line,
+            col: error.position.col,
+        })?;
+
+    let mut theme = Theme::default();
+    for (field, value, color) in [
+        ("background", &file.background, &mut theme.background),
+        ("border", &file.border, &mut theme.border),
+        ("error", &file.error, &mut theme.error),
+        ("greyed", &file.greyed, &mut theme.greyed),
+        ("highlight", &file.highlight, &mut theme.highlight),
+        (
+            "node_background",
+            &file.node_background,
+            &mut theme.node_background,
+        ),
+        ("primary", &file.primary, &mut theme.primary),
+        ("shadow", &file.shadow, &mut theme.shadow),
+        ("success", &file.success, &mut theme.success),
+        ("text", &file.text, &mut theme.text),
+        (
+            "text_background",
+            &file.text_background,
+            &mut theme.text_background,
+        ),
+    ] {
+        if let Some(value) = value {
+            *color = parse_color(value).ok_or_else(|| ThemeErrors::InvalidColor {
+                field,
+                value: value.clone(),
+            })?;
+        }
+    }
+    Ok((file.name, theme))
+}
+
+/// Parses `#rgb`, `#rrggbb` or `#rrggbbaa`
+fn parse_color(value: &str) -> Option<Color> {
+    let hex = value.trim().strip_prefix('#')?;
+    if !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+        return None;
+    }
+    let channel = |index: usize, width: usize| {
+        let digits = &hex[index * width..(index + 1) * width];
+        let value = u8::from_str_radix(digits, 16).ok()?;
+        // #rgb repeats each digit, f becomes ff
+        Some(if width == 1 { value * 17 } else { value })
+    };
+
+    let (width, has_alpha) = match hex.len() {
+        3 => (1, false),
+        6 => (2, false),
+        8 => (2, true),
+        _ => return None,
+    };
+    let alpha = if has_alpha { channel(3, width)? } else { 255 };
+    Some(Color::from_rgba8(
+        channel(0, width)?,
+        channel(1, width)?,
+        channel(2, width)?,
+        alpha as f32 / 255.0,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_theme_from_ron() {
+        assert_eq!(parse_color("#fff"), Some(Color::WHITE));
+        assert_eq!(
+            parse_color("#00000080").map(|color| color.a),
+            Some(128.0 / 255.0)
+        );
+        assert_eq!(parse_color("1e1e1e"), None);
+        assert_eq!(parse_color("#12345g"), None);
+
+        let (name, theme) = from_ron(r##"(name: "Team", background: "#ff0000")"##).unwrap();
+        assert_eq!(name, "Team");
+        assert_eq!(theme.background, Color::from_rgb8(255, 0, 0));
+        assert_eq!(theme.primary, Theme::default().primary);
+
+        assert!(matches!(
+            from_ron(r##"(name: "Team", primary: "#12345g")"##),
+            Err(ThemeErrors::InvalidColor {
+                field: "primary",
+                ..
+            })
+        ));
+        assert!(matches!(
+            from_ron("(name: \"Team\""),
+            Err(ThemeErrors::Parse { .. })
+        ));
+    }
+}
@@ -0,0 +1,85 @@
+use std::time::{Duration, Instant};
+
+/// How long a toast stays up unless it's closed first
+const TOAST_DURATION: Duration = Duration::from_secs(5);
+/// The oldest toast is dropped to make room past this many
+const MAX_TOASTS: usize = 4;
+/// How often toasts are checked for expiry, so they can stay up this much past `TOAST_DURATION`
+pub const TOAST_TICK: Duration = Duration::from_millis(250);
+
+/// A message shown over the graph for a few seconds without blocking anything
+#[derive(Debug, Clone)]
+pub struct Toast {
+    pub id: usize,
+    pub message: String,
+    shown: Instant,
+}
+
+/// The toasts currently showing, oldest first
+#[derive(Debug, Default)]
+pub struct Toasts {
+    entries: Vec<Toast>,
+    next_id: usize,
+}
+
+impl Toasts {
+    pub fn close(&mut self, id: usize) {
+        self.entries.retain(|toast| toast.id != id);
+    }
+
+    pub fn get_toasts(&self) -> &[Toast] {
+        &self.entries
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn push(&mut self, message: impl Into<String>, now: Instant) {
+        if self.entries.len() == MAX_TOASTS {
+            self.entries.remove(0);
+        }
+        self.entries.push(Toast {
+            id: self.next_id,
+            message: message.into(),
+            shown: now,
+        });
+        self.next_id += 1;
+    }
+
+    /// Drops the toasts that have been up long enough, returns whether any were
+    pub fn expire(&mut self, now: Instant) -> bool {
+        let count = self.entries.len();
+        self.entries
+            .retain(|toast| now.duration_since(toast.shown) < TOAST_DURATION);
+        self.entries.len() != count
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_toasts_expire_and_close() {
+        let start = Instant::now();
+        let mut toasts = Toasts::default();
+        for index in 0..=MAX_TOASTS {
+            toasts.push(
+                format!("error {}", index),
+                start + Duration::from_secs(index as u64),
+            );
+        }
+        assert_eq!(toasts.get_toasts().len(), MAX_TOASTS);
+        assert_eq!(toasts.get_toasts()[0].message, "error 1");
+
+        toasts.close(toasts.get_toasts()[0].id);
+        assert_eq!(toasts.get_toasts()[0].message, "error 2");
+
+        assert!(!toasts.expire(start + Duration::from_secs(6)));
+        assert!(toasts.expire(start + TOAST_DURATION + Duration::from_secs(2)));
+        assert_eq!(toasts.get_toasts().len(), 2);
+        assert!(toasts.expire(start + Duration::from_secs(60)));
+        assert!(toasts.is_empty());
+    }
+}
@@ -0,0 +1,283 @@
+use serde::{Deserialize, Serialize};
+use std::f64::consts::{PI, TAU};
+
+use crate::ui::expression;
+
+/// Exponents of mass, length and time.
+/// Angles are dimensionless like in SI, so `rad/s` is the same kind of unit as `1/s`.
+pub type Dimension = [i32; 3];
+
+/// Units a field can be typed in, and how many SI units each one is
+const UNITS: [(&str, f64, Dimension); 20] = [
+    ("1", 1.0, [0, 0, 0]),
+    ("rad", 1.0, [0, 0, 0]),
+    ("deg", PI / 180.0, [0, 0, 0]),
+    ("rev", TAU, [0, 0, 0]),
+    ("kg", 1.0, [1, 0, 0]),
+    ("g", 1e-3, [1, 0, 0]),
+    ("lbm", 0.45359237, [1, 0, 0]),
+    ("slug", 14.593902937, [1, 0, 0]),
+    ("m", 1.0, [0, 1, 0]),
+    ("km", 1e3, [0, 1, 0]),
+    ("cm", 1e-2, [0, 1, 0]),
+    ("mm", 1e-3, [0, 1, 0]),
+    ("in", 0.0254, [0, 1, 0]),
+    ("ft", 0.3048, [0, 1, 0]),
+    ("s", 1.0, [0, 0, 1]),
+    ("ms", 1e-3, [0, 0, 1]),
+    ("min", 60.0, [0, 0, 1]),
+    ("h", 3600.0, [0, 0, 1]),
+    ("N", 1.0, [1, 1, -2]),
+    ("lbf", 4.4482216152605, [1, 1, -2]),
+];
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum UnitErrors {
+    InvalidUnit(String),
+    UnknownUnit(String),
+    /// The unit measures something else, holds the unit that was expected
+    WrongDimension(String),
+}
+
+impl UnitErrors {
+    pub fn get_error_message(&self) -> String {
+        match self {
+            UnitErrors::InvalidUnit(unit) => format!("'{}' is not a valid unit.", unit),
+            UnitErrors::UnknownUnit(unit) => format!("Unknown unit '{}'.", unit),
+            UnitErrors::WrongDimension(unit) => format!("Must be in units of {}.", unit),
+        }
+    }
+}
+
+/// A parsed unit like `g*cm^2`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Unit {
+    pub dimension: Dimension,
+    /// How many SI units one of this unit is
+    pub factor: f64,
+}
+
+impl Unit {
+    pub const ONE: Unit = Unit {
+        dimension: [0, 0, 0],
+        factor: 1.0,
+    };
+
+    /// `value` of this unit in `to`, `None` if they measure different things
+    pub fn convert(&self, value: f64, to: &Unit) -> Option<f64> {
+        (self.dimension == to.dimension).then(|| value * self.factor / to.factor)
+    }
+
+    /// `None` if a dimension's power overflows
+    fn powi(&self, exponent: i32) -> Option<Unit> {
+        let mut dimension = self.dimension;
+        for base in dimension.iter_mut() {
+            *base = base.checked_mul(exponent)?;
+        }
+        Some(Unit {
+            dimension,
+            factor: self.factor.powi(exponent),
+        })
+    }
+
+    /// `None` if a dimension's power overflows
+    fn times(&self, other: &Unit) -> Option<Unit> {
+        let mut dimension = self.dimension;
+        for (base, other) in dimension.iter_mut().zip(other.dimension) {
+            *base = base.checked_add(other)?;
+        }
+        Some(Unit {
+            dimension,
+            factor: self.factor * other.factor,
+        })
+    }
+}
+
+/// Parses units multiplied and divided together with integer powers, like `N*m*s/rad` or `lbm*in^2`.
+/// Each `/` divides by the unit right after it.
+pub fn parse_unit(text: &str) -> Result<Unit, UnitErrors> {
+    let invalid = || UnitErrors::InvalidUnit(text.trim().to_string());
+    let mut unit = Unit::ONE;
+    let mut rest = text.trim();
+    if rest.is_empty() {
+        return Ok(unit);
+    }
+
+    let mut is_divided = false;
+    loop {
+        let end = rest
+            .find(|c: char| !c.is_alphanumeric())
+            .unwrap_or(rest.len());
+        let name = &rest[..end];
+        if name.is_empty() {
+            return Err(invalid());
+        }
+        let base = match UNITS.iter().find(|(unit, _, _)| *unit == name) {
+            Some((_, factor, dimension)) => Unit {
+                dimension: *dimension,
+                factor: *factor,
+            },
+            None => return Err(UnitErrors::UnknownUnit(name.to_string())),
+        };
+        rest = rest[end..].trim_start();
+
+        let mut exponent: i32 = 1;
+        if let Some(power) = rest.strip_prefix('^') {
+            let power = power.trim_start();
+            let end = power
+                .char_indices()
+                .find(|(index, c)| !(c.is_ascii_digit() || (*index == 0 && *c == '-')))
+                .map_or(power.len(), |(index, _)| index);
+            exponent = power[..end].parse().map_err(|_| invalid())?;
+            rest = power[end..].trim_start();
+        }
+        if is_divided {
+            exponent = exponent.checked_neg().ok_or_else(invalid)?;
+        }
+        unit = base
+            .powi(exponent)
+            .and_then(|power| unit.times(&power))
+            .ok_or_else(invalid)?;
+
+        is_divided = match rest.chars().next() {
+            None => return Ok(unit),
+            Some('*') => false,
+            Some('/') => true,
+            Some(_) => return Err(invalid()),
+        };
+        rest = rest[1..].trim_start();
+    }
+}
+
+/// Splits field text into its expression and the unit written after it, like `m*L^2/3 lbm*in^2`.
+/// The unit is whatever follows a space and parses as one, as long as everything before it
+/// is a whole expression. Text without a unit comes back whole.
+pub fn split_unit(text: &str) -> Result<(&str, Option<Unit>), UnitErrors> {
+    let mut unknown = None;
+    for (index, _) in text.char_indices().filter(|(_, c)| c.is_whitespace()) {
+        let (expression, unit) = (text[..index].trim(), text[index..].trim());
+        if expression.is_empty() || !expression::is_valid_syntax(expression) {
+            continue;
+        }
+        match parse_unit(unit) {
+            Ok(unit) => return Ok((expression, Some(unit))),
+            // only report text that looks like it's meant as a unit, not `2 + x`
+            Err(error) if unit.starts_with(char::is_alphabetic) => {
+                unknown.get_or_insert(error);
+            }
+            Err(_) => {}
+        }
+    }
+    match unknown {
+        Some(error) if !expression::is_valid_syntax(text) => Err(error),
+        _ => Ok((text, None)),
+    }
+}
+
+/// Which units fields are shown in, and what bare numbers typed into them mean
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum UnitSystem {
+    #[default]
+    Si,
+    /// Pounds, inches and degrees
+    Imperial,
+}
+
+impl UnitSystem {
+    pub const ALL: [Self; 2] = [Self::Si, Self::Imperial];
+
+    /// Writes an SI unit in this system, `kg*m^2` is `lbm*in^2` in imperial
+    pub fn get_display_unit(&self, si_unit: &str) -> String {
+        let replace = |name: &str| -> String {
+            match (self, name) {
+                (UnitSystem::Imperial, "kg") => "lbm".to_string(),
+                (UnitSystem::Imperial, "m") => "in".to_string(),
+                (UnitSystem::Imperial, "N") => "lbf".to_string(),
+                (UnitSystem::Imperial, "rad") => "deg".to_string(),
+                _ => name.to_string(),
+            }
+        };
+
+        let mut display = String::new();
+        let mut name = String::new();
+        for c in si_unit.chars() {
+            if c.is_alphanumeric() {
+                name.push(c);
+            } else {
+                display += &replace(&name);
+                display.push(c);
+                name.clear();
+            }
+        }
+        display + &replace(&name)
+    }
+}
+
+impl std::fmt::Display for UnitSystem {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            Self::Si => "SI",
+            Self::Imperial => "Imperial",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_units() {
+        let inertia = parse_unit("g*cm^2").unwrap();
+        assert_eq!(inertia.dimension, [1, 2, 0]);
+        assert!(
+            (inertia
+                .convert(1200.0, &parse_unit("kg*m^2").unwrap())
+                .unwrap()
+                - 1.2e-4)
+                .abs()
+                < 1e-15
+        );
+        assert_eq!(
+            parse_unit("N*m*s/rad").unwrap().dimension,
+            parse_unit("kg * m^2 / s").unwrap().dimension
+        );
+        assert_eq!(parse_unit("1/s").unwrap().dimension, [0, 0, -1]);
+        assert_eq!(
+            parse_unit("furlong"),
+            Err(UnitErrors::UnknownUnit("furlong".to_string()))
+        );
+        assert_eq!(
+            parse_unit("m^"),
+            Err(UnitErrors::InvalidUnit("m^".to_string()))
+        );
+        // powers too large for a dimension are rejected rather than overflowing
+        for text in ["N^2000000000", "m^-2147483648/m", "s/m^-2147483648", "m^2147483647*m"] {
+            assert_eq!(parse_unit(text), Err(UnitErrors::InvalidUnit(text.to_string())));
+        }
+        assert_eq!(
+            parse_unit("kg")
+                .unwrap()
+                .convert(1.0, &parse_unit("m").unwrap()),
+            None
+        );
+
+        let (expression, unit) = split_unit("m*L^2/3 lbm*in^2").unwrap();
+        assert_eq!(expression, "m*L^2/3");
+        assert_eq!(unit.unwrap().dimension, [1, 2, 0]);
+        assert_eq!(split_unit("2 * pi").unwrap(), ("2 * pi", None));
+        assert_eq!(split_unit("x + m").unwrap(), ("x + m", None));
+        assert_eq!(split_unit("30 deg").unwrap().1.unwrap().factor, PI / 180.0);
+        assert_eq!(
+            split_unit("2.5 lbs"),
+            Err(UnitErrors::UnknownUnit("lbs".to_string()))
+        );
+
+        assert_eq!(
+            UnitSystem::Imperial.get_display_unit("N*m*s/rad"),
+            "lbf*in*s/deg"
+        );
+        assert_eq!(UnitSystem::Si.get_display_unit("kg*m^2"), "kg*m^2");
+    }
+}